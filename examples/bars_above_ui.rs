@@ -0,0 +1,51 @@
+// A UI panel wider than the safe area. Its ends show over the letterboxes until space is pressed,
+// which moves the letterboxes above UI so they clip it.
+use bevy::prelude::*;
+use bevy_letterboxes::*;
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .insert_resource(ScreenUnits {
+            width: 16.0,
+            height: 9.0,
+        })
+        .add_plugin(LetterboxPlugin {
+            spawn_camera: true,
+            ..default()
+        })
+        .add_startup_system(setup_ui)
+        .add_system(toggle_layering)
+        .run();
+}
+
+fn setup_ui(mut commands: Commands) {
+    // UI needs its own camera in this version of Bevy.
+    commands.spawn_bundle(UiCameraBundle::default());
+
+    // Deliberately overflows the window on both sides.
+    commands.spawn_bundle(NodeBundle {
+        style: Style {
+            position_type: PositionType::Absolute,
+            position: Rect {
+                left: Val::Percent(-25.0),
+                bottom: Val::Percent(40.0),
+                ..default()
+            },
+            size: Size::new(Val::Percent(150.0), Val::Percent(20.0)),
+            ..default()
+        },
+        color: Color::rgb( 0.9, 0.5, 0.1 ).into(),
+        ..default()
+    });
+}
+
+fn toggle_layering(keys: Res<Input<KeyCode>>, mut layering: ResMut<BarLayering>) {
+    if keys.just_pressed(KeyCode::Space) {
+        *layering = match *layering {
+            BarLayering::BelowUi => BarLayering::AboveUi,
+            BarLayering::AboveUi => BarLayering::BelowUi,
+        };
+        info!("Letterboxes are now {:?}", *layering);
+    }
+}
@@ -0,0 +1,84 @@
+// A 3D cube rendered by a perspective camera, with the letterboxes drawn by the plugin's 2D camera.
+// The cube is larger than the safe area, and the letterboxes hide the parts which stick out. A 2D HUD
+// on the same camera shows both sides of LETTERBOX_Z: a strip the bars cut off and a marker above them.
+use bevy::prelude::*;
+use bevy_letterboxes::*;
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .insert_resource(ScreenUnits {
+            width: 20.0,
+            height: 15.0,
+        })
+        .add_plugin(LetterboxPlugin {
+            spawn_camera: true,
+            ..default()
+        })
+        .add_startup_system(setup_scene)
+        .add_system(spin_cube)
+        .add_system(report_status)
+        .run();
+}
+
+#[derive(Component)]
+struct SpinningCube;
+
+fn setup_scene(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands
+        .spawn_bundle(PbrBundle {
+            mesh: meshes.add(Mesh::from(shape::Cube { size: 3.0 })),
+            material: materials.add(Color::rgb(0.8, 0.3, 0.3).into()),
+            ..default()
+        })
+        .insert(SpinningCube);
+    commands.spawn_bundle(PointLightBundle {
+        transform: Transform::from_xyz(4.0, 8.0, 4.0),
+        ..default()
+    });
+    commands.spawn_bundle(PerspectiveCameraBundle {
+        transform: Transform::from_xyz(0.0, 0.0, 4.0).looking_at(Vec3::ZERO, Vec3::Y),
+        ..default()
+    });
+
+    // Below the bars: wider than the safe area, so its ends are hidden by the letterboxes.
+    commands
+        .spawn_bundle(SpriteBundle {
+            sprite: Sprite {
+                color: Color::rgba(1.0, 1.0, 1.0, 0.5),
+                custom_size: Some(Vec2::new(40.0, 0.5)),
+                ..default()
+            },
+            transform: Transform::from_xyz(0.0, 0.0, 10.0),
+            ..default()
+        })
+        .insert(SafeAnchor::percent(0.5, 0.9));
+    // Above the bars: straddles the left edge of the safe area and stays whole.
+    commands
+        .spawn_bundle(SpriteBundle {
+            sprite: Sprite {
+                color: Color::YELLOW,
+                custom_size: Some(Vec2::splat(1.0)),
+                ..default()
+            },
+            transform: Transform::from_xyz(0.0, 0.0, LETTERBOX_Z + 0.3),
+            ..default()
+        })
+        .insert(SafeAnchor::percent(0.0, 0.1));
+}
+
+fn report_status(status: Res<LetterboxStatus>) {
+    if status.is_changed() && !status.is_ok() {
+        warn!("Letterbox problems: {:?}", status.problems());
+    }
+}
+
+fn spin_cube(time: Res<Time>, mut cube_query: Query<&mut Transform, With<SpinningCube>>) {
+    for mut transform in cube_query.iter_mut() {
+        transform.rotate(Quat::from_rotation_y(time.delta_seconds()));
+    }
+}
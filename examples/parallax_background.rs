@@ -0,0 +1,75 @@
+// A parallax background scrolling across the whole window, through the bar area, while the gameplay
+// layer is clipped to the safe area by the scissor backend.
+use bevy::prelude::*;
+use bevy::render::view::RenderLayers;
+use bevy_letterboxes::*;
+
+// Layer the background is drawn on. The managed camera only sees layer 0.
+const BACKGROUND_LAYER: u8 = 1;
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .insert_resource(ScreenUnits {
+            width: 16.0,
+            height: 9.0,
+        })
+        .insert_resource(LetterboxBackend::Scissor)
+        .insert_resource(BackgroundLayers(Some(RenderLayers::layer(BACKGROUND_LAYER))))
+        .add_plugin(LetterboxPlugin {
+            spawn_camera: true,
+            ..default()
+        })
+        .add_startup_system(setup_scene)
+        .add_system(scroll_background)
+        .run();
+}
+
+#[derive(Component)]
+struct Parallax {
+    speed: f32,
+}
+
+fn setup_scene(mut commands: Commands) {
+    // Two rows of hills, the farther one slower, spaced past the widest window either way.
+    for (row, (color, height, speed)) in [(Color::rgb(0.3, 0.35, 0.5), 6.0, 0.5), (Color::rgb(0.2, 0.3, 0.35), 3.0, 1.5)]
+        .into_iter()
+        .enumerate()
+    {
+        for index in 0..16 {
+            commands
+                .spawn_bundle(SpriteBundle {
+                    sprite: Sprite {
+                        color,
+                        custom_size: Some(Vec2::new(3.0, height)),
+                        ..default()
+                    },
+                    transform: Transform::from_xyz(index as f32 * 4.0 - 32.0, height / 2.0 - 4.5, row as f32),
+                    ..default()
+                })
+                .insert(Parallax { speed })
+                .insert(RenderLayers::layer(BACKGROUND_LAYER));
+        }
+    }
+
+    // Gameplay, wider than the safe area, so its ends are clipped on wide windows.
+    commands.spawn_bundle(SpriteBundle {
+        sprite: Sprite {
+            color: Color::rgb(0.9, 0.6, 0.2),
+            custom_size: Some(Vec2::new(24.0, 1.0)),
+            ..default()
+        },
+        transform: Transform::from_xyz(0.0, -3.0, 10.0),
+        ..default()
+    });
+}
+
+fn scroll_background(time: Res<Time>, mut parallax_query: Query<(&Parallax, &mut Transform)>) {
+    for (parallax, mut transform) in parallax_query.iter_mut() {
+        transform.translation.x -= parallax.speed * time.delta_seconds();
+        // Wrap around the 64 units the hills are spread over.
+        if transform.translation.x < -32.0 {
+            transform.translation.x += 64.0;
+        }
+    }
+}
@@ -0,0 +1,67 @@
+// Snow drifting across the whole window, over the letterboxes, while a block in the middle of the
+// scene stays masked to the safe area.
+use bevy::prelude::*;
+use bevy_letterboxes::*;
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .insert_resource(ScreenUnits {
+            width: 20.0,
+            height: 15.0,
+        })
+        .add_plugin(LetterboxPlugin {
+            spawn_camera: true,
+            ..default()
+        })
+        .add_startup_system(setup_scene)
+        .add_system(drift_snow)
+        .run();
+}
+
+#[derive(Component)]
+struct Snowflake { speed: f32 }
+
+fn setup_scene(mut commands: Commands) {
+    // Wider than the safe area, so its ends are hidden by the letterboxes on wide windows.
+    commands.spawn_bundle(SpriteBundle {
+        sprite: Sprite {
+            color: Color::rgb( 0.2, 0.4, 0.8 ),
+            custom_size: Some(Vec2::new( 30.0, 4.0 )),
+            ..default()
+        },
+        ..default()
+    });
+
+    for index in 0..60 {
+        let x = (index as f32 * 7.3) % 40.0 - 20.0;
+        let y = (index as f32 * 3.1) % 20.0 - 10.0;
+        commands
+            .spawn_bundle(SpriteBundle {
+                sprite: Sprite {
+                    color: Color::WHITE,
+                    custom_size: Some(Vec2::splat( 0.2 )),
+                    ..default()
+                },
+                transform: Transform::from_xyz( x, y, 10.0 ),
+                ..default()
+            })
+            .insert(Snowflake { speed: 1.0 + (index % 5) as f32 * 0.3 })
+            .insert(AllowBleed);
+    }
+}
+
+fn drift_snow(time: Res<Time>, layout: Res<LetterboxLayout>, mut snow_query: Query<(&Snowflake, &mut Transform)>) {
+    let visible = layout.visible;
+    for (snowflake, mut transform) in snow_query.iter_mut() {
+        transform.translation.y -= snowflake.speed * time.delta_seconds();
+        transform.translation.x += 0.5 * time.delta_seconds();
+        // Wrap around the whole window, bars included.
+        if transform.translation.y < visible.min.y {
+            transform.translation.y = visible.max.y;
+        }
+        if transform.translation.x > visible.max.x {
+            transform.translation.x = visible.min.x;
+        }
+    }
+}
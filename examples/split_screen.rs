@@ -0,0 +1,75 @@
+// Two players sharing the window. Tab switches between side by side, stacked and a single view, and
+// the arrow keys move the players' cameras apart.
+use bevy::prelude::*;
+use bevy_letterboxes::*;
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .insert_resource(ScreenUnits {
+            width: 16.0,
+            height: 9.0,
+        })
+        .insert_resource(SplitLayout {
+            players: [
+                None,
+                Some(ScreenUnits {
+                    width: 8.0,
+                    height: 8.0,
+                }),
+            ],
+            ..SplitLayout::new(SplitDirection::SideBySide)
+        })
+        .add_plugin(LetterboxPlugin {
+            spawn_camera: true,
+            ..default()
+        })
+        .add_startup_system(setup_scene)
+        .add_system(cycle_split)
+        .add_system(move_players)
+        .run();
+}
+
+fn setup_scene(mut commands: Commands) {
+    // The player views are shown with UI, which needs its own camera in this version of Bevy.
+    commands.spawn_bundle(UiCameraBundle::default());
+
+    for (x, color) in [(-4.0, Color::rgb( 0.9, 0.3, 0.2 )), (4.0, Color::rgb( 0.2, 0.5, 0.9 ))] {
+        commands.spawn_bundle(SpriteBundle {
+            sprite: Sprite {
+                color,
+                custom_size: Some(Vec2::splat(2.0)),
+                ..default()
+            },
+            transform: Transform::from_translation(Vec3::new( x, 0.0, 0.0 )),
+            ..default()
+        });
+    }
+}
+
+fn cycle_split(keys: Res<Input<KeyCode>>, mut split: ResMut<SplitLayout>) {
+    if keys.just_pressed(KeyCode::Tab) {
+        split.direction = match split.direction {
+            Some(SplitDirection::SideBySide) => Some(SplitDirection::Stacked),
+            Some(SplitDirection::Stacked) => None,
+            None => Some(SplitDirection::SideBySide),
+        };
+        info!("Split is now {:?}", split.direction);
+    }
+}
+
+fn move_players(
+    time: Res<Time>,
+    keys: Res<Input<KeyCode>>,
+    mut camera_query: Query<(&SplitPlayerCamera, &mut Transform)>,
+) {
+    let direction = match (keys.pressed(KeyCode::Left), keys.pressed(KeyCode::Right)) {
+        (true, false) => -1.0,
+        (false, true) => 1.0,
+        _ => return,
+    };
+    for (player, mut transform) in camera_query.iter_mut() {
+        let away = if player.player == 0 { -1.0 } else { 1.0 };
+        transform.translation.x += direction * away * 4.0 * time.delta_seconds();
+    }
+}
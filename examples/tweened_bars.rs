@@ -0,0 +1,63 @@
+// Cinematic bars driven by bevy_tweening: the bars slide in to a widescreen framing and then fade
+// from black to a deep red. Clicking a letterbox skips to the end. Run with --features tweening.
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy_letterboxes::*;
+use bevy_tweening::*;
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugin(TweeningPlugin)
+        .insert_resource(ScreenUnits {
+            width: 20.0,
+            height: 15.0,
+        })
+        .add_plugin(LetterboxPlugin {
+            spawn_camera: true,
+            ..default()
+        })
+        .add_startup_system(start_tweens)
+        .add_system(skip_on_bar_click)
+        .run();
+}
+
+fn start_tweens(mut commands: Commands) {
+    let widescreen = Tween::new(
+        EaseFunction::QuadraticInOut,
+        TweeningType::Once,
+        Duration::from_secs_f32(1.5),
+        CinematicFractionLens { start: 0.0, end: 0.12 },
+    );
+    let fade = Tween::new(
+        EaseFunction::QuadraticInOut,
+        TweeningType::Once,
+        Duration::from_secs(2),
+        CinematicColorLens {
+            start: Color::BLACK,
+            end: Color::rgb(0.3, 0.0, 0.0),
+        },
+    );
+
+    commands
+        .spawn()
+        .insert(LetterboxTweenTarget::default())
+        .insert(Animator::new(widescreen.then(fade)));
+}
+
+fn skip_on_bar_click(
+    mut commands: Commands,
+    mut clicks: EventReader<BarClicked>,
+    mut cinematic_bars: ResMut<CinematicBars>,
+    animator_query: Query<Entity, With<Animator<LetterboxTweenTarget>>>,
+) {
+    if clicks.iter().last().is_none() {
+        return;
+    }
+    for entity in animator_query.iter() {
+        commands.entity(entity).despawn();
+    }
+    cinematic_bars.fraction = 0.12;
+    cinematic_bars.color = Color::rgb(0.3, 0.0, 0.0);
+}
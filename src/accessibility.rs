@@ -0,0 +1,84 @@
+use bevy::prelude::*;
+
+use crate::backend::ui_rect_style;
+use crate::{LetterboxLayout, LetterboxRect, LetterboxSide, ManagedCamera};
+
+// Resource which makes every letterbox animation apply instantly, for players who get motion sick.
+// Completion events are still sent in the same order, and animations already running snap ahead
+// as soon as it's turned on.
+#[derive(Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ReduceMotion(pub bool);
+
+// Resource for a high contrast outline around the safe area for accessibility, separate from the
+// development only SafeAreaBorder. It's drawn with bevy_ui so it shows above gameplay, the letterboxes
+// and other UI spawned before it, and it needs a UI camera, which only the render texture backend
+// spawns. The outline sits just outside the safe area, so screenshots cropped to SafeAreaPx leave it
+// out, unless the letterboxes are thinner than it and it has to overlap the safe area to stay in the window.
+pub struct SafeAreaIndicator {
+    pub enabled: bool,
+    pub color: Color,
+    // Thickness in physical pixels, the same at any resolution or scale.
+    pub thickness: f32,
+}
+
+impl Default for SafeAreaIndicator {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            color: Color::YELLOW,
+            thickness: 4.0,
+        }
+    }
+}
+
+// Component for the UI nodes which make up the safe area indicator.
+#[derive(Component)]
+pub struct SafeAreaIndicatorEdge { pub side: LetterboxSide }
+
+pub(crate) fn spawn_safe_area_indicator(mut commands: Commands) {
+    for side in LetterboxSide::ALL {
+        commands
+            .spawn_bundle(NodeBundle {
+                visibility: Visibility { is_visible: false },
+                ..default()
+            })
+            .insert(SafeAreaIndicatorEdge { side });
+    }
+}
+
+pub(crate) fn update_safe_area_indicator(
+    indicator: Res<SafeAreaIndicator>,
+    layout: Res<LetterboxLayout>,
+    managed_camera: Res<ManagedCamera>,
+    windows: Res<Windows>,
+    mut edge_query: Query<(&SafeAreaIndicatorEdge, &mut Style, &mut UiColor, &mut Visibility)>,
+) {
+    let scale_factor = windows.get(managed_camera.window).map_or(1.0, |window| window.scale_factor() as f32);
+    // UI is laid out in logical pixels.
+    let window = LetterboxRect::new(Vec2::ZERO, layout.window_size / layout.dpi_scale);
+    let safe = layout.safe_area_px.scaled(1.0 / layout.dpi_scale).intersect(window);
+    let thickness = (indicator.thickness / scale_factor).min(window.size().min_element() / 2.0).max(0.0);
+    let outline = LetterboxRect::new(safe.min - Vec2::splat(thickness), safe.max + Vec2::splat(thickness));
+
+    for (edge, mut style, mut color, mut visibility) in edge_query.iter_mut() {
+        let rect = outline.frame_side(safe, edge.side);
+        // Move edges which would leave the window back inside it.
+        let shift = (window.min - rect.min).max(Vec2::ZERO) + (window.max - rect.max).min(Vec2::ZERO);
+        let rect = LetterboxRect::new(rect.min + shift, rect.max + shift);
+
+        // Thin outlines on high DPI displays are under a logical pixel but still drawn.
+        let new_style = Style {
+            display: Display::Flex,
+            ..ui_rect_style(rect)
+        };
+        if *style != new_style {
+            *style = new_style;
+        }
+        if color.0 != indicator.color {
+            color.0 = indicator.color;
+        }
+        if visibility.is_visible != indicator.enabled {
+            visibility.is_visible = indicator.enabled;
+        }
+    }
+}
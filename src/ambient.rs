@@ -0,0 +1,508 @@
+use bevy::core_pipeline::node::MAIN_PASS_DRIVER;
+use bevy::prelude::*;
+use bevy::reflect::TypeUuid;
+use bevy::render::camera::RenderTarget;
+use bevy::render::render_asset::RenderAssets;
+use bevy::render::render_graph::{Node, NodeRunError, RenderGraph, RenderGraphContext};
+use bevy::render::render_phase::TrackedRenderPass;
+use bevy::render::render_resource::std140::AsStd140;
+use bevy::render::render_resource::*;
+use bevy::render::renderer::{RenderContext, RenderDevice, RenderQueue};
+use bevy::render::{RenderApp, RenderStage};
+use bevy::ui::node::UI_PASS_DRIVER;
+
+use crate::backend::ui_bar_color;
+use crate::{
+    LetterboxBackend, LetterboxLayout, LetterboxSide, LetterboxStyle, ManagedCamera, RenderTextureSettings,
+    ScreenUnits, TransparentLetterboxes, UiLetterbox,
+};
+
+const AMBIENT_BARS_SHADER_HANDLE: HandleUntyped =
+    HandleUntyped::weak_from_u64(Shader::TYPE_UUID, 5830871146283557121);
+
+// Render graph node tinting the ambient bars. It runs after the main passes, once the render texture
+// has been drawn, and before bevy_ui shows it.
+pub const AMBIENT_BARS_PASS: &str = "letterbox_ambient_bars_pass";
+
+// Resource for tinting the render texture backend's letterboxes toward the colors along the nearby
+// edges of the picture, like a TV's ambient light. Every frame a render graph node averages a strip
+// along each edge of the rendered image into a one pixel image which the bar shows, so nothing is read
+// back from the GPU and the strips follow a resize in the same frame. Only the render texture backend
+// is sampled, since Bevy 0.7 creates window surfaces for rendering into only. The other backends, and
+// apps without a renderer, keep the style's colors.
+pub struct AmbientBars {
+    pub enabled: bool,
+    // How far each bar moves from the style's color toward the sampled one, from 0 to 1.
+    pub intensity: f32,
+    // Seconds for a bar to cover most of the way to a newly sampled color. Zero follows it immediately.
+    pub smoothing: f32,
+    // Depth of the strip sampled along each edge of the safe area, in world units.
+    pub edge_depth: f32,
+    // The images the bars show, in the order of LetterboxSide::ALL, once the bars were first tinted.
+    images: Option<[Handle<Image>; 4]>,
+}
+
+impl Default for AmbientBars {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            intensity: 0.6,
+            smoothing: 0.25,
+            edge_depth: 0.5,
+            images: None,
+        }
+    }
+}
+
+impl AmbientBars {
+    fn image_for(&self, side: LetterboxSide) -> Option<Handle<Image>> {
+        let index = LetterboxSide::ALL.iter().position(|each| *each == side)?;
+        self.images.as_ref().map(|images| images[index].clone())
+    }
+}
+
+// The strip sampled for each side, in the order of LetterboxSide::ALL, as the left, top, right and bottom
+// edges in texture coordinates of the rendered image. Strips never reach past the middle of the picture.
+pub(crate) fn edge_strips(settings: &RenderTextureSettings, layout: &LetterboxLayout, edge_depth: f32) -> [Vec4; 4] {
+    let safe_size = layout.safe_area.size();
+    let image_layout = settings.image_layout(&ScreenUnits {
+        width: safe_size.x,
+        height: safe_size.y,
+    });
+    let safe = image_layout.safe_area_px;
+    let depth = Vec2::splat(edge_depth.max(0.0) * image_layout.pixels_per_unit).min(safe.size() / 2.0);
+    let image_size = settings.size();
+    LetterboxSide::ALL.map(|side| {
+        let (min, max) = match side {
+            LetterboxSide::Left => (safe.min, Vec2::new(safe.min.x + depth.x, safe.max.y)),
+            LetterboxSide::Right => (Vec2::new(safe.max.x - depth.x, safe.min.y), safe.max),
+            LetterboxSide::Top => (Vec2::new(safe.min.x, safe.max.y - depth.y), safe.max),
+            LetterboxSide::Bottom => (safe.min, Vec2::new(safe.max.x, safe.min.y + depth.y)),
+        };
+        // Layout pixels count up from the bottom, texture coordinates down from the top.
+        Vec4::new(
+            min.x / image_size.x,
+            1.0 - max.y / image_size.y,
+            max.x / image_size.x,
+            1.0 - min.y / image_size.y,
+        )
+    })
+}
+
+pub(crate) fn add_ambient_bars(app: &mut App) {
+    app.init_resource::<AmbientBars>()
+        .add_system(update_ambient_bars.after(crate::backend::update_render_texture_backend));
+
+    // Headless apps have no renderer.
+    if app.get_sub_app(RenderApp).is_err() {
+        return;
+    }
+    app.world
+        .resource_mut::<Assets<Shader>>()
+        .set_untracked(AMBIENT_BARS_SHADER_HANDLE, Shader::from_wgsl(include_str!("ambient_bars.wgsl")));
+    let render_app = app.sub_app_mut(RenderApp);
+    render_app
+        .init_resource::<AmbientBarsPipeline>()
+        .init_resource::<AmbientBarUniforms>()
+        .add_system_to_stage(RenderStage::Extract, extract_ambient_bars)
+        .add_system_to_stage(RenderStage::Prepare, prepare_ambient_bars)
+        .add_system_to_stage(RenderStage::Queue, queue_ambient_bars_bind_group);
+    let pass_node = AmbientBarsPassNode;
+    let mut graph = render_app.world.resource_mut::<RenderGraph>();
+    graph.add_node(AMBIENT_BARS_PASS, pass_node);
+    graph.add_node_edge(MAIN_PASS_DRIVER, AMBIENT_BARS_PASS).unwrap();
+    if graph.get_node_id(UI_PASS_DRIVER).is_ok() {
+        graph.add_node_edge(AMBIENT_BARS_PASS, UI_PASS_DRIVER).unwrap();
+    }
+}
+
+// A one pixel image starting out in the style's color, so a bar looks the same as without ambient
+// colors until the first strip has been sampled.
+fn ambient_image(color: Color) -> Image {
+    let mut image = Image::new_fill(
+        Extent3d {
+            width: 1,
+            height: 1,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &color.as_rgba_f32().map(|channel| (channel.clamp(0.0, 1.0) * 255.0).round() as u8),
+        TextureFormat::Rgba8UnormSrgb,
+    );
+    image.texture_descriptor.usage =
+        TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST | TextureUsages::RENDER_ATTACHMENT;
+    image
+}
+
+fn ambient_bars_active(
+    ambient: &AmbientBars,
+    backend: &LetterboxBackend,
+    transparency: &TransparentLetterboxes,
+) -> bool {
+    // Transparent letterboxes are holes, which have no color to tint.
+    ambient.enabled && matches!(backend, LetterboxBackend::RenderTexture(_)) && !transparency.enabled
+}
+
+// Shows the ambient images on the UI letterboxes while the bars are tinted, and the style's colors
+// otherwise. The images are tinted on the GPU, so the nodes themselves are white.
+pub(crate) fn update_ambient_bars(
+    backend: Res<LetterboxBackend>,
+    style: Res<LetterboxStyle>,
+    transparency: Res<TransparentLetterboxes>,
+    mut ambient: ResMut<AmbientBars>,
+    mut images: ResMut<Assets<Image>>,
+    mut bar_query: Query<(&UiLetterbox, &mut UiImage, &mut UiColor)>,
+) {
+    let active = ambient_bars_active(&ambient, &backend, &transparency);
+    if active && ambient.images.is_none() {
+        ambient.images = Some(LetterboxSide::ALL.map(|side| images.add(ambient_image(style.color_for(side)))));
+    }
+
+    for (bar, mut image, mut color) in bar_query.iter_mut() {
+        let (new_image, new_color) = match ambient.image_for(bar.side) {
+            Some(ambient_image) if active => (ambient_image, Color::WHITE),
+            _ => (UiImage::default().0, ui_bar_color(&style, &transparency, bar.side)),
+        };
+        if image.0 != new_image {
+            image.0 = new_image;
+        }
+        if color.0 != new_color {
+            color.0 = new_color;
+        }
+    }
+}
+
+#[derive(Clone, AsStd140)]
+struct AmbientBarUniform {
+    // The strip to sample, as in edge_strips.
+    strip: Vec4,
+    // The style's color in linear space.
+    base: Vec4,
+    intensity: f32,
+}
+
+// What the pass draws this frame, if the bars are tinted.
+struct ExtractedAmbientBars {
+    source: Handle<Image>,
+    targets: [Handle<Image>; 4],
+    uniforms: [AmbientBarUniform; 4],
+    // How much of the new color is blended over the last one.
+    blend: f32,
+}
+
+#[derive(Default)]
+struct AmbientBarUniforms {
+    uniforms: DynamicUniformVec<AmbientBarUniform>,
+    offsets: Vec<u32>,
+    bind_group: Option<BindGroup>,
+}
+
+struct AmbientBarsPipeline {
+    layout: BindGroupLayout,
+    sampler: Sampler,
+    pipeline: CachedRenderPipelineId,
+}
+
+impl FromWorld for AmbientBarsPipeline {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+        let layout = render_device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            entries: &[
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Uniform,
+                        has_dynamic_offset: true,
+                        min_binding_size: BufferSize::new(AmbientBarUniform::std140_size_static() as u64),
+                    },
+                    count: None,
+                },
+                BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Texture {
+                        sample_type: TextureSampleType::Float { filterable: true },
+                        view_dimension: TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Sampler(SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+            label: Some("ambient_bars_layout"),
+        });
+        // Each sample averages the texels around it, so a few samples cover a strip.
+        let sampler = render_device.create_sampler(&SamplerDescriptor {
+            mag_filter: FilterMode::Linear,
+            min_filter: FilterMode::Linear,
+            ..default()
+        });
+        // The new color is blended over the last one by the blend constant, for the smoothing over time.
+        let blend = BlendComponent {
+            src_factor: BlendFactor::Constant,
+            dst_factor: BlendFactor::OneMinusConstant,
+            operation: BlendOperation::Add,
+        };
+        let descriptor = RenderPipelineDescriptor {
+            vertex: VertexState {
+                shader: AMBIENT_BARS_SHADER_HANDLE.typed::<Shader>(),
+                entry_point: "vertex".into(),
+                shader_defs: Vec::new(),
+                buffers: Vec::new(),
+            },
+            fragment: Some(FragmentState {
+                shader: AMBIENT_BARS_SHADER_HANDLE.typed::<Shader>(),
+                shader_defs: Vec::new(),
+                entry_point: "fragment".into(),
+                targets: vec![ColorTargetState {
+                    format: TextureFormat::Rgba8UnormSrgb,
+                    blend: Some(BlendState {
+                        color: blend,
+                        alpha: blend,
+                    }),
+                    write_mask: ColorWrites::ALL,
+                }],
+            }),
+            layout: Some(vec![layout.clone()]),
+            primitive: PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: MultisampleState::default(),
+            label: Some("ambient_bars_pipeline".into()),
+        };
+        let pipeline = world.resource_mut::<PipelineCache>().queue_render_pipeline(descriptor);
+        Self {
+            layout,
+            sampler,
+            pipeline,
+        }
+    }
+}
+
+fn extract_ambient_bars(
+    mut commands: Commands,
+    ambient: Res<AmbientBars>,
+    backend: Res<LetterboxBackend>,
+    transparency: Res<TransparentLetterboxes>,
+    layout: Res<LetterboxLayout>,
+    style: Res<LetterboxStyle>,
+    time: Res<Time>,
+    managed_camera: Res<ManagedCamera>,
+    camera_query: Query<&Camera>,
+) {
+    let settings = match *backend {
+        LetterboxBackend::RenderTexture(settings) if ambient_bars_active(&ambient, &backend, &transparency) => settings,
+        _ => return commands.remove_resource::<ExtractedAmbientBars>(),
+    };
+    let source = managed_camera.entity.and_then(|entity| camera_query.get(entity).ok()).and_then(|camera| {
+        match &camera.target {
+            RenderTarget::Image(image) => Some(image.clone()),
+            RenderTarget::Window(_) => None,
+        }
+    });
+    let (source, targets) = match (source, &ambient.images) {
+        (Some(source), Some(targets)) if layout.ready => (source, targets.clone()),
+        _ => return commands.remove_resource::<ExtractedAmbientBars>(),
+    };
+
+    let strips = edge_strips(&settings, &layout, ambient.edge_depth);
+    let intensity = ambient.intensity.clamp(0.0, 1.0);
+    let uniforms = [0, 1, 2, 3].map(|index| AmbientBarUniform {
+        strip: strips[index],
+        base: Vec4::from(style.color_for(LetterboxSide::ALL[index]).as_linear_rgba_f32()),
+        intensity,
+    });
+    let blend = if ambient.smoothing > 0.0 {
+        1.0 - (-time.delta_seconds() / ambient.smoothing).exp()
+    } else {
+        1.0
+    };
+    commands.insert_resource(ExtractedAmbientBars {
+        source,
+        targets,
+        uniforms,
+        blend,
+    });
+}
+
+fn prepare_ambient_bars(
+    render_device: Res<RenderDevice>,
+    render_queue: Res<RenderQueue>,
+    extracted: Option<Res<ExtractedAmbientBars>>,
+    mut uniforms: ResMut<AmbientBarUniforms>,
+) {
+    uniforms.uniforms.clear();
+    uniforms.offsets.clear();
+    if let Some(extracted) = extracted {
+        for uniform in &extracted.uniforms {
+            let offset = uniforms.uniforms.push(uniform.clone());
+            uniforms.offsets.push(offset);
+        }
+    }
+    uniforms.uniforms.write_buffer(&render_device, &render_queue);
+}
+
+fn queue_ambient_bars_bind_group(
+    render_device: Res<RenderDevice>,
+    pipeline: Res<AmbientBarsPipeline>,
+    images: Res<RenderAssets<Image>>,
+    extracted: Option<Res<ExtractedAmbientBars>>,
+    mut uniforms: ResMut<AmbientBarUniforms>,
+) {
+    let uniforms = &mut *uniforms;
+    // The render texture is only there once its image was prepared, a frame after it was added.
+    let source = extracted.and_then(|extracted| images.get(&extracted.source));
+    uniforms.bind_group = match (uniforms.uniforms.binding(), source) {
+        (Some(binding), Some(source)) => Some(render_device.create_bind_group(&BindGroupDescriptor {
+            entries: &[
+                BindGroupEntry { binding: 0, resource: binding },
+                BindGroupEntry {
+                    binding: 1,
+                    resource: BindingResource::TextureView(&source.texture_view),
+                },
+                BindGroupEntry {
+                    binding: 2,
+                    resource: BindingResource::Sampler(&pipeline.sampler),
+                },
+            ],
+            label: Some("ambient_bars_bind_group"),
+            layout: &pipeline.layout,
+        })),
+        _ => None,
+    };
+}
+
+struct AmbientBarsPassNode;
+
+impl Node for AmbientBarsPassNode {
+    fn run(
+        &self,
+        _graph: &mut RenderGraphContext,
+        render_context: &mut RenderContext,
+        world: &World,
+    ) -> Result<(), NodeRunError> {
+        let extracted = match world.get_resource::<ExtractedAmbientBars>() {
+            Some(extracted) => extracted,
+            None => return Ok(()),
+        };
+        let uniforms = world.resource::<AmbientBarUniforms>();
+        let pipeline_id = world.resource::<AmbientBarsPipeline>().pipeline;
+        // Nothing is drawn until the pipeline has compiled, and the bars keep their last color meanwhile.
+        let pipeline = world.resource::<PipelineCache>().get_render_pipeline(pipeline_id);
+        let (pipeline, bind_group) = match (pipeline, &uniforms.bind_group) {
+            (Some(pipeline), Some(bind_group)) => (pipeline, bind_group),
+            _ => return Ok(()),
+        };
+        let images = world.resource::<RenderAssets<Image>>();
+
+        for (target, offset) in extracted.targets.iter().zip(&uniforms.offsets) {
+            let target = match images.get(target) {
+                Some(target) => target,
+                None => continue,
+            };
+            let pass_descriptor = RenderPassDescriptor {
+                label: Some("letterbox_ambient_bars_pass"),
+                color_attachments: &[RenderPassColorAttachment {
+                    view: &target.texture_view,
+                    resolve_target: None,
+                    ops: Operations {
+                        load: LoadOp::Load,
+                        store: true,
+                    },
+                }],
+                depth_stencil_attachment: None,
+            };
+            let render_pass = render_context.command_encoder.begin_render_pass(&pass_descriptor);
+            let mut pass = TrackedRenderPass::new(render_pass);
+            pass.set_render_pipeline(pipeline);
+            pass.set_bind_group(0, bind_group, &[*offset]);
+            let blend = extracted.blend;
+            pass.set_blend_constant(Color::rgba_linear(blend, blend, blend, blend));
+            pass.draw(0..3, 0..1);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use crate::{compute_letterbox_layout, LetterboxPlugin, PlatformInsets};
+
+    #[test]
+    fn samples_strips_along_the_edges_of_the_picture() {
+        let screen_units = ScreenUnits {
+            width: 16.0,
+            height: 9.0,
+        };
+        let layout = compute_letterbox_layout(Vec2::new(2000.0, 900.0), &screen_units, &PlatformInsets::default());
+        // A 320x180 image shows the safe area at 20 pixels per unit, so a strip of one unit is 20 pixels.
+        let strips = edge_strips(&RenderTextureSettings::new(320, 180), &layout, 1.0);
+        let expected = [
+            Vec4::new(0.0, 0.0, 0.0625, 1.0),
+            Vec4::new(0.9375, 0.0, 1.0, 1.0),
+            Vec4::new(0.0, 0.0, 1.0, 1.0 / 9.0),
+            Vec4::new(0.0, 8.0 / 9.0, 1.0, 1.0),
+        ];
+        for (side, (strip, expected)) in LetterboxSide::ALL.iter().zip(strips.iter().zip(expected)) {
+            assert!(strip.abs_diff_eq(expected, 1e-5), "{:?}: {:?}", side, strip);
+        }
+
+        // A 4:3 image has bars of its own above and below the picture, which aren't sampled.
+        let strips = edge_strips(&RenderTextureSettings::new(320, 240), &layout, 1.0);
+        assert!(strips[0].abs_diff_eq(Vec4::new(0.0, 0.125, 0.0625, 0.875), 1e-5), "{:?}", strips[0]);
+        assert!(strips[2].abs_diff_eq(Vec4::new(0.0, 0.125, 1.0, 0.125 + 20.0 / 240.0), 1e-5), "{:?}", strips[2]);
+
+        // Strips stop at the middle of the picture.
+        let strips = edge_strips(&RenderTextureSettings::new(320, 180), &layout, 100.0);
+        assert!(strips[0].abs_diff_eq(Vec4::new(0.0, 0.0, 0.5, 1.0), 1e-5), "{:?}", strips[0]);
+        assert!(strips[3].abs_diff_eq(Vec4::new(0.0, 0.5, 1.0, 1.0), 1e-5), "{:?}", strips[3]);
+    }
+
+    #[test]
+    fn shows_the_ambient_images_only_while_tinting() {
+        let mut app = LetterboxTestApp::new()
+            .plugin(LetterboxPlugin {
+                spawn_camera: true,
+                ..default()
+            })
+            .window(2000.0, 900.0, 1.0)
+            .build();
+        let ui_bars = |app: &mut App| {
+            let mut query = app.world.query::<(&UiLetterbox, &UiImage, &UiColor)>();
+            query
+                .iter(&app.world)
+                .map(|(bar, image, color)| (bar.side, image.0.clone(), color.0))
+                .collect::<Vec<_>>()
+        };
+        app.world.resource_mut::<AmbientBars>().enabled = true;
+        app.insert_resource(LetterboxBackend::RenderTexture(RenderTextureSettings::new(320, 180)));
+        app.update();
+        app.update();
+
+        let bars = ui_bars(&mut app);
+        assert_eq!(bars.len(), 4);
+        for (side, image, color) in bars {
+            assert_eq!(Some(image.clone()), app.world.resource::<AmbientBars>().image_for(side));
+            assert_eq!(color, Color::WHITE);
+            // Until the GPU has sampled the picture the bars show the style's color.
+            let pixel = &app.world.resource::<Assets<Image>>().get(&image).unwrap().data;
+            let style_color = app.world.resource::<LetterboxStyle>().color_for(side);
+            let expected = style_color.as_rgba_f32().map(|channel| (channel * 255.0).round() as u8);
+            assert_eq!(pixel.as_slice(), expected.as_slice());
+        }
+
+        app.world.resource_mut::<AmbientBars>().enabled = false;
+        app.update();
+        for (side, image, color) in ui_bars(&mut app) {
+            assert_eq!(image, UiImage::default().0);
+            assert_eq!(color, app.world.resource::<LetterboxStyle>().color_for(side));
+        }
+    }
+}
@@ -0,0 +1,37 @@
+// Averages a strip along one edge of the rendered image into a bar's one pixel image, moved from the
+// style's color toward the sampled one by the intensity. The pipeline blends it over the last color.
+struct AmbientBar {
+    // The strip's left, top, right and bottom edges in texture coordinates.
+    strip: vec4<f32>;
+    // The style's color in linear space.
+    base: vec4<f32>;
+    intensity: f32;
+};
+
+[[group(0), binding(0)]]
+var<uniform> bar: AmbientBar;
+[[group(0), binding(1)]]
+var source: texture_2d<f32>;
+[[group(0), binding(2)]]
+var source_sampler: sampler;
+
+[[stage(vertex)]]
+fn vertex([[builtin(vertex_index)]] index: u32) -> [[builtin(position)]] vec4<f32> {
+    let uv = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+    return vec4<f32>(uv * 2.0 - 1.0, 0.0, 1.0);
+}
+
+[[stage(fragment)]]
+fn fragment([[builtin(position)]] position: vec4<f32>) -> [[location(0)]] vec4<f32> {
+    // An 8x8 grid of filtered samples, each averaging the texels around it.
+    var sum = vec3<f32>(0.0, 0.0, 0.0);
+    for (var x: i32 = 0; x < 8; x = x + 1) {
+        for (var y: i32 = 0; y < 8; y = y + 1) {
+            let t = (vec2<f32>(f32(x), f32(y)) + 0.5) / 8.0;
+            let uv = mix(bar.strip.xy, bar.strip.zw, t);
+            sum = sum + textureSampleLevel(source, source_sampler, uv, 0.0).rgb;
+        }
+    }
+    let sampled = sum / 64.0;
+    return vec4<f32>(mix(bar.base.rgb, sampled, bar.intensity), bar.base.a);
+}
@@ -0,0 +1,68 @@
+use bevy::prelude::*;
+
+use crate::{LetterboxLayout, ManagedCamera};
+
+// A position relative to the safe area, where (0, 0) is its bottom left and (1, 1) its top right.
+// Values outside 0 to 1 extrapolate linearly, so (-0.1, 0.5) is a tenth of the safe area's width
+// left of it. As a component it keeps world entities (through Transform) and UI nodes (through
+// Style) at that position across resizes and ScreenUnits changes.
+#[derive(Component, Default, Clone, Copy, PartialEq, Debug)]
+pub struct SafeAnchor {
+    pub fraction: Vec2,
+}
+
+impl SafeAnchor {
+    pub fn percent(x: f32, y: f32) -> Self {
+        Self { fraction: Vec2::new(x, y) }
+    }
+
+    // The anchored position in world units, relative to the camera.
+    pub fn camera_position(&self, layout: &LetterboxLayout) -> Vec2 {
+        layout.safe_area.min + layout.safe_area.size() * self.fraction
+    }
+
+    // The anchored position in logical pixels from the bottom left of the window.
+    pub fn window_position(&self, layout: &LetterboxLayout) -> Vec2 {
+        (layout.safe_area_px.min + layout.safe_area_px.size() * self.fraction) / layout.dpi_scale
+    }
+
+    // Absolute UI position offsets which put a node's bottom left corner at the anchored position.
+    pub fn ui_position(&self, layout: &LetterboxLayout) -> Rect<Val> {
+        let position = self.window_position(layout);
+        Rect {
+            left: Val::Px(position.x),
+            bottom: Val::Px(position.y),
+            ..default()
+        }
+    }
+}
+
+pub(crate) fn apply_safe_anchors(
+    layout: Res<LetterboxLayout>,
+    managed_camera: Res<ManagedCamera>,
+    camera_query: Query<&GlobalTransform>,
+    mut world_query: Query<(&SafeAnchor, &mut Transform), Without<Node>>,
+    mut ui_query: Query<(&SafeAnchor, &mut Style)>,
+) {
+    // World positions follow the camera, so anchored entities stay put on screen as it moves.
+    let camera_position = managed_camera
+        .entity
+        .and_then(|entity| camera_query.get(entity).ok())
+        .map_or(Vec2::ZERO, |transform| transform.translation.truncate());
+
+    // Only write when something moved so change detection on anchored entities stays meaningful.
+    for (anchor, mut transform) in world_query.iter_mut() {
+        let position = camera_position + anchor.camera_position(&layout);
+        if transform.translation.truncate() != position {
+            transform.translation.x = position.x;
+            transform.translation.y = position.y;
+        }
+    }
+    for (anchor, mut style) in ui_query.iter_mut() {
+        let position = anchor.ui_position(&layout);
+        if style.position_type != PositionType::Absolute || style.position != position {
+            style.position_type = PositionType::Absolute;
+            style.position = position;
+        }
+    }
+}
@@ -0,0 +1,237 @@
+use bevy::prelude::*;
+use bevy::render::camera::{RenderTarget, ScalingMode};
+
+use crate::backend::render_target_image;
+use crate::camera::{add_extra_camera, extra_camera_bundle};
+use crate::{compute_fill_layout, FillMode, LayoutInputs, LetterboxRect, LetterboxStyle, ManagedCamera, PlatformInsets};
+
+// Window aspect ratios the preview shows, as width and height.
+pub const PREVIEW_ASPECT_RATIOS: [(f32, f32); 5] = [
+    (4.0, 3.0),
+    (16.0, 10.0),
+    (16.0, 9.0),
+    (19.5, 9.0),
+    (21.0, 9.0),
+];
+
+// Marker for the camera rendering the thumbnails.
+#[derive(Component, Default)]
+pub(crate) struct AspectPreviewCamera;
+
+// Resource for a strip of thumbnails along the bottom of the window showing how the scene frames in
+// windows of each of PREVIEW_ASPECT_RATIOS, with the current ScreenUnits, fill mode and bar color. It
+// needs a UI camera. Thumbnails are only rendered when the preview is enabled or refreshed, one per
+// frame through a second camera placed like the managed one, so leaving the strip up costs nothing.
+// Everything the managed camera would see is drawn, including this window's own letterboxes where
+// they overlap a thumbnail's framed area.
+pub struct AspectPreview {
+    pub enabled: bool,
+    // Height of each thumbnail in logical pixels.
+    pub thumbnail_height: f32,
+    // Font for the labels, which are left out without one.
+    pub font: Option<Handle<Font>>,
+    refresh_requested: bool,
+}
+
+impl Default for AspectPreview {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            thumbnail_height: 90.0,
+            font: None,
+            refresh_requested: false,
+        }
+    }
+}
+
+impl AspectPreview {
+    // Renders the thumbnails again, e.g. after moving the camera or changing the scene.
+    pub fn refresh(&mut self) {
+        self.refresh_requested = true;
+    }
+}
+
+// Component for identifying the UI node holding the thumbnails.
+#[derive(Component)]
+pub struct AspectPreviewStrip;
+
+#[derive(Default)]
+pub(crate) struct AspectPreviewState {
+    strip: Option<Entity>,
+    camera: Option<Entity>,
+    thumbnails: Vec<Thumbnail>,
+    // Thumbnails still to be rendered, in order.
+    pending: Vec<usize>,
+}
+
+struct Thumbnail {
+    image: Handle<Image>,
+    // The framed area of the simulated window, which is all the camera renders.
+    framed: LetterboxRect,
+}
+
+pub(crate) fn update_aspect_preview(
+    mut commands: Commands,
+    mut preview: ResMut<AspectPreview>,
+    inputs: LayoutInputs,
+    fill_mode: Res<FillMode>,
+    style: Res<LetterboxStyle>,
+    managed_camera: Res<ManagedCamera>,
+    windows: Res<Windows>,
+    mut images: ResMut<Assets<Image>>,
+    camera_query: Query<&GlobalTransform>,
+    mut preview_camera_query: Query<(&mut Camera, &mut OrthographicProjection, &mut Transform), With<AspectPreviewCamera>>,
+    mut state: Local<AspectPreviewState>,
+) {
+    let rebuild = preview.enabled && (state.strip.is_none() || preview.refresh_requested);
+    if !preview.enabled || rebuild {
+        for entity in state.strip.take().into_iter().chain(state.camera.take()) {
+            commands.entity(entity).despawn_recursive();
+        }
+        for thumbnail in state.thumbnails.drain(..) {
+            images.remove(&thumbnail.image);
+        }
+        state.pending.clear();
+    }
+    if !preview.enabled {
+        return;
+    }
+
+    if rebuild {
+        if preview.refresh_requested {
+            preview.refresh_requested = false;
+        }
+        let screen_units = inputs.screen_units();
+        let scale_factor = windows.get(managed_camera.window).map_or(1.0, |window| window.scale_factor()) as f32;
+        let strip = commands
+            .spawn_bundle(NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    position: Rect {
+                        left: Val::Px(8.0),
+                        bottom: Val::Px(8.0),
+                        ..default()
+                    },
+                    ..default()
+                },
+                color: Color::NONE.into(),
+                ..default()
+            })
+            .insert(AspectPreviewStrip)
+            .id();
+
+        for (width, height) in PREVIEW_ASPECT_RATIOS {
+            // Thumbnails are laid out in logical pixels, and rendered in physical ones to stay sharp.
+            let size = Vec2::new(preview.thumbnail_height * width / height, preview.thumbnail_height);
+            let layout = compute_fill_layout(size, &screen_units, &PlatformInsets::default(), *fill_mode);
+            let image_rect = layout.to_window_px(layout.framed);
+            let render_size = (image_rect.size() * scale_factor).round().max(Vec2::ONE);
+            let image = images.add(render_target_image(render_size.x as u32, render_size.y as u32, false));
+
+            let thumbnail = commands
+                .spawn_bundle(NodeBundle {
+                    style: Style {
+                        size: Size::new(Val::Px(size.x), Val::Px(size.y)),
+                        margin: Rect {
+                            right: Val::Px(8.0),
+                            ..default()
+                        },
+                        ..default()
+                    },
+                    color: style.color.into(),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent.spawn_bundle(ImageBundle {
+                        style: Style {
+                            position_type: PositionType::Absolute,
+                            position: Rect {
+                                left: Val::Px(image_rect.min.x),
+                                bottom: Val::Px(image_rect.min.y),
+                                ..default()
+                            },
+                            size: Size::new(Val::Px(image_rect.size().x), Val::Px(image_rect.size().y)),
+                            ..default()
+                        },
+                        image: UiImage(image.clone()),
+                        ..default()
+                    });
+                    if let Some(font) = &preview.font {
+                        parent.spawn_bundle(TextBundle {
+                            style: Style {
+                                position_type: PositionType::Absolute,
+                                position: Rect {
+                                    left: Val::Px(4.0),
+                                    top: Val::Px(2.0),
+                                    ..default()
+                                },
+                                ..default()
+                            },
+                            text: Text::with_section(
+                                format!("{}:{}", width, height),
+                                TextStyle {
+                                    font: font.clone(),
+                                    font_size: 14.0,
+                                    color: Color::WHITE,
+                                },
+                                default(),
+                            ),
+                            ..default()
+                        });
+                    }
+                })
+                .id();
+            commands.entity(strip).add_child(thumbnail);
+            state.thumbnails.push(Thumbnail {
+                image,
+                framed: layout.framed,
+            });
+        }
+        state.strip = Some(strip);
+        state.pending = (0..state.thumbnails.len()).rev().collect();
+    }
+
+    // Render the next thumbnail, and remove the camera once they're all done.
+    let index = match state.pending.pop() {
+        Some(index) => index,
+        None => {
+            if let Some(camera) = state.camera.take() {
+                commands.entity(camera).despawn();
+            }
+            return;
+        },
+    };
+    let thumbnail = &state.thumbnails[index];
+    let transform = managed_camera
+        .entity
+        .and_then(|entity| camera_query.get(entity).ok())
+        .map_or(Transform::default(), |transform| Transform::from_matrix(transform.compute_matrix()));
+    let framed = thumbnail.framed;
+    let target = RenderTarget::Image(thumbnail.image.clone());
+    let set_projection = |projection: &mut OrthographicProjection| {
+        projection.scaling_mode = ScalingMode::None;
+        projection.left = framed.min.x;
+        projection.right = framed.max.x;
+        projection.bottom = framed.min.y;
+        projection.top = framed.max.y;
+        projection.scale = 1.0;
+    };
+
+    match state.camera.and_then(|entity| preview_camera_query.get_mut(entity).ok()) {
+        Some((mut camera, mut projection, mut camera_transform)) => {
+            camera.target = target;
+            set_projection(&mut projection);
+            *camera_transform = transform;
+        },
+        None => {
+            let mut bundle = extra_camera_bundle::<AspectPreviewCamera>(RenderTarget::Image(thumbnail.image.clone()));
+            set_projection(&mut bundle.orthographic_projection);
+            bundle.transform = transform;
+            state.camera = Some(commands.spawn_bundle(bundle).id());
+        },
+    }
+}
+
+pub(crate) fn add_preview_camera(app: &mut App) {
+    add_extra_camera::<AspectPreviewCamera>(app, "letterbox_aspect_preview_driver");
+}
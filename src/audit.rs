@@ -0,0 +1,121 @@
+use bevy::ecs::schedule::ShouldRun;
+use bevy::prelude::*;
+use bevy::render::camera::{RenderTarget, ScalingMode};
+
+use crate::{LayoutInputs, Letterbox, LetterboxLayout, LetterboxManaged, ManagedCamera};
+
+// Resource for an opt-in check that the projection and letterboxes still match the layout, catching
+// other code writing to them. Each frame the expected layout is recomputed from the window and
+// ScreenUnits and compared against what's actually applied. Cheap enough for internal builds.
+pub struct LetterboxAudit {
+    pub enabled: bool,
+    // Largest difference in world units which isn't reported.
+    pub tolerance: f32,
+}
+
+impl Default for LetterboxAudit {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            tolerance: 1e-3,
+        }
+    }
+}
+
+pub(crate) fn audit_enabled(audit: Res<LetterboxAudit>) -> ShouldRun {
+    if audit.enabled {
+        ShouldRun::Yes
+    } else {
+        ShouldRun::No
+    }
+}
+
+pub(crate) fn audit_letterboxes(
+    audit: Res<LetterboxAudit>,
+    inputs: LayoutInputs,
+    windows: Res<Windows>,
+    images: Res<Assets<Image>>,
+    managed_camera: Res<ManagedCamera>,
+    layout: Res<LetterboxLayout>,
+    projection_query: Query<(&Camera, &OrthographicProjection), With<LetterboxManaged>>,
+    camera_query: Query<&Transform, With<Camera>>,
+    letterbox_query: Query<(Entity, &Letterbox, &Transform, &Sprite, &Visibility)>,
+    mut was_mismatched: Local<bool>,
+) {
+    if inputs.suspend.is_suspended() {
+        return;
+    }
+    let camera = match managed_camera.entity {
+        Some(camera) => camera,
+        None => return,
+    };
+
+    // Cameras rendering into their own image are laid out within the image, like in the layout system.
+    let image_target = match projection_query.get(camera) {
+        Ok((Camera { target: RenderTarget::Image(handle), .. }, _)) if inputs.backend.renders_to_camera_target() => {
+            Some(handle)
+        },
+        _ => None,
+    };
+    let target_size = match image_target {
+        Some(handle) => images.get(handle).map(|image| {
+            (Vec2::new(image.texture_descriptor.size.width as f32, image.texture_descriptor.size.height as f32), 1.0)
+        }),
+        None => windows.get(managed_camera.window).map(|window| (Vec2::new(window.width(), window.height()), window.scale_factor())),
+    };
+    let (target_size, scale_factor) = match target_size {
+        Some(size) => size,
+        None => return,
+    };
+
+    let (expected_layout, expected_projection) = inputs.compute(target_size, &inputs.screen_units(), scale_factor);
+    let tolerance = audit.tolerance;
+    let near = |a: Vec2, b: Vec2| (a - b).abs().max_element() <= tolerance;
+    let mut mismatches = Vec::new();
+
+    if !near(layout.visible.min, expected_layout.visible.min) || !near(layout.visible.max, expected_layout.visible.max) {
+        mismatches.push(format!("LetterboxLayout visible area is {:?}, expected {:?}", layout.visible, expected_layout.visible));
+    }
+
+    if let Ok((_, projection)) = projection_query.get(camera) {
+        let actual_min = Vec2::new(projection.left, projection.bottom) * projection.scale;
+        let actual_max = Vec2::new(projection.right, projection.top) * projection.scale;
+        if !matches!(projection.scaling_mode, ScalingMode::None) {
+            mismatches.push(format!("camera {:?} uses {:?}, expected ScalingMode::None", camera, projection.scaling_mode));
+        } else if !near(actual_min, expected_projection.visible.min) || !near(actual_max, expected_projection.visible.max) {
+            mismatches.push(format!(
+                "camera {:?} shows {:?} to {:?}, expected {:?}",
+                camera, actual_min, actual_max, expected_projection.visible
+            ));
+        }
+    }
+
+    // The letterboxes are compared in camera space, so ones left behind by a moving camera are caught.
+    // Hidden letterboxes, e.g. collapsed ones, keep their last transform.
+    let camera_position = camera_query.get(camera).map_or(Vec2::ZERO, |transform| transform.translation.truncate());
+    for (entity, letterbox, transform, sprite, visibility) in letterbox_query.iter() {
+        if !visibility.is_visible {
+            continue;
+        }
+        let expected = expected_projection.bar(letterbox.side);
+        let size = sprite.custom_size.unwrap_or(Vec2::ONE) * transform.scale.truncate();
+        let position = transform.translation.truncate() - camera_position;
+        if !near(size, expected.size()) || !near(position, expected.center()) {
+            mismatches.push(format!(
+                "{:?} letterbox {:?} is {:?} at {:?}, expected {:?} at {:?}",
+                letterbox.side,
+                entity,
+                size,
+                position,
+                expected.size(),
+                expected.center()
+            ));
+        }
+    }
+
+    // Report when a desync starts rather than every frame it lasts.
+    if !mismatches.is_empty() && !*was_mismatched {
+        warn!("Letterbox audit found the layout out of sync:\n{}", mismatches.join("\n"));
+    }
+    *was_mismatched = !mismatches.is_empty();
+}
@@ -0,0 +1,312 @@
+use bevy::prelude::*;
+use bevy::render::camera::RenderTarget;
+use bevy::render::render_resource::{
+    Extent3d, FilterMode, SamplerDescriptor, TextureDescriptor, TextureDimension, TextureFormat, TextureUsages,
+};
+
+use crate::{
+    compute_letterbox_layout, BarRect, LetterboxLayout, LetterboxRect, LetterboxSide, LetterboxStyle, ManagedCamera,
+    PhysicalRect, PlatformInsets, ScreenUnits, TransparentLetterboxes,
+};
+
+// Resource selecting how the letterboxing is drawn. It can be changed at any time: the previous
+// backend's entities and render target are removed and the new backend is laid out in the same frame.
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
+pub enum LetterboxBackend {
+    // The camera renders straight to the window and sprites cover everything outside the safe area.
+    #[default]
+    Sprites,
+    // The camera renders to an image at a fixed resolution which is shown centered in the window with
+    // bevy_ui letterboxes around it. Resizing the window only changes how large the image is shown. The
+    // game's UI camera draws them if it has one, otherwise the backend spawns its own.
+    RenderTexture(RenderTextureSettings),
+    // The camera renders straight to the window, but only inside the area the letterboxes leave. The
+    // letterboxes are painted onto the target in the style's colors first and the 2D pass is scissored
+    // to the rest, so nothing is rasterized under them. Textures, slices and layers aren't drawn, and
+    // neither is anything bleeding over a bar.
+    #[cfg(feature = "scissor")]
+    Scissor,
+    // The camera renders straight to the window and the letterboxes are painted over it in the style's
+    // colors by a fullscreen pass after everything else but bevy_ui, so effects and post processing
+    // can't spill over them. Custom passes should have an edge into LETTERBOX_MASK_PASS. Textures,
+    // slices and layers aren't drawn.
+    #[cfg(feature = "post-process")]
+    PostProcess,
+}
+
+impl LetterboxBackend {
+    // Whether the managed camera draws into its own target, rather than the render texture backend's
+    // image. The layout and the letterbox sprites are then in the target's pixels.
+    pub fn renders_to_camera_target(&self) -> bool {
+        !matches!(self, LetterboxBackend::RenderTexture(_))
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct RenderTextureSettings {
+    // Resolution of the image the camera renders to, in pixels.
+    pub width: u32,
+    pub height: u32,
+    // Only show the image at whole multiples of its resolution, for crisp pixel art.
+    pub integer_scaling: bool,
+    // Renders at this multiple of the resolution and filters it down when shown, for anti-aliasing.
+    // The image takes 4 * width * height * supersample^2 bytes of GPU memory, so the factor is
+    // clamped to MAX_SUPERSAMPLE. It doesn't affect the layout or anything measured in units.
+    pub supersample: f32,
+}
+
+impl RenderTextureSettings {
+    pub const MAX_SUPERSAMPLE: f32 = 4.0;
+
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            integer_scaling: false,
+            supersample: 1.0,
+        }
+    }
+
+    // Size of the image the camera renders to, including supersampling.
+    pub fn render_size(&self) -> (u32, u32) {
+        let supersample = if self.supersample.is_finite() {
+            self.supersample.clamp(1.0, Self::MAX_SUPERSAMPLE)
+        } else {
+            1.0
+        };
+        (
+            ((self.width as f32 * supersample).round() as u32).max(1),
+            ((self.height as f32 * supersample).round() as u32).max(1),
+        )
+    }
+
+    pub fn size(&self) -> Vec2 {
+        Vec2::new(self.width as f32, self.height as f32)
+    }
+
+    // The layout of the screen units within the image.
+    pub fn image_layout(&self, screen_units: &ScreenUnits) -> LetterboxLayout {
+        compute_letterbox_layout(self.size(), screen_units, &PlatformInsets::default())
+    }
+
+    // Where the image is shown in the window, in layout pixels.
+    pub fn blit_rect(&self, layout: &LetterboxLayout) -> LetterboxRect {
+        let safe_size = layout.safe_area.size();
+        let image_layout = self.image_layout(&ScreenUnits {
+            width: safe_size.x,
+            height: safe_size.y,
+        });
+        let center = layout.safe_area_px.center();
+        LetterboxRect::new(
+            center + image_layout.visible.min * layout.pixels_per_unit,
+            center + image_layout.visible.max * layout.pixels_per_unit,
+        )
+    }
+}
+
+// Component for the UI node which shows the rendered image.
+#[derive(Component)]
+pub struct RenderTextureBlit;
+
+// Component for the UI nodes which cover the window around the rendered image.
+#[derive(Component)]
+pub struct UiLetterbox { pub side: LetterboxSide }
+
+pub(crate) struct RenderTextureState {
+    settings: RenderTextureSettings,
+    image: Handle<Image>,
+    camera: Entity,
+    entities: Vec<Entity>,
+}
+
+pub(crate) fn update_render_texture_backend(
+    mut commands: Commands,
+    backend: Res<LetterboxBackend>,
+    layout: Res<LetterboxLayout>,
+    style: Res<LetterboxStyle>,
+    managed_camera: Res<ManagedCamera>,
+    windows: Res<Windows>,
+    transparency: Res<TransparentLetterboxes>,
+    mut images: ResMut<Assets<Image>>,
+    mut camera_query: Query<&mut Camera>,
+    ui_camera_query: Query<(), With<CameraUi>>,
+    mut blit_query: Query<&mut Style, With<RenderTextureBlit>>,
+    mut bar_query: Query<(&UiLetterbox, &mut Style, &mut UiColor, Option<&mut BarRect>), Without<RenderTextureBlit>>,
+    mut state: Local<Option<RenderTextureState>>,
+) {
+    let desired = match *backend {
+        LetterboxBackend::RenderTexture(settings) => managed_camera.entity.map(|camera| (settings, camera)),
+        _ => None,
+    };
+
+    // Tear down the previous render texture when it's turned off or no longer matches the settings,
+    // pointing the camera back at the window it was rendering to.
+    if let Some(current) = &*state {
+        if desired != Some((current.settings, current.camera)) {
+            if let Ok(mut camera) = camera_query.get_mut(current.camera) {
+                camera.target = RenderTarget::Window(managed_camera.window);
+            }
+            for entity in &current.entities {
+                commands.entity(*entity).despawn_recursive();
+            }
+            images.remove(&current.image);
+            *state = None;
+            info!("Switched away from the render texture letterbox backend");
+        }
+    }
+
+    let (settings, camera_entity) = match desired {
+        Some(desired) => desired,
+        None => return,
+    };
+    // UI is positioned in logical pixels.
+    let blit_rect = settings.blit_rect(&layout).scaled(1.0 / layout.dpi_scale);
+    let window_size = layout.window_size / layout.dpi_scale;
+    let scale_factor = windows.get(managed_camera.window).map_or(1.0, |window| window.scale_factor());
+
+    if state.is_none() {
+        let mut camera = match camera_query.get_mut(camera_entity) {
+            Ok(camera) => camera,
+            Err(_) => return,
+        };
+        let (width, height) = settings.render_size();
+        let image = images.add(render_target_image(width, height, settings.supersample > 1.0));
+        camera.target = RenderTarget::Image(image.clone());
+
+        let mut entities = vec![commands
+            .spawn_bundle(ImageBundle {
+                style: ui_rect_style(blit_rect),
+                image: UiImage(image.clone()),
+                ..default()
+            })
+            .insert(RenderTextureBlit)
+            .id()];
+        // The image and bars are UI nodes, so they need a UI camera. One the game already has is used,
+        // otherwise one is spawned and removed again with the rest.
+        if ui_camera_query.is_empty() {
+            entities.push(commands.spawn_bundle(UiCameraBundle::default()).id());
+        }
+        for side in LetterboxSide::ALL {
+            entities.push(
+                commands
+                    .spawn_bundle(NodeBundle {
+                        style: ui_rect_style(ui_bar_rect(window_size, blit_rect, side)),
+                        color: UiColor(ui_bar_color(&style, &transparency, side)),
+                        ..default()
+                    })
+                    .insert(UiLetterbox { side })
+                    .insert(ui_bar_extent(&layout, ui_bar_rect(window_size, blit_rect, side), scale_factor))
+                    .id(),
+            );
+        }
+
+        info!("Switched to the render texture letterbox backend at {}x{}", settings.width, settings.height);
+        *state = Some(RenderTextureState {
+            settings,
+            image,
+            camera: camera_entity,
+            entities,
+        });
+        return;
+    }
+
+    // The scale factor can change without the layout changing, so these are checked every frame.
+    for (bar, _, _, bar_rect) in bar_query.iter_mut() {
+        if let Some(mut bar_rect) = bar_rect {
+            let new_bar_rect = ui_bar_extent(&layout, ui_bar_rect(window_size, blit_rect, bar.side), scale_factor);
+            if *bar_rect != new_bar_rect {
+                *bar_rect = new_bar_rect;
+            }
+        }
+    }
+
+    if !layout.is_changed() && !style.is_changed() && !transparency.is_changed() {
+        return;
+    }
+    for mut blit_style in blit_query.iter_mut() {
+        *blit_style = ui_rect_style(blit_rect);
+    }
+    for (bar, mut bar_style, mut color, _) in bar_query.iter_mut() {
+        *bar_style = ui_rect_style(ui_bar_rect(window_size, blit_rect, bar.side));
+        color.0 = ui_bar_color(&style, &transparency, bar.side);
+    }
+}
+
+// Transparent letterboxes leave holes in a transparent window.
+pub(crate) fn ui_bar_color(
+    style: &LetterboxStyle,
+    transparency: &TransparentLetterboxes,
+    side: LetterboxSide,
+) -> Color {
+    if transparency.enabled {
+        Color::NONE
+    } else {
+        style.color_for(side)
+    }
+}
+
+pub(crate) fn render_target_image(width: u32, height: u32, supersampled: bool) -> Image {
+    let size = Extent3d {
+        width,
+        height,
+        depth_or_array_layers: 1,
+    };
+    let mut image = Image {
+        texture_descriptor: TextureDescriptor {
+            label: None,
+            size,
+            dimension: TextureDimension::D2,
+            format: TextureFormat::Bgra8UnormSrgb,
+            mip_level_count: 1,
+            sample_count: 1,
+            usage: TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST | TextureUsages::RENDER_ATTACHMENT,
+        },
+        ..default()
+    };
+    image.resize(size);
+
+    // Supersampled images are shown smaller than they were rendered, which needs filtering.
+    if supersampled {
+        image.sampler_descriptor = SamplerDescriptor {
+            mag_filter: FilterMode::Linear,
+            min_filter: FilterMode::Linear,
+            ..default()
+        };
+    }
+    image
+}
+
+// The part of the window outside the image which a UI letterbox covers, tiled the same way as the sprites.
+fn ui_bar_rect(window_size: Vec2, blit: LetterboxRect, side: LetterboxSide) -> LetterboxRect {
+    LetterboxRect::new(Vec2::ZERO, window_size).frame_side(blit, side)
+}
+
+// The BarRect of a UI letterbox covering a rectangle in logical pixels.
+fn ui_bar_extent(layout: &LetterboxLayout, rect: LetterboxRect, scale_factor: f64) -> BarRect {
+    let rect = rect.scaled(layout.dpi_scale);
+    let origin_px = layout.safe_area_px.center();
+    BarRect {
+        units: LetterboxRect::new(
+            (rect.min - origin_px) / layout.pixels_per_unit,
+            (rect.max - origin_px) / layout.pixels_per_unit,
+        ),
+        pixels: PhysicalRect::from_layout_px(layout, rect, scale_factor),
+    }
+}
+
+// Absolutely positions a UI node over a rectangle in logical pixels from the bottom left of the window.
+// Nodes thinner than a pixel aren't laid out or drawn, like the collapsed letterbox sprites.
+pub(crate) fn ui_rect_style(rect: LetterboxRect) -> Style {
+    let size = rect.size().max(Vec2::ZERO);
+    Style {
+        display: if size.min_element() < 1.0 { Display::None } else { Display::Flex },
+        position_type: PositionType::Absolute,
+        position: Rect {
+            left: Val::Px(rect.min.x),
+            bottom: Val::Px(rect.min.y),
+            ..default()
+        },
+        size: Size::new(Val::Px(size.x), Val::Px(size.y)),
+        ..default()
+    }
+}
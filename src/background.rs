@@ -0,0 +1,75 @@
+use bevy::prelude::*;
+use bevy::render::view::RenderLayers;
+
+use crate::camera::{add_extra_camera, extra_camera_bundle};
+use crate::ManagedCamera;
+
+// Resource for render layers drawn over the whole window beneath the letterboxes, e.g. a distant
+// parallax background which continues into the bar area while the gameplay layers stay framed. They're
+// drawn before the managed camera by a camera following it, so they have to be layers the managed
+// camera doesn't see; entities without RenderLayers are on layer 0 like the managed camera. The bar area
+// is only left to them with LetterboxBackend::Scissor, from the scissor feature, which then clips the
+// managed camera to the framed area without painting the bars. The other backends still draw their bars
+// over it. None turns it off.
+#[derive(Default, Clone, Copy, PartialEq, Debug)]
+pub struct BackgroundLayers(pub Option<RenderLayers>);
+
+// Component for the camera which draws the background layers.
+#[derive(Component, Default)]
+pub struct LetterboxBackgroundCamera;
+
+pub(crate) fn add_background_camera(app: &mut App) {
+    add_extra_camera::<LetterboxBackgroundCamera>(app, "letterbox_background_driver");
+}
+
+// Keeps the background camera looking exactly where the managed camera does, over the whole window.
+pub(crate) fn update_background_camera(
+    mut commands: Commands,
+    layers: Res<BackgroundLayers>,
+    managed_camera: Res<ManagedCamera>,
+    managed_query: Query<
+        (&Camera, &Transform, &OrthographicProjection, ChangeTrackers<OrthographicProjection>),
+        Without<LetterboxBackgroundCamera>,
+    >,
+    mut background_query: Query<
+        (Entity, &mut Camera, &mut Transform, &mut OrthographicProjection, &mut RenderLayers),
+        With<LetterboxBackgroundCamera>,
+    >,
+) {
+    let managed = managed_camera.entity.and_then(|entity| managed_query.get(entity).ok());
+    let (layers, (camera, transform, projection, projection_tracker)) = match (layers.0, managed) {
+        (Some(layers), Some(managed)) => (layers, managed),
+        _ => {
+            for (entity, ..) in background_query.iter() {
+                commands.entity(entity).despawn();
+            }
+            return;
+        },
+    };
+
+    let (_, mut background, mut background_transform, mut background_projection, mut background_layers) =
+        match background_query.get_single_mut() {
+            Ok(background) => background,
+            Err(_) => {
+                let mut bundle = extra_camera_bundle::<LetterboxBackgroundCamera>(camera.target.clone());
+                bundle.orthographic_projection = projection.clone();
+                bundle.transform = *transform;
+                commands.spawn_bundle(bundle).insert(layers);
+                return;
+            },
+        };
+    if background.target != camera.target {
+        background.target = camera.target.clone();
+    }
+    if *background_transform != *transform {
+        *background_transform = *transform;
+    }
+    // Projections can't be compared, and copying one every frame would recompute its matrix every frame.
+    if projection_tracker.is_changed() || managed_camera.is_changed() {
+        *background_projection = projection.clone();
+    }
+    if *background_layers != layers {
+        *background_layers = layers;
+    }
+}
+
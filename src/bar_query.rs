@@ -0,0 +1,68 @@
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+
+use crate::{Letterbox, LetterboxBackend, LetterboxLayout, LetterboxRect, LetterboxSide, UiLetterbox};
+
+// Filters for the entities drawing letterboxes with each backend.
+pub type WithLetterbox = With<Letterbox>;
+pub type WithUiLetterbox = With<UiLetterbox>;
+
+// A letterbox as it's currently drawn.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct BarInfo {
+    pub side: LetterboxSide,
+    // The sprite with the sprite backend, or the UI node with the render texture backend.
+    pub entity: Entity,
+    // In world units relative to the camera.
+    pub rect: LetterboxRect,
+    // In layout pixels from the bottom left of the window.
+    pub rect_px: LetterboxRect,
+    pub visible: bool,
+}
+
+// System parameter for finding the letterboxes without matching sides by hand. It looks at whichever
+// entities the current backend draws with, so it keeps working when the backend is switched. Sides
+// without an entity, e.g. in compute only mode, are skipped.
+#[derive(SystemParam)]
+pub struct LetterboxQuery<'w, 's> {
+    layout: Res<'w, LetterboxLayout>,
+    backend: Res<'w, LetterboxBackend>,
+    sprite_query: Query<'w, 's, (Entity, &'static Letterbox, &'static Visibility)>,
+    ui_query: Query<'w, 's, (Entity, &'static UiLetterbox, &'static Visibility, &'static Style)>,
+}
+
+impl<'w, 's> LetterboxQuery<'w, 's> {
+    pub fn get(&self, side: LetterboxSide) -> Option<BarInfo> {
+        let (entity, visible) = match *self.backend {
+            LetterboxBackend::RenderTexture(_) => self
+                .ui_query
+                .iter()
+                .find(|(_, letterbox, ..)| letterbox.side == side)
+                .map(|(entity, _, visibility, style)| (entity, visibility.is_visible && style.display != Display::None)),
+            // The other backends keep the sprites laid out, even when they draw the bars themselves.
+            _ => self
+                .sprite_query
+                .iter()
+                .find(|(_, letterbox, _)| letterbox.side == side)
+                .map(|(entity, _, visibility)| (entity, visibility.is_visible)),
+        }?;
+        let rect = self.layout.bar(side);
+        Some(BarInfo {
+            side,
+            entity,
+            rect,
+            rect_px: self.layout.to_window_px(rect),
+            visible,
+        })
+    }
+
+    // Every letterbox, in the order of LetterboxSide::ALL.
+    pub fn iter(&self) -> impl Iterator<Item = BarInfo> + '_ {
+        LetterboxSide::ALL.into_iter().filter_map(|side| self.get(side))
+    }
+
+    // The letterboxes which are currently drawn.
+    pub fn iter_active(&self) -> impl Iterator<Item = BarInfo> + '_ {
+        self.iter().filter(|bar| bar.visible)
+    }
+}
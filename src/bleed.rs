@@ -0,0 +1,19 @@
+use bevy::prelude::*;
+
+// Depth entities marked with AllowBleed are lifted to: above the letterboxes and cinematic bars, below
+// the progress indicator, transitions and subtitles.
+pub const BLEED_Z: f32 = 999.1;
+
+// Component for entities which should draw over the letterboxes, e.g. weather particles spilling
+// across the bars. Their depth is raised to BLEED_Z, so put it on top level entities. With the render
+// texture backend the world is only rendered inside the safe area, so there's nothing to bleed.
+#[derive(Component, Default, Clone, Copy, PartialEq, Debug)]
+pub struct AllowBleed;
+
+pub(crate) fn lift_bleeding_entities(mut bleed_query: Query<&mut Transform, With<AllowBleed>>) {
+    for mut transform in bleed_query.iter_mut() {
+        if transform.translation.z < BLEED_Z {
+            transform.translation.z = BLEED_Z;
+        }
+    }
+}
@@ -0,0 +1,73 @@
+use bevy::prelude::*;
+
+use crate::camera::ManagedCameraPosition;
+use crate::{LetterboxLayout, LetterboxRect, LetterboxSide};
+
+// Resource for an outline drawn just inside the edge of the safe area during development.
+// It's drawn above gameplay but below the letterboxes and never affects the layout.
+pub struct SafeAreaBorder {
+    pub enabled: bool,
+    // Thickness of the outline in world units.
+    pub thickness: f32,
+    pub color: Color,
+}
+
+impl Default for SafeAreaBorder {
+    fn default() -> Self {
+        Self {
+            // Only shown in debug builds unless turned on explicitly.
+            enabled: cfg!(debug_assertions),
+            thickness: 0.05,
+            color: Color::rgb(1.0, 0.0, 1.0),
+        }
+    }
+}
+
+// Component for identifying the entities which make up the safe area border.
+#[derive(Component)]
+pub struct SafeAreaBorderEdge { pub side: LetterboxSide }
+
+pub(crate) fn spawn_safe_area_border(mut commands: Commands) {
+    for side in LetterboxSide::ALL {
+        commands
+            .spawn_bundle(SpriteBundle {
+                transform: Transform {
+                    scale: Vec3::new( 0.0, 0.0, 1.0 ),
+                    ..default()
+                },
+                ..default()
+            })
+            .insert(SafeAreaBorderEdge { side });
+    }
+}
+
+pub(crate) fn update_safe_area_border(
+    border: Res<SafeAreaBorder>,
+    layout: Res<LetterboxLayout>,
+    camera_position: Res<ManagedCameraPosition>,
+    mut edge_query: Query<(&SafeAreaBorderEdge, &mut Transform, &mut Sprite, &mut Visibility)>,
+) {
+    if !border.is_changed() && !layout.is_changed() && !camera_position.is_changed() {
+        return;
+    }
+
+    let safe = layout.safe_area;
+    let thickness = border.thickness.min(safe.size().min_element() / 2.0);
+
+    for (edge, mut transform, mut sprite, mut visibility) in edge_query.iter_mut() {
+        visibility.is_visible = border.enabled;
+        sprite.color = border.color;
+
+        // Each edge is a thin strip along the inside of the safe area.
+        let rect = match edge.side {
+            LetterboxSide::Left => LetterboxRect::new(safe.min, Vec2::new(safe.min.x + thickness, safe.max.y)),
+            LetterboxSide::Right => LetterboxRect::new(Vec2::new(safe.max.x - thickness, safe.min.y), safe.max),
+            LetterboxSide::Top => LetterboxRect::new(Vec2::new(safe.min.x, safe.max.y - thickness), safe.max),
+            LetterboxSide::Bottom => LetterboxRect::new(safe.min, Vec2::new(safe.max.x, safe.min.y + thickness)),
+        };
+        let size = rect.size();
+        let center = camera_position.0 + rect.center();
+        transform.scale = Vec3::new( size.x, size.y, 1.0 );
+        transform.translation = Vec3::new( center.x, center.y, 998.0 );
+    }
+}
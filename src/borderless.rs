@@ -0,0 +1,89 @@
+use bevy::prelude::*;
+use bevy::window::WindowMode;
+
+// Resource for how long the layout waits for a window entering borderless fullscreen to settle. Some
+// platforms, such as Windows with a taskbar, first size the window to the monitor's work area and only
+// cover the whole monitor a few frames later, so laying out every step would pop. Once the window
+// reports the same size for frames frames in a row, or timeout seconds have passed, the layout is
+// applied once with the final size. Zero frames turns the wait off.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct BorderlessSettling {
+    pub frames: u32,
+    pub timeout: f32,
+}
+
+impl Default for BorderlessSettling {
+    fn default() -> Self {
+        Self {
+            frames: 3,
+            timeout: 0.25,
+        }
+    }
+}
+
+pub(crate) enum Settle {
+    // Still settling, so nothing should be laid out.
+    Waiting,
+    // The new window size to lay out, if there is one.
+    Ready(Option<Vec2>),
+}
+
+#[derive(Default)]
+pub(crate) struct BorderlessState {
+    last_mode: Option<WindowMode>,
+    settling: Option<Settling>,
+}
+
+struct Settling {
+    started: f64,
+    size: Vec2,
+    stable_frames: u32,
+}
+
+impl BorderlessState {
+    pub(crate) fn settle(
+        &mut self,
+        settings: &BorderlessSettling,
+        window: Option<&Window>,
+        now: f64,
+        new_size: Option<Vec2>,
+    ) -> Settle {
+        let window = match window {
+            Some(window) => window,
+            None => {
+                self.settling = None;
+                return Settle::Ready(new_size);
+            },
+        };
+        let size = Vec2::new(window.width(), window.height());
+        let entered = window.mode() == WindowMode::BorderlessFullscreen
+            && self.last_mode.is_some_and(|mode| mode != WindowMode::BorderlessFullscreen);
+        self.last_mode = Some(window.mode());
+        if entered && settings.frames > 0 {
+            self.settling = Some(Settling {
+                started: now,
+                size,
+                stable_frames: 0,
+            });
+            return Settle::Waiting;
+        }
+
+        let settling = match &mut self.settling {
+            Some(settling) => settling,
+            None => return Settle::Ready(new_size),
+        };
+        if size == settling.size {
+            settling.stable_frames += 1;
+        } else {
+            settling.size = size;
+            settling.stable_frames = 0;
+        }
+        let timed_out = now - settling.started >= settings.timeout as f64;
+        if settling.stable_frames >= settings.frames || timed_out || window.mode() != WindowMode::BorderlessFullscreen {
+            self.settling = None;
+            Settle::Ready(Some(size))
+        } else {
+            Settle::Waiting
+        }
+    }
+}
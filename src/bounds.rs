@@ -0,0 +1,90 @@
+use bevy::prelude::*;
+
+use crate::{LetterboxLayout, LetterboxRect, ManagedCamera};
+
+// Component which keeps a camera from showing anything outside a rectangle in world units, such as the
+// level, however much the current layout shows. Along an axis where the rectangle is smaller than the
+// area between the letterboxes the camera is centered on it instead. Systems moving the camera should run before
+// LetterboxSystem::CameraClamp.
+#[derive(Component, Clone, Copy, PartialEq, Debug)]
+pub struct ClampCameraToBounds {
+    pub rect: LetterboxRect,
+}
+
+pub(crate) fn clamp_cameras_to_bounds(
+    layout: Res<LetterboxLayout>,
+    mut camera_query: Query<(&ClampCameraToBounds, &mut Transform)>,
+) {
+    // The framed area is relative to the camera, so the camera's position offsets it into the world.
+    let safe = layout.framed;
+    let clamp_axis = |position: f32, bounds_min: f32, bounds_max: f32, safe_min: f32, safe_max: f32| {
+        if bounds_max - bounds_min <= safe_max - safe_min {
+            (bounds_min + bounds_max - safe_min - safe_max) / 2.0
+        } else {
+            position.clamp(bounds_min - safe_min, bounds_max - safe_max)
+        }
+    };
+
+    for (bounds, mut transform) in camera_query.iter_mut() {
+        let rect = bounds.rect;
+        let x = clamp_axis(transform.translation.x, rect.min.x, rect.max.x, safe.min.x, safe.max.x);
+        let y = clamp_axis(transform.translation.y, rect.min.y, rect.max.y, safe.min.y, safe.max.y);
+        if transform.translation.x != x || transform.translation.y != y {
+            transform.translation.x = x;
+            transform.translation.y = y;
+        }
+    }
+}
+
+// Component which keeps an entity, e.g. the player, inside the picture shown between the letterboxes:
+// the safe area, plus the extra world shown with FillMode::Expand, minus anything cropped. The entity's
+// half extents and margin stay inside too, so it never pokes under a bar. Clamping runs in
+// CoreStage::PostUpdate, after movement in the update stage, and also pulls entities back in when
+// ScreenUnits shrinks. Only the translation is changed, so it's meant for entities without a parent.
+#[derive(Component, Default, Clone, Copy, PartialEq, Debug)]
+pub struct ClampToSafeArea {
+    // In world units, kept between the entity's edges and the edges of the picture.
+    pub margin: Vec2,
+    // In world units before the entity's scale. Uses the sprite's custom size when unset.
+    pub half_extents: Option<Vec2>,
+}
+
+pub(crate) fn clamp_to_safe_area(
+    layout: Res<LetterboxLayout>,
+    managed_camera: Res<ManagedCamera>,
+    camera_query: Query<&Transform, Without<ClampToSafeArea>>,
+    mut entity_query: Query<(&ClampToSafeArea, &mut Transform, Option<&Sprite>)>,
+) {
+    // The camera has already moved this frame, so its Transform is more current than its GlobalTransform.
+    let camera_position = managed_camera
+        .entity
+        .and_then(|entity| camera_query.get(entity).ok())
+        .map_or(Vec2::ZERO, |transform| transform.translation.truncate());
+    let picture = layout.framed.intersect(layout.visible);
+
+    for (clamp, mut transform, sprite) in entity_query.iter_mut() {
+        let half_extents = clamp
+            .half_extents
+            .or_else(|| sprite.and_then(|sprite| sprite.custom_size).map(|size| size / 2.0))
+            .unwrap_or(Vec2::ZERO);
+        let reach = half_extents * transform.scale.truncate().abs() + clamp.margin.max(Vec2::ZERO);
+        let min = camera_position + picture.min + reach;
+        let max = camera_position + picture.max - reach;
+        // Entities too large to fit are centered instead.
+        let center = camera_position + picture.center();
+        let clamp_axis = |position: f32, min: f32, max: f32, center: f32| {
+            if min > max {
+                center
+            } else {
+                position.clamp(min, max)
+            }
+        };
+
+        let x = clamp_axis(transform.translation.x, min.x, max.x, center.x);
+        let y = clamp_axis(transform.translation.y, min.y, max.y, center.y);
+        if transform.translation.x != x || transform.translation.y != y {
+            transform.translation.x = x;
+            transform.translation.y = y;
+        }
+    }
+}
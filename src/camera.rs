@@ -0,0 +1,148 @@
+use std::marker::PhantomData;
+
+use bevy::core_pipeline::{draw_2d_graph, node, Transparent2d};
+use bevy::prelude::*;
+use bevy::render::camera::{ActiveCamera, Camera2d, Camera3d, CameraTypePlugin, RenderTarget};
+use bevy::render::render_graph::{Node, NodeRunError, RenderGraph, RenderGraphContext, SlotValue};
+use bevy::render::render_phase::RenderPhase;
+use bevy::render::renderer::RenderContext;
+use bevy::render::{RenderApp, RenderStage};
+use bevy::window::WindowCreated;
+
+use crate::ManagedCamera;
+
+// The letterboxes are sprites, so they're drawn by the 2D camera. Bevy renders the 3D pass before the
+// 2D pass, which means a 3D camera's meshes are always covered by the letterboxes as long as a 2D camera
+// is active. These checks warn about setups where that isn't the case.
+pub(crate) fn check_camera_setup(
+    added_query: Query<(), Added<Camera>>,
+    camera_2d_query: Query<(), (With<Camera>, With<Camera2d>)>,
+    camera_3d_query: Query<(), (With<Camera>, With<Camera3d>)>,
+) {
+    if added_query.is_empty() {
+        return;
+    }
+
+    let cameras_2d = camera_2d_query.iter().count();
+    let cameras_3d = camera_3d_query.iter().count();
+
+    if cameras_2d == 0 && cameras_3d > 0 {
+        warn!("Found a 3D camera but no 2D camera, the letterboxes won't be drawn and 3D content will show in the bar area");
+    }
+    if cameras_2d > 1 {
+        warn!("Found {} 2D cameras but only one is rendered, the letterboxes may be framed for the wrong one", cameras_2d);
+    }
+}
+
+// Resource with the managed camera's position in the plane, once it has stopped moving for the frame.
+// The layout is relative to the camera, so the sprites drawing the letterboxes and their overlays are
+// placed at this position plus their part of the layout, and follow the camera when it's clamped,
+// zoomed, panned or snapped. The camera's Transform is read rather than its GlobalTransform, which is a
+// frame behind, so a managed camera with a parent isn't followed.
+#[derive(Default, Clone, Copy, PartialEq, Debug)]
+pub(crate) struct ManagedCameraPosition(pub(crate) Vec2);
+
+// Label for the system updating ManagedCameraPosition. Systems placing sprites run after it.
+#[derive(SystemLabel, Clone, PartialEq, Eq, Hash, Debug)]
+pub(crate) struct TrackManagedCamera;
+
+pub(crate) fn track_managed_camera(
+    managed_camera: Res<ManagedCamera>,
+    camera_query: Query<&Transform, With<Camera>>,
+    mut position: ResMut<ManagedCameraPosition>,
+) {
+    let new_position = managed_camera
+        .entity
+        .and_then(|entity| camera_query.get(entity).ok())
+        .map_or(Vec2::ZERO, |transform| transform.translation.truncate());
+    // Only write on change, so the placement systems can skip frames where the camera stayed put.
+    if position.0 != new_position {
+        position.0 = new_position;
+    }
+}
+
+// When the managed window is closed, e.g. to recreate it with new display settings, the next window to
+// be created takes its place. Cameras which rendered to the closed window are retargeted at the new one,
+// which makes the layout measure it and apply itself again.
+pub(crate) fn rebind_closed_window(
+    mut created_events: EventReader<WindowCreated>,
+    windows: Res<Windows>,
+    mut managed: ResMut<ManagedCamera>,
+    mut camera_query: Query<&mut Camera>,
+) {
+    let created = match created_events.iter().last() {
+        Some(event) => event.id,
+        None => return,
+    };
+    let closed = managed.window;
+    if created == closed || windows.get(closed).is_some() {
+        return;
+    }
+
+    info!("The letterboxes' window was closed, moving to the newly created window");
+    managed.window = created;
+    for mut camera in camera_query.iter_mut() {
+        if camera.target == RenderTarget::Window(closed) {
+            camera.target = RenderTarget::Window(created);
+        }
+    }
+}
+
+// Bevy 0.7 only renders the active camera of each camera type, so the extra cameras the plugin renders,
+// like the aspect preview's or the background camera, each get their own marker type and a driver which
+// draws the active camera of that type like a 2D camera before the main pass.
+pub(crate) fn add_extra_camera<M: Component + Default>(app: &mut App, driver: &'static str) {
+    app.add_plugin(CameraTypePlugin::<M>::default());
+    // Headless apps have no renderer.
+    let render_app = match app.get_sub_app_mut(RenderApp) {
+        Ok(render_app) => render_app,
+        Err(_) => return,
+    };
+    render_app.add_system_to_stage(RenderStage::Extract, extract_extra_camera_phase::<M>);
+    let mut graph = render_app.world.get_resource_mut::<RenderGraph>().unwrap();
+    graph.add_node(driver, ExtraCameraDriver::<M>(PhantomData));
+    graph.add_node_edge(node::MAIN_PASS_DEPENDENCIES, driver).unwrap();
+    graph.add_node_edge(node::CLEAR_PASS_DRIVER, driver).unwrap();
+    graph.add_node_edge(driver, node::MAIN_PASS_DRIVER).unwrap();
+}
+
+// A 2D camera marked as an extra camera instead of with Camera2d, so it's neither the camera the main
+// pass draws nor one the plugin adopts as the managed camera.
+pub(crate) fn extra_camera_bundle<M: Component + Default>(target: RenderTarget) -> OrthographicCameraBundle<M> {
+    let bundle = OrthographicCameraBundle::new_2d();
+    OrthographicCameraBundle {
+        camera: Camera {
+            target,
+            ..bundle.camera
+        },
+        orthographic_projection: bundle.orthographic_projection,
+        visible_entities: bundle.visible_entities,
+        frustum: bundle.frustum,
+        transform: bundle.transform,
+        global_transform: bundle.global_transform,
+        marker: M::default(),
+    }
+}
+
+fn extract_extra_camera_phase<M: Component + Default>(mut commands: Commands, active_camera: Res<ActiveCamera<M>>) {
+    if let Some(entity) = active_camera.get() {
+        commands.get_or_spawn(entity).insert(RenderPhase::<Transparent2d>::default());
+    }
+}
+
+struct ExtraCameraDriver<M: Component + Default>(PhantomData<M>);
+
+impl<M: Component + Default> Node for ExtraCameraDriver<M> {
+    fn run(
+        &self,
+        graph: &mut RenderGraphContext,
+        _render_context: &mut RenderContext,
+        world: &World,
+    ) -> Result<(), NodeRunError> {
+        // The extracted ActiveCamera is only there once the camera type has been extracted.
+        if let Some(camera) = world.get_resource::<ActiveCamera<M>>().and_then(|active| active.get()) {
+            graph.run_sub_graph(draw_2d_graph::NAME, vec![SlotValue::Entity(camera)])?;
+        }
+        Ok(())
+    }
+}
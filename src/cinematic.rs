@@ -0,0 +1,199 @@
+use bevy::prelude::*;
+
+use crate::camera::ManagedCameraPosition;
+use crate::{
+    DefaultLetterboxEasing, FittedLayout, LetterboxEasing, LetterboxRect, LetterboxSide, ReduceMotion, LETTERBOX_Z,
+};
+
+// Resource for cinematic bars which cover the top and bottom of the safe area, drawn over the
+// letterboxes. The fraction is how much of the safe area's height each bar covers. While they're up the
+// layout's safe area stops at them and the letterboxes grow under them, see BarCoverage.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct CinematicBars {
+    pub fraction: f32,
+    pub color: Color,
+}
+
+impl Default for CinematicBars {
+    fn default() -> Self {
+        Self {
+            fraction: 0.0,
+            color: Color::BLACK,
+        }
+    }
+}
+
+// Component for identifying cinematic bar entities.
+#[derive(Component)]
+pub struct CinematicBar { pub side: LetterboxSide }
+
+// Height of the cinematic bars above the letterboxes, which grow under them, and the style's bar layers.
+const CINEMATIC_Z_OFFSET: f32 = 0.08;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct LetterboxKeyframe {
+    // Seconds from the start of the track.
+    pub time: f32,
+    pub fraction: f32,
+    pub color: Color,
+}
+
+// Keyframes for the cinematic bars. Keyframes must be sorted by time. Each segment between two
+// keyframes is eased, with DefaultLetterboxEasing when the track doesn't set its own.
+#[derive(Default, Clone, PartialEq, Debug)]
+pub struct LetterboxTrack {
+    pub keyframes: Vec<LetterboxKeyframe>,
+    pub easing: Option<LetterboxEasing>,
+}
+
+impl LetterboxTrack {
+    pub fn duration(&self) -> f32 {
+        self.keyframes.last().map_or(0.0, |keyframe| keyframe.time)
+    }
+
+    // The bar fraction and color at a time without interpolating, i.e. the last keyframe reached.
+    pub fn sample_stepped(&self, time: f32) -> Option<(f32, Color)> {
+        self.keyframes
+            .iter()
+            .rev()
+            .find(|keyframe| keyframe.time <= time)
+            .or_else(|| self.keyframes.first())
+            .map(|keyframe| (keyframe.fraction, keyframe.color))
+    }
+
+    // The bar fraction and color at a time, holding the first and last keyframes outside the track.
+    // Tracks without their own easing use the default, as when they're played.
+    pub fn sample(&self, time: f32, default_easing: &DefaultLetterboxEasing) -> Option<(f32, Color)> {
+        self.sample_eased(time, self.easing.unwrap_or(default_easing.0))
+    }
+
+    fn sample_eased(&self, time: f32, easing: LetterboxEasing) -> Option<(f32, Color)> {
+        let next_index = self.keyframes.iter().position(|keyframe| keyframe.time > time);
+        match next_index {
+            Some(0) => self.keyframes.first().map(|keyframe| (keyframe.fraction, keyframe.color)),
+            Some(index) => {
+                let from = &self.keyframes[index - 1];
+                let to = &self.keyframes[index];
+                let t = easing.ease((time - from.time) / (to.time - from.time));
+                Some((from.fraction + (to.fraction - from.fraction) * t, lerp_color(from.color, to.color, t)))
+            },
+            None => self.keyframes.last().map(|keyframe| (keyframe.fraction, keyframe.color)),
+        }
+    }
+}
+
+pub(crate) fn lerp_color(from: Color, to: Color, t: f32) -> Color {
+    let from = from.as_rgba_f32();
+    let to = to.as_rgba_f32();
+    let mix = |i: usize| from[i] + (to[i] - from[i]) * t;
+    Color::rgba(mix(0), mix(1), mix(2), mix(3))
+}
+
+// Resource which plays a LetterboxTrack on the cinematic bars.
+#[derive(Default)]
+pub struct LetterboxTrackPlayer {
+    track: Option<LetterboxTrack>,
+    time: f32,
+    playing: bool,
+}
+
+impl LetterboxTrackPlayer {
+    pub fn start(&mut self, track: LetterboxTrack) {
+        self.track = Some(track);
+        self.time = 0.0;
+        self.playing = true;
+    }
+
+    // Stops playback, leaving the cinematic bars where they are. No end event is sent.
+    pub fn stop(&mut self) {
+        self.playing = false;
+    }
+
+    pub fn seek(&mut self, time: f32) {
+        self.time = time.max(0.0);
+    }
+
+    pub fn time(&self) -> f32 {
+        self.time
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.playing
+    }
+}
+
+// Event sent when a track plays past its last keyframe.
+pub struct LetterboxTrackFinished;
+
+pub(crate) fn play_letterbox_track(
+    time: Res<Time>,
+    reduce_motion: Res<ReduceMotion>,
+    default_easing: Res<DefaultLetterboxEasing>,
+    mut player: ResMut<LetterboxTrackPlayer>,
+    mut cinematic_bars: ResMut<CinematicBars>,
+    mut finished_events: EventWriter<LetterboxTrackFinished>,
+) {
+    if !player.playing {
+        return;
+    }
+    player.time += time.delta_seconds();
+
+    let player = &mut *player;
+    let track = match &player.track {
+        Some(track) => track,
+        None => return,
+    };
+    // With reduced motion the bars jump between keyframes instead of moving.
+    let sample = if reduce_motion.0 {
+        track.sample_stepped(player.time)
+    } else {
+        track.sample(player.time, &default_easing)
+    };
+    if let Some((fraction, color)) = sample {
+        cinematic_bars.fraction = fraction;
+        cinematic_bars.color = color;
+    }
+    if player.time >= track.duration() {
+        player.playing = false;
+        finished_events.send(LetterboxTrackFinished);
+    }
+}
+
+pub(crate) fn spawn_cinematic_bars(mut commands: Commands) {
+    for side in [LetterboxSide::Top, LetterboxSide::Bottom] {
+        commands
+            .spawn_bundle(SpriteBundle {
+                sprite: Sprite {
+                    custom_size: Some(Vec2::ZERO),
+                    ..default()
+                },
+                ..default()
+            })
+            .insert(CinematicBar { side });
+    }
+}
+
+pub(crate) fn update_cinematic_bars(
+    cinematic_bars: Res<CinematicBars>,
+    fitted: Res<FittedLayout>,
+    camera_position: Res<ManagedCameraPosition>,
+    mut bar_query: Query<(&CinematicBar, &mut Transform, &mut Sprite)>,
+) {
+    if !cinematic_bars.is_changed() && !fitted.is_changed() && !camera_position.is_changed() {
+        return;
+    }
+
+    // The fraction is of the safe area before the bars cut into it.
+    let safe = fitted.0.safe_area;
+    let height = safe.size().y * cinematic_bars.fraction.clamp(0.0, 0.5);
+    for (bar, mut transform, mut sprite) in bar_query.iter_mut() {
+        let rect = match bar.side {
+            LetterboxSide::Top => LetterboxRect::new(Vec2::new(safe.min.x, safe.max.y - height), safe.max),
+            _ => LetterboxRect::new(safe.min, Vec2::new(safe.max.x, safe.min.y + height)),
+        };
+        let center = camera_position.0 + rect.center();
+        sprite.color = cinematic_bars.color;
+        sprite.custom_size = Some(rect.size());
+        transform.translation = Vec3::new(center.x, center.y, LETTERBOX_Z + CINEMATIC_Z_OFFSET);
+    }
+}
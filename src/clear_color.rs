@@ -0,0 +1,46 @@
+use bevy::prelude::*;
+
+use crate::{LetterboxBackend, LetterboxStyle, TransparentLetterboxes};
+
+// Resource for letting the letterboxes own the global ClearColor, so any pixel neither a bar nor the
+// world covers for a frame, e.g. mid resize, looks like a bar instead of the default grey. Bevy clears
+// every camera target with the same color, so this also becomes the background of the world. The
+// original color is restored when it's turned off. TransparentLetterboxes takes over while it's active.
+// Unless enabled is set, it's on with the render texture backend, where the window around the image is
+// only ever cleared, and off with the others.
+#[derive(Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct LetterboxClearColor {
+    pub enabled: Option<bool>,
+}
+
+impl LetterboxClearColor {
+    pub fn is_enabled(&self, backend: &LetterboxBackend) -> bool {
+        self.enabled.unwrap_or(matches!(backend, LetterboxBackend::RenderTexture(_)))
+    }
+}
+
+pub(crate) fn sync_clear_color(
+    ownership: Res<LetterboxClearColor>,
+    style: Res<LetterboxStyle>,
+    transparency: Res<TransparentLetterboxes>,
+    backend: Res<LetterboxBackend>,
+    mut clear_color: ResMut<ClearColor>,
+    mut original: Local<Option<Color>>,
+) {
+    if transparency.is_active(&backend) {
+        return;
+    }
+    if !ownership.is_enabled(&backend) {
+        if let Some(color) = original.take() {
+            clear_color.0 = color;
+        }
+        return;
+    }
+
+    if original.is_none() {
+        *original = Some(clear_color.0);
+    }
+    if clear_color.0 != style.color {
+        clear_color.0 = style.color;
+    }
+}
@@ -0,0 +1,85 @@
+use bevy::prelude::*;
+
+use crate::{LetterboxLayout, LetterboxRect, ManagedCamera};
+
+// Resource for keeping the OS cursor inside the safe area, e.g. for edge scrolling. Bevy can only grab
+// the cursor to the whole window, so the cursor is also moved back into the safe area every frame it
+// strays onto a letterbox. On Windows and X11 the grab keeps it inside the window, so it only ever
+// strays as far as the bars. On macOS a grab freezes the cursor in place and on Wayland it's up to
+// the compositor, so only the moving back is used there, and a fast flick can briefly leave the window.
+// Confinement is released while the window is unfocused and when it's disabled.
+#[derive(Default)]
+pub struct CursorConfinement {
+    pub enabled: bool,
+    method: ConfinementMethod,
+}
+
+impl CursorConfinement {
+    // How the cursor is currently being confined, if at all.
+    pub fn method(&self) -> ConfinementMethod {
+        self.method
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.method != ConfinementMethod::None
+    }
+}
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub enum ConfinementMethod {
+    #[default]
+    None,
+    // The window grabs the cursor and it's moved back into the safe area.
+    GrabAndClamp,
+    // The cursor is only moved back into the safe area.
+    Clamp,
+}
+
+// Whether grabbing the cursor keeps it inside the window without freezing it.
+const GRAB_CONFINES: bool = cfg!(any(target_os = "windows", target_os = "linux"));
+
+pub(crate) fn confine_cursor(
+    mut confinement: ResMut<CursorConfinement>,
+    layout: Res<LetterboxLayout>,
+    managed_camera: Res<ManagedCamera>,
+    mut windows: ResMut<Windows>,
+) {
+    let window = match windows.get_mut(managed_camera.window) {
+        Some(window) => window,
+        None => {
+            if confinement.method != ConfinementMethod::None {
+                confinement.method = ConfinementMethod::None;
+            }
+            return;
+        },
+    };
+
+    let method = match (confinement.enabled && window.is_focused(), GRAB_CONFINES) {
+        (false, _) => ConfinementMethod::None,
+        (true, true) => ConfinementMethod::GrabAndClamp,
+        (true, false) => ConfinementMethod::Clamp,
+    };
+    // Only grab and release on changes, so a grab made by other code is left alone otherwise.
+    if method != confinement.method {
+        if method == ConfinementMethod::GrabAndClamp {
+            window.set_cursor_lock_mode(true);
+        } else if confinement.method == ConfinementMethod::GrabAndClamp {
+            window.set_cursor_lock_mode(false);
+        }
+        confinement.method = method;
+    }
+    if method == ConfinementMethod::None {
+        return;
+    }
+
+    // Cursor positions are logical pixels from the bottom left of the window.
+    let safe = layout
+        .safe_area_px
+        .intersect(LetterboxRect::new(Vec2::ZERO, layout.window_size))
+        .scaled(1.0 / layout.dpi_scale);
+    if let Some(position) = window.cursor_position() {
+        if !safe.contains(position) {
+            window.set_cursor_position(position.clamp(safe.min, safe.max));
+        }
+    }
+}
@@ -0,0 +1,216 @@
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+use crate::{CinematicBars, FittedLayout, LetterboxLayout, LetterboxRect, LetterboxSide};
+
+// Something covering the edges of the window. The built in sources are kept up to date by the plugin,
+// and games can add their own, e.g. a dialogue panel rising from the bottom.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum CoverageSource {
+    // The letterboxes fitting ScreenUnits into the window, which include the insets.
+    Aspect,
+    // Platform insets and reserved bands on their own.
+    Insets,
+    // The cinematic bars, while their fraction is above zero.
+    Cinematic,
+    Custom(&'static str),
+}
+
+// How far in from each edge of the window something covers, in world units.
+#[derive(Default, Clone, Copy, PartialEq, Debug)]
+pub struct SideCoverage {
+    pub left: f32,
+    pub right: f32,
+    pub top: f32,
+    pub bottom: f32,
+}
+
+impl SideCoverage {
+    pub fn get(&self, side: LetterboxSide) -> f32 {
+        match side {
+            LetterboxSide::Left => self.left,
+            LetterboxSide::Right => self.right,
+            LetterboxSide::Top => self.top,
+            LetterboxSide::Bottom => self.bottom,
+        }
+    }
+
+    // The larger coverage of each side.
+    pub fn max(&self, other: &SideCoverage) -> SideCoverage {
+        SideCoverage {
+            left: self.left.max(other.left),
+            right: self.right.max(other.right),
+            top: self.top.max(other.top),
+            bottom: self.bottom.max(other.bottom),
+        }
+    }
+
+    // The coverage leaving a rectangle uncovered inside an area.
+    pub fn around(area: LetterboxRect, inner: LetterboxRect) -> SideCoverage {
+        SideCoverage {
+            left: (inner.min.x - area.min.x).max(0.0),
+            right: (area.max.x - inner.max.x).max(0.0),
+            top: (area.max.y - inner.max.y).max(0.0),
+            bottom: (inner.min.y - area.min.y).max(0.0),
+        }
+    }
+}
+
+// Resource combining every source covering the window's edges. Sources are measured from the window
+// edge, so they overlap rather than stack, and each side is covered as far as its furthest reaching
+// source. A source meant to sit next to another includes the other's coverage in its own. Sources can
+// be set every frame to animate them, and removed independently of each other.
+//
+// The layout is fitted around the letterboxes and insets, and the cinematic bars and custom sources
+// then cut into it: LetterboxLayout's framed and safe areas stop where they reach, the letterboxes grow
+// over them and LetterboxLayoutChanged carries the result. The render texture backend's image always
+// holds the whole safe area, so there they only cover it.
+#[derive(Default)]
+pub struct BarCoverage {
+    sources: HashMap<CoverageSource, SideCoverage>,
+    visible: LetterboxRect,
+    combined: SideCoverage,
+}
+
+impl BarCoverage {
+    pub fn set(&mut self, source: CoverageSource, coverage: SideCoverage) {
+        if self.sources.get(&source) != Some(&coverage) {
+            self.sources.insert(source, coverage);
+            self.combine();
+        }
+    }
+
+    pub fn remove(&mut self, source: CoverageSource) {
+        if self.sources.remove(&source).is_some() {
+            self.combine();
+        }
+    }
+
+    pub fn source(&self, source: CoverageSource) -> Option<SideCoverage> {
+        self.sources.get(&source).copied()
+    }
+
+    pub fn combined(&self) -> SideCoverage {
+        self.combined
+    }
+
+    // The part of the window no source covers, in world units relative to the camera. It never
+    // extends past the layout's framed area, since the letterboxes are a source.
+    pub fn uncovered(&self) -> LetterboxRect {
+        let combined = self.combined;
+        let min = self.visible.min + Vec2::new(combined.left, combined.bottom);
+        let max = self.visible.max - Vec2::new(combined.right, combined.top);
+        LetterboxRect::new(min, max.max(min))
+    }
+
+    // How far the cinematic bars and custom sources reach in from the edges of a fitted layout.
+    pub(crate) fn extra(&self, layout: &LetterboxLayout, cinematic_bars: &CinematicBars) -> SideCoverage {
+        let custom = self
+            .sources
+            .iter()
+            .filter(|(source, _)| matches!(source, CoverageSource::Custom(_)))
+            .fold(SideCoverage::default(), |combined, (_, coverage)| combined.max(coverage));
+        cinematic_coverage(layout, cinematic_bars).map_or(custom, |cinematic| cinematic.max(&custom))
+    }
+
+    fn combine(&mut self) {
+        self.combined = self.sources.values().fold(SideCoverage::default(), |combined, coverage| combined.max(coverage));
+    }
+}
+
+// Event sent when the part of the window no source covers changes, after the layout is applied.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct CoverageChanged {
+    pub old: LetterboxRect,
+    pub new: LetterboxRect,
+}
+
+// The cinematic bars' coverage, placed the same way as their sprites, while their fraction is above zero.
+fn cinematic_coverage(layout: &LetterboxLayout, cinematic_bars: &CinematicBars) -> Option<SideCoverage> {
+    let fraction = cinematic_bars.fraction.clamp(0.0, 0.5);
+    if fraction <= 0.0 {
+        return None;
+    }
+    let visible = layout.visible;
+    let safe = layout.safe_area;
+    let height = safe.size().y * fraction;
+    let inner = LetterboxRect::new(
+        Vec2::new(visible.min.x, safe.min.y + height),
+        Vec2::new(visible.max.x, safe.max.y - height),
+    );
+    Some(SideCoverage::around(visible, inner))
+}
+
+// Shrinks a fitted layout's framed and safe areas to what extra coverage leaves uncovered.
+pub(crate) fn cover_layout(layout: LetterboxLayout, extra: SideCoverage) -> LetterboxLayout {
+    if extra == SideCoverage::default() {
+        return layout;
+    }
+    let visible = layout.visible;
+    let cover = |rect: LetterboxRect| {
+        let mut min = rect.min;
+        let mut max = rect.max;
+        if extra.left > 0.0 {
+            min.x = min.x.max(visible.min.x + extra.left);
+        }
+        if extra.right > 0.0 {
+            max.x = max.x.min(visible.max.x - extra.right);
+        }
+        if extra.top > 0.0 {
+            max.y = max.y.min(visible.max.y - extra.top);
+        }
+        if extra.bottom > 0.0 {
+            min.y = min.y.max(visible.min.y + extra.bottom);
+        }
+        LetterboxRect::new(min, max.max(min))
+    };
+    let safe_area = cover(layout.safe_area);
+    LetterboxLayout {
+        safe_area,
+        safe_area_px: layout.to_window_px(safe_area),
+        framed: cover(layout.framed),
+        ..layout
+    }
+}
+
+pub(crate) fn update_bar_coverage(
+    fitted: Res<FittedLayout>,
+    cinematic_bars: Res<CinematicBars>,
+    mut coverage: ResMut<BarCoverage>,
+    mut changed_events: EventWriter<CoverageChanged>,
+    mut last_uncovered: Local<LetterboxRect>,
+) {
+    if fitted.is_changed() || cinematic_bars.is_changed() {
+        let layout = &fitted.0;
+        let visible = layout.visible;
+        if coverage.visible != visible {
+            coverage.visible = visible;
+        }
+        coverage.set(CoverageSource::Aspect, SideCoverage::around(visible, layout.framed));
+
+        let pixels_per_unit = layout.pixels_per_unit_axes();
+        coverage.set(CoverageSource::Insets, SideCoverage {
+            left: layout.insets.left / pixels_per_unit.x,
+            right: layout.insets.right / pixels_per_unit.x,
+            top: layout.insets.top / pixels_per_unit.y,
+            bottom: layout.insets.bottom / pixels_per_unit.y,
+        });
+
+        match cinematic_coverage(layout, &cinematic_bars) {
+            Some(cinematic) => coverage.set(CoverageSource::Cinematic, cinematic),
+            None => coverage.remove(CoverageSource::Cinematic),
+        }
+    }
+
+    if !coverage.is_changed() {
+        return;
+    }
+    let uncovered = coverage.uncovered();
+    if *last_uncovered != uncovered {
+        changed_events.send(CoverageChanged {
+            old: *last_uncovered,
+            new: uncovered,
+        });
+        *last_uncovered = uncovered;
+    }
+}
@@ -0,0 +1,137 @@
+use bevy::prelude::*;
+
+use crate::camera::ManagedCameraPosition;
+use crate::{LetterboxLayout, LetterboxRect, LETTERBOX_Z};
+
+// Resource for a gamepad driven cursor, e.g. for couch UI. It's kept inside the part of the safe area
+// shown in the window, inset by margin, so it never drifts onto the letterboxes. Layout changes clamp
+// it again. Any connected gamepad's stick moves it.
+pub struct VirtualCursor {
+    pub enabled: bool,
+    // In world units relative to the camera.
+    pub position: Vec2,
+    pub x_axis: GamepadAxisType,
+    pub y_axis: GamepadAxisType,
+    // Stick deflections below this are ignored.
+    pub dead_zone: f32,
+    // Top speed in world units per second at full deflection.
+    pub max_speed: f32,
+    // World units per second squared. Zero or less reaches the stick's speed immediately.
+    pub acceleration: f32,
+    // Distance kept from the edges of the safe area, in world units.
+    pub margin: f32,
+    // Size and color of the sprite showing the cursor, in world units.
+    pub size: f32,
+    pub color: Color,
+    velocity: Vec2,
+}
+
+impl Default for VirtualCursor {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            position: Vec2::ZERO,
+            x_axis: GamepadAxisType::LeftStickX,
+            y_axis: GamepadAxisType::LeftStickY,
+            dead_zone: 0.15,
+            max_speed: 10.0,
+            acceleration: 40.0,
+            margin: 0.0,
+            size: 0.25,
+            color: Color::WHITE,
+            velocity: Vec2::ZERO,
+        }
+    }
+}
+
+impl VirtualCursor {
+    // The area the cursor is kept in, in world units relative to the camera.
+    pub fn bounds(&self, layout: &LetterboxLayout) -> LetterboxRect {
+        let shown = layout.shown_safe_area();
+        let margin = Vec2::splat(self.margin.max(0.0)).min(shown.size() / 2.0);
+        LetterboxRect::new(shown.min + margin, shown.max - margin)
+    }
+
+    // The cursor in logical pixels from the bottom left of the window, for placing UI.
+    pub fn window_position(&self, layout: &LetterboxLayout) -> Vec2 {
+        (layout.origin_px() + self.position * layout.pixels_per_unit_axes()) / layout.dpi_scale
+    }
+}
+
+// Component for identifying the entity which shows the virtual cursor.
+#[derive(Component)]
+pub struct VirtualCursorSprite;
+
+// Height of the cursor above the letterboxes, over every other overlay.
+const CURSOR_Z_OFFSET: f32 = 0.9;
+
+pub(crate) fn spawn_virtual_cursor(mut commands: Commands) {
+    commands
+        .spawn_bundle(SpriteBundle {
+            sprite: Sprite {
+                custom_size: Some(Vec2::ZERO),
+                ..default()
+            },
+            visibility: Visibility { is_visible: false },
+            ..default()
+        })
+        .insert(VirtualCursorSprite);
+}
+
+pub(crate) fn move_virtual_cursor(
+    time: Res<Time>,
+    gamepads: Res<Gamepads>,
+    axes: Res<Axis<GamepadAxis>>,
+    layout: Res<LetterboxLayout>,
+    mut cursor: ResMut<VirtualCursor>,
+) {
+    if !cursor.enabled {
+        return;
+    }
+
+    // The most deflected stick wins when several gamepads are connected.
+    let stick = gamepads
+        .iter()
+        .map(|gamepad| {
+            Vec2::new(
+                axes.get(GamepadAxis(*gamepad, cursor.x_axis)).unwrap_or(0.0),
+                axes.get(GamepadAxis(*gamepad, cursor.y_axis)).unwrap_or(0.0),
+            )
+        })
+        .fold(Vec2::ZERO, |most, stick| if stick.length() > most.length() { stick } else { most });
+    let stick = if stick.length() < cursor.dead_zone { Vec2::ZERO } else { stick.clamp_length_max(1.0) };
+
+    let delta = time.delta_seconds();
+    let target_velocity = stick * cursor.max_speed;
+    let velocity = if cursor.acceleration <= 0.0 {
+        target_velocity
+    } else {
+        let change = target_velocity - cursor.velocity;
+        cursor.velocity + change.clamp_length_max(cursor.acceleration * delta)
+    };
+
+    let bounds = cursor.bounds(&layout);
+    let position = (cursor.position + velocity * delta).clamp(bounds.min, bounds.max);
+    // Stop at the edges rather than building up speed against them.
+    let velocity = if position == cursor.position { Vec2::ZERO } else { velocity };
+
+    // Only write on change so UI can use change detection on the cursor.
+    if cursor.position != position || cursor.velocity != velocity {
+        cursor.position = position;
+        cursor.velocity = velocity;
+    }
+}
+
+pub(crate) fn update_virtual_cursor_sprite(
+    cursor: Res<VirtualCursor>,
+    camera_position: Res<ManagedCameraPosition>,
+    mut sprite_query: Query<(&mut Transform, &mut Sprite, &mut Visibility), With<VirtualCursorSprite>>,
+) {
+    for (mut transform, mut sprite, mut visibility) in sprite_query.iter_mut() {
+        let position = camera_position.0 + cursor.position;
+        visibility.is_visible = cursor.enabled;
+        sprite.color = cursor.color;
+        sprite.custom_size = Some(Vec2::splat(cursor.size));
+        transform.translation = Vec3::new( position.x, position.y, LETTERBOX_Z + CURSOR_Z_OFFSET );
+    }
+}
@@ -0,0 +1,289 @@
+use bevy::prelude::*;
+
+use crate::camera::ManagedCameraPosition;
+use crate::{FillMode, LetterboxBackend, LetterboxLayout, LetterboxRect, LetterboxSide, ManagedCamera, SafeAnchor};
+
+// Aspect ratios which can be simulated with the number keys 1 to 5.
+pub const SIMULATED_ASPECT_RATIOS: [(f32, f32); 5] = [
+    (4.0, 3.0),
+    (16.0, 10.0),
+    (16.0, 9.0),
+    (21.0, 9.0),
+    (32.0, 9.0),
+];
+
+const ASPECT_RATIO_KEYS: [KeyCode; 5] = [
+    KeyCode::Key1,
+    KeyCode::Key2,
+    KeyCode::Key3,
+    KeyCode::Key4,
+    KeyCode::Key5,
+];
+
+// Resource controlling the aspect ratio hotkeys. Setting a cycle timer steps through every ratio
+// automatically, which is useful for soak testing.
+#[derive(Default)]
+pub struct AspectRatioSimulator {
+    pub cycle_timer: Option<Timer>,
+    next_index: usize,
+    log_next_layout: bool,
+}
+
+impl AspectRatioSimulator {
+    pub fn cycling(seconds: f32) -> Self {
+        Self {
+            cycle_timer: Some(Timer::from_seconds(seconds, true)),
+            ..default()
+        }
+    }
+}
+
+pub(crate) fn simulate_aspect_ratios(
+    keys: Res<Input<KeyCode>>,
+    time: Res<Time>,
+    mut simulator: ResMut<AspectRatioSimulator>,
+    mut windows: ResMut<Windows>,
+) {
+    let mut selected = ASPECT_RATIO_KEYS.iter().position(|key| keys.just_pressed(*key));
+
+    if let Some(timer) = simulator.cycle_timer.as_mut() {
+        if timer.tick(time.delta()).just_finished() {
+            selected = Some(simulator.next_index);
+        }
+    }
+
+    let index = match selected {
+        Some(index) => index,
+        None => return,
+    };
+    let window = match windows.get_primary_mut() {
+        Some(window) => window,
+        None => return,
+    };
+
+    // Keep the current height and derive the width from the ratio.
+    let (ratio_width, ratio_height) = SIMULATED_ASPECT_RATIOS[index];
+    let height = window.height();
+    window.set_resolution(height * ratio_width / ratio_height, height);
+    info!("Simulating a {}:{} window", ratio_width, ratio_height);
+
+    simulator.next_index = (index + 1) % SIMULATED_ASPECT_RATIOS.len();
+    simulator.log_next_layout = true;
+}
+
+// The window is resized asynchronously, so the layout is logged once it has been recomputed.
+pub(crate) fn log_simulated_layout(
+    layout: Res<LetterboxLayout>,
+    mut simulator: ResMut<AspectRatioSimulator>,
+) {
+    if simulator.log_next_layout && layout.is_changed() {
+        info!("Simulated layout: {:?}", *layout);
+        simulator.log_next_layout = false;
+    }
+}
+
+// Resource for a grid of world unit lines over the safe area, for placing level geometry. Lines are
+// drawn above gameplay and below the letterboxes, every spacing units from the world origin, with every
+// major_every'th line drawn in the major style. Toggle it with enabled.
+pub struct UnitGrid {
+    pub enabled: bool,
+    pub spacing: f32,
+    pub major_every: u32,
+    pub minor_color: Color,
+    pub major_color: Color,
+    // Line thicknesses in logical pixels, so lines stay crisp at any window size.
+    pub minor_thickness: f32,
+    pub major_thickness: f32,
+}
+
+impl Default for UnitGrid {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            spacing: 1.0,
+            major_every: 5,
+            minor_color: Color::rgba(1.0, 1.0, 1.0, 0.15),
+            major_color: Color::rgba(1.0, 1.0, 1.0, 0.4),
+            minor_thickness: 1.0,
+            major_thickness: 2.0,
+        }
+    }
+}
+
+// Component for identifying the entities which draw the unit grid.
+#[derive(Component)]
+pub struct UnitGridLine;
+
+// Keeps a dense grid from spawning an unbounded number of sprites.
+const MAX_GRID_LINES: usize = 512;
+
+pub(crate) fn update_unit_grid(
+    mut commands: Commands,
+    grid: Res<UnitGrid>,
+    layout: Res<LetterboxLayout>,
+    camera_position: Res<ManagedCameraPosition>,
+    mut line_query: Query<(Entity, &mut Transform, &mut Sprite), With<UnitGridLine>>,
+) {
+    let camera_position = camera_position.0;
+
+    // Lines in world units, spanning the part of the safe area in the window.
+    let mut lines = Vec::new();
+    if grid.enabled && grid.spacing > 0.0 && layout.pixels_per_unit > 0.0 {
+        let shown = layout.shown_safe_area();
+        let area = LetterboxRect::new(shown.min + camera_position, shown.max + camera_position);
+        let units_per_logical = layout.dpi_scale / layout.pixels_per_unit;
+        let style = |index: i64| {
+            if grid.major_every > 0 && index % grid.major_every as i64 == 0 {
+                (grid.major_color, grid.major_thickness * units_per_logical)
+            } else {
+                (grid.minor_color, grid.minor_thickness * units_per_logical)
+            }
+        };
+        for index in (area.min.x / grid.spacing).ceil() as i64..=(area.max.x / grid.spacing).floor() as i64 {
+            let (color, thickness) = style(index);
+            let x = index as f32 * grid.spacing;
+            let rect = LetterboxRect::new(Vec2::new(x - thickness / 2.0, area.min.y), Vec2::new(x + thickness / 2.0, area.max.y));
+            lines.push((rect, color));
+        }
+        for index in (area.min.y / grid.spacing).ceil() as i64..=(area.max.y / grid.spacing).floor() as i64 {
+            let (color, thickness) = style(index);
+            let y = index as f32 * grid.spacing;
+            let rect = LetterboxRect::new(Vec2::new(area.min.x, y - thickness / 2.0), Vec2::new(area.max.x, y + thickness / 2.0));
+            lines.push((rect, color));
+        }
+        lines.truncate(MAX_GRID_LINES);
+    }
+
+    // Reuse the existing line entities, spawning or despawning only the difference.
+    let mut lines = lines.into_iter();
+    for (entity, mut transform, mut sprite) in line_query.iter_mut() {
+        match lines.next() {
+            Some((rect, color)) => {
+                let center = rect.center();
+                sprite.color = color;
+                sprite.custom_size = Some(rect.size());
+                transform.translation = Vec3::new( center.x, center.y, 998.2 );
+            },
+            None => commands.entity(entity).despawn(),
+        }
+    }
+    for (rect, color) in lines {
+        let center = rect.center();
+        commands
+            .spawn_bundle(SpriteBundle {
+                sprite: Sprite {
+                    color,
+                    custom_size: Some(rect.size()),
+                    ..default()
+                },
+                transform: Transform::from_xyz(center.x, center.y, 998.2),
+                ..default()
+            })
+            .insert(UnitGridLine);
+    }
+}
+
+// Resource for a text readout of the cursor's world position, pixels per unit, fill mode, backend and
+// bar sizes, updated live. Everything shown comes from the LetterboxLayout conversions, so it doubles as
+// a check on them. Bevy has no built in font, so nothing is shown until one is set, and bevy_ui needs a
+// UI camera, which only the render texture backend spawns.
+pub struct CoordinateReadout {
+    pub enabled: bool,
+    pub font: Option<Handle<Font>>,
+    pub font_size: f32,
+    pub color: Color,
+    // Where the readout's bottom left corner sits in the safe area.
+    pub anchor: SafeAnchor,
+}
+
+impl Default for CoordinateReadout {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            font: None,
+            font_size: 16.0,
+            color: Color::WHITE,
+            anchor: SafeAnchor::percent(0.01, 0.8),
+        }
+    }
+}
+
+// Component for identifying the coordinate readout's text entity.
+#[derive(Component)]
+pub struct CoordinateReadoutText;
+
+pub(crate) fn update_coordinate_readout(
+    mut commands: Commands,
+    readout: Res<CoordinateReadout>,
+    layout: Res<LetterboxLayout>,
+    fill_mode: Res<FillMode>,
+    backend: Res<LetterboxBackend>,
+    managed_camera: Res<ManagedCamera>,
+    windows: Res<Windows>,
+    camera_query: Query<&GlobalTransform>,
+    mut text_query: Query<(Entity, &mut Text, &mut SafeAnchor), With<CoordinateReadoutText>>,
+) {
+    let font = match (&readout.font, readout.enabled) {
+        (Some(font), true) => font.clone(),
+        _ => {
+            for (entity, ..) in text_query.iter() {
+                commands.entity(entity).despawn();
+            }
+            return;
+        },
+    };
+
+    let camera_position = managed_camera
+        .entity
+        .and_then(|entity| camera_query.get(entity).ok())
+        .map_or(Vec2::ZERO, |transform| transform.translation.truncate());
+    let cursor = windows
+        .get(managed_camera.window)
+        .and_then(|window| window.cursor_position())
+        .map(|position| camera_position + layout.window_point_to_units(position));
+    let bar = |side: LetterboxSide| layout.bar(side).size();
+    let value = [
+        match cursor {
+            Some(cursor) => format!("cursor: ({:.3}, {:.3})", cursor.x, cursor.y),
+            None => "cursor: outside the window".to_string(),
+        },
+        format!("pixels per unit: {:.3} ({:.4} units per pixel)", layout.pixels_per_unit, 1.0 / layout.pixels_per_unit),
+        format!("fill: {:?}", *fill_mode),
+        format!("backend: {:?}", *backend),
+        format!(
+            "bars: left {:.2}, right {:.2}, top {:.2}, bottom {:.2}",
+            bar(LetterboxSide::Left).x,
+            bar(LetterboxSide::Right).x,
+            bar(LetterboxSide::Top).y,
+            bar(LetterboxSide::Bottom).y,
+        ),
+    ]
+    .join("\n");
+    let style = TextStyle {
+        font,
+        font_size: readout.font_size,
+        color: readout.color,
+    };
+
+    match text_query.get_single_mut() {
+        Ok((_, mut text, mut anchor)) => {
+            let section = &text.sections[0];
+            if section.value != value || section.style.color != style.color || section.style.font_size != style.font_size {
+                text.sections[0].value = value;
+                text.sections[0].style = style;
+            }
+            if *anchor != readout.anchor {
+                *anchor = readout.anchor;
+            }
+        },
+        Err(_) => {
+            commands
+                .spawn_bundle(TextBundle {
+                    text: Text::with_section(value, style, default()),
+                    ..default()
+                })
+                .insert(readout.anchor)
+                .insert(CoordinateReadoutText);
+        },
+    }
+}
@@ -0,0 +1,59 @@
+use bevy::prelude::*;
+
+use crate::ScreenUnits;
+
+// Component for the demo scene's objects, which bounce back and forth across the safe area.
+#[derive(Component)]
+pub struct DemoObject {
+    // World units per second.
+    pub velocity: Vec2,
+}
+
+// How far past the edge of the safe area objects travel under the letterboxes before turning back.
+const OVERSHOOT: f32 = 2.0;
+
+pub(crate) fn spawn_demo_scene(mut commands: Commands) {
+    // One object crosses the left and right edges, one the top and bottom, and one goes diagonally
+    // through the corners.
+    let objects = [
+        (Vec2::new( 10.0, 0.0 ), Color::rgb( 1.0, 1.0, 1.0 )),
+        (Vec2::new( 0.0, 8.0 ), Color::rgb( 1.0, 0.5, 0.5 )),
+        (Vec2::new( 7.0, 5.0 ), Color::rgb( 0.5, 0.5, 1.0 )),
+    ];
+    for (velocity, color) in objects {
+        commands
+            .spawn_bundle(SpriteBundle {
+                sprite: Sprite {
+                    color,
+                    ..default()
+                },
+                transform: Transform::from_translation(Vec3::new( 0.0, 0.0, 10.0 )),
+                ..default()
+            })
+            .insert(DemoObject { velocity });
+    }
+}
+
+// Bounces the demo objects off bounds just outside the safe area, so they render under the letterboxes
+// before coming back. Movement scales with the frame time so it looks the same at any frame rate.
+pub(crate) fn move_demo_objects(
+    time: Res<Time>,
+    screen_units: Res<ScreenUnits>,
+    mut objects: Query<(&mut DemoObject, &mut Transform)>,
+) {
+    let bounds = Vec2::new(screen_units.width, screen_units.height) / 2.0 + OVERSHOOT;
+    for (mut object, mut transform) in objects.iter_mut() {
+        let mut position = transform.translation.truncate() + object.velocity * time.delta_seconds();
+        // Turn back at the bounds, and head back in if ScreenUnits shrank around the object.
+        if position.x.abs() > bounds.x {
+            position.x = position.x.clamp(-bounds.x, bounds.x);
+            object.velocity.x = -object.velocity.x.abs() * position.x.signum();
+        }
+        if position.y.abs() > bounds.y {
+            position.y = position.y.clamp(-bounds.y, bounds.y);
+            object.velocity.y = -object.velocity.y.abs() * position.y.signum();
+        }
+        transform.translation.x = position.x;
+        transform.translation.y = position.y;
+    }
+}
@@ -0,0 +1,75 @@
+// Curves applied to the normalized time of letterbox animations.
+#[derive(Clone, Copy, Default, Debug)]
+pub enum LetterboxEasing {
+    Linear,
+    // Smoothstep: slow at both ends.
+    #[default]
+    EaseInOut,
+    QuadInOut,
+    CubicInOut,
+    SineInOut,
+    // Overshoots the target slightly before settling on it.
+    BackOut,
+    // Any curve mapping 0 to 0 and 1 to 1. Other endpoint values are ignored.
+    Custom(fn(f32) -> f32),
+}
+
+// Custom curves are equal when they're the same function, by address.
+impl PartialEq for LetterboxEasing {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (LetterboxEasing::Custom(a), LetterboxEasing::Custom(b)) => *a as usize == *b as usize,
+            _ => std::mem::discriminant(self) == std::mem::discriminant(other),
+        }
+    }
+}
+
+impl Eq for LetterboxEasing {}
+
+impl LetterboxEasing {
+    // Maps normalized time to progress. Time is clamped to 0 to 1 and the endpoints are exact.
+    pub fn ease(&self, t: f32) -> f32 {
+        if t <= 0.0 {
+            return 0.0;
+        }
+        if t >= 1.0 {
+            return 1.0;
+        }
+        match self {
+            LetterboxEasing::Linear => t,
+            LetterboxEasing::EaseInOut => t * t * (3.0 - 2.0 * t),
+            LetterboxEasing::QuadInOut => {
+                if t < 0.5 {
+                    2.0 * t * t
+                } else {
+                    1.0 - (2.0 - 2.0 * t).powi(2) / 2.0
+                }
+            },
+            LetterboxEasing::CubicInOut => {
+                if t < 0.5 {
+                    4.0 * t * t * t
+                } else {
+                    1.0 - (2.0 - 2.0 * t).powi(3) / 2.0
+                }
+            },
+            LetterboxEasing::SineInOut => (1.0 - (t * std::f32::consts::PI).cos()) / 2.0,
+            LetterboxEasing::BackOut => {
+                const OVERSHOOT: f32 = 1.70158;
+                let u = t - 1.0;
+                1.0 + (OVERSHOOT + 1.0) * u * u * u + OVERSHOOT * u * u
+            },
+            LetterboxEasing::Custom(curve) => curve(t),
+        }
+    }
+}
+
+// Resource holding the easing used by animations which don't set their own: cinematic tracks and
+// theme crossfades. Linear unless changed.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct DefaultLetterboxEasing(pub LetterboxEasing);
+
+impl Default for DefaultLetterboxEasing {
+    fn default() -> Self {
+        DefaultLetterboxEasing(LetterboxEasing::Linear)
+    }
+}
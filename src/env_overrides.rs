@@ -0,0 +1,59 @@
+use bevy::prelude::*;
+
+use crate::{FillMode, ScreenUnits};
+
+pub const UNITS_VARIABLE: &str = "LETTERBOX_UNITS";
+pub const MODE_VARIABLE: &str = "LETTERBOX_MODE";
+
+// Applies LETTERBOX_UNITS (e.g. "20x15") and LETTERBOX_MODE over the configured values.
+// Malformed values are reported and ignored so automation never crashes the game.
+pub(crate) fn apply_env_overrides(app: &mut App) {
+    if let Ok(value) = std::env::var(UNITS_VARIABLE) {
+        match parse_screen_units(&value) {
+            Ok(units) => {
+                info!("{} applied: {}x{} units", UNITS_VARIABLE, units.width, units.height);
+                app.insert_resource(units);
+            },
+            Err(message) => error!("Ignoring {}={:?}: {}", UNITS_VARIABLE, value, message),
+        }
+    }
+
+    if let Ok(value) = std::env::var(MODE_VARIABLE) {
+        match value.trim() {
+            "bars" => {
+                info!("{} applied: bars", MODE_VARIABLE);
+                app.insert_resource(FillMode::Bars);
+            },
+            "crop" => {
+                info!("{} applied: crop", MODE_VARIABLE);
+                app.insert_resource(FillMode::Crop);
+            },
+            // Between 4:3 and 21:9 more of the world is shown instead of bars.
+            "expand" => {
+                info!("{} applied: expand", MODE_VARIABLE);
+                app.insert_resource(FillMode::Expand {
+                    min_aspect: 4.0 / 3.0,
+                    max_aspect: 21.0 / 9.0,
+                });
+            },
+            "stretch" => {
+                info!("{} applied: stretch", MODE_VARIABLE);
+                app.insert_resource(FillMode::Stretch);
+            },
+            _ => error!("Ignoring {}={:?}: expected bars, crop, expand or stretch", MODE_VARIABLE, value),
+        }
+    }
+}
+
+pub fn parse_screen_units(value: &str) -> Result<ScreenUnits, String> {
+    let (width, height) = value
+        .trim()
+        .split_once(['x', 'X'])
+        .ok_or_else(|| "expected WIDTHxHEIGHT".to_string())?;
+    let width: f32 = width.trim().parse().map_err(|_| format!("{:?} isn't a number", width))?;
+    let height: f32 = height.trim().parse().map_err(|_| format!("{:?} isn't a number", height))?;
+
+    let units = ScreenUnits { width, height };
+    units.validate()?;
+    Ok(units)
+}
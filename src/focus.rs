@@ -0,0 +1,137 @@
+use bevy::prelude::*;
+use bevy::window::WindowFocused;
+
+use crate::camera::ManagedCameraPosition;
+use crate::{DefaultLetterboxEasing, LetterboxEasing, LetterboxLayout, ManagedCamera, ReduceMotion};
+
+// Resource for a translucent cover over the safe area while the game window is unfocused, e.g. behind
+// a pause menu. It fades in when the window loses focus and out when it regains it. Focus changing
+// again mid-fade reverses the fade from where it is, so alt-tabbing quickly never stacks covers.
+// The letterboxes are never touched.
+pub struct FocusOverlay {
+    pub enabled: bool,
+    // Color when fully faded in, including its alpha.
+    pub color: Color,
+    // Seconds to fade fully in or out.
+    pub fade: f32,
+    // Uses DefaultLetterboxEasing when unset.
+    pub easing: Option<LetterboxEasing>,
+    covering: bool,
+    // How far faded in the cover is, from 0 to 1, before easing.
+    progress: f32,
+}
+
+impl Default for FocusOverlay {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            color: Color::rgba(0.0, 0.0, 0.0, 0.5),
+            fade: 0.25,
+            easing: None,
+            covering: false,
+            progress: 0.0,
+        }
+    }
+}
+
+impl FocusOverlay {
+    // Whether the cover is shown or fading in.
+    pub fn is_covering(&self) -> bool {
+        self.covering
+    }
+
+    // How far faded in the cover is, from 0 to 1.
+    pub fn progress(&self) -> f32 {
+        self.progress
+    }
+}
+
+// Event sent when the cover starts fading in or out, once per change of focus. Repeated focus events
+// for the same state don't send it again.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct FocusOverlayChanged {
+    pub covering: bool,
+}
+
+// Component for identifying the focus overlay entity.
+#[derive(Component)]
+pub struct FocusOverlayCover;
+
+pub(crate) fn spawn_focus_overlay(mut commands: Commands) {
+    commands
+        .spawn_bundle(SpriteBundle {
+            sprite: Sprite {
+                custom_size: Some(Vec2::ZERO),
+                ..default()
+            },
+            visibility: Visibility { is_visible: false },
+            ..default()
+        })
+        .insert(FocusOverlayCover);
+}
+
+pub(crate) fn track_window_focus(
+    time: Res<Time>,
+    reduce_motion: Res<ReduceMotion>,
+    managed_camera: Res<ManagedCamera>,
+    mut focus_events: EventReader<WindowFocused>,
+    mut overlay: ResMut<FocusOverlay>,
+    mut changed_events: EventWriter<FocusOverlayChanged>,
+) {
+    // Only the last event for the managed window matters when focus flaps within a frame.
+    let focused = focus_events
+        .iter()
+        .rfind(|event| event.id == managed_camera.window)
+        .map(|event| event.focused);
+
+    let covering = match focused {
+        Some(focused) => overlay.enabled && !focused,
+        // Turning the overlay off retracts it.
+        None => overlay.enabled && overlay.covering,
+    };
+    if covering != overlay.covering {
+        overlay.covering = covering;
+        changed_events.send(FocusOverlayChanged { covering });
+    }
+
+    let target = if overlay.covering { 1.0 } else { 0.0 };
+    if overlay.progress == target {
+        return;
+    }
+    let progress = if reduce_motion.0 || overlay.fade <= 0.0 {
+        target
+    } else {
+        let step = time.delta_seconds() / overlay.fade;
+        if target > overlay.progress {
+            (overlay.progress + step).min(target)
+        } else {
+            (overlay.progress - step).max(target)
+        }
+    };
+    overlay.progress = progress;
+}
+
+pub(crate) fn update_focus_overlay(
+    overlay: Res<FocusOverlay>,
+    default_easing: Res<DefaultLetterboxEasing>,
+    layout: Res<LetterboxLayout>,
+    camera_position: Res<ManagedCameraPosition>,
+    mut cover_query: Query<(&mut Transform, &mut Sprite, &mut Visibility), With<FocusOverlayCover>>,
+) {
+    if !overlay.is_changed() && !layout.is_changed() && !camera_position.is_changed() {
+        return;
+    }
+
+    let easing = overlay.easing.unwrap_or(default_easing.0);
+    let rect = layout.shown_safe_area();
+    for (mut transform, mut sprite, mut visibility) in cover_query.iter_mut() {
+        let center = camera_position.0 + rect.center();
+        let mut color = overlay.color;
+        color.set_a(overlay.color.a() * easing.ease(overlay.progress));
+        visibility.is_visible = overlay.progress > 0.0;
+        sprite.color = color;
+        sprite.custom_size = Some(rect.size());
+        // Above gameplay and below the letterboxes, which it never overlaps anyway.
+        transform.translation = Vec3::new( center.x, center.y, 998.5 );
+    }
+}
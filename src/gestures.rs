@@ -0,0 +1,160 @@
+use bevy::prelude::*;
+use bevy::utils::HashSet;
+
+use crate::{DefaultLetterboxEasing, LetterboxLayout, ManagedCamera, ReduceMotion, ScrollZoom};
+
+// Resource for framing with two fingers on touch screens. Pinching zooms through ScrollZoom within its
+// limits, around the point between the fingers, and dragging pans the managed camera, which
+// ClampCameraToBounds still keeps in bounds. Touches which begin over a letterbox are ignored, and only
+// the first two touches on the picture take part. ScrollZoom doesn't need to be enabled for pinching.
+pub struct TouchGestures {
+    pub enabled: bool,
+    pub pinch: bool,
+    pub pan: bool,
+    // How far past ScrollZoom's limits a pinch can go, as a fraction of the limit, e.g. 0.2 for 20%.
+    // Once the fingers lift the zoom eases back within the limits over snap_back seconds, using
+    // DefaultLetterboxEasing.
+    pub overshoot: f32,
+    pub snap_back: f32,
+}
+
+impl Default for TouchGestures {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            pinch: true,
+            pan: true,
+            overshoot: 0.0,
+            snap_back: 0.2,
+        }
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct GestureState {
+    // Touches which began over the picture.
+    accepted: HashSet<u64>,
+    // The ids of the two touches last frame, so a change of fingers doesn't jump.
+    last_pair: Option<(u64, u64)>,
+    snap: Option<SnapBack>,
+}
+
+struct SnapBack {
+    from: f32,
+    to: f32,
+    elapsed: f32,
+}
+
+// Fingers closer than this many logical pixels don't zoom, as their distance is mostly noise.
+const MIN_PINCH_DISTANCE: f32 = 10.0;
+
+pub(crate) fn apply_touch_gestures(
+    time: Res<Time>,
+    gestures: Res<TouchGestures>,
+    touches: Res<Touches>,
+    layout: Res<LetterboxLayout>,
+    managed_camera: Res<ManagedCamera>,
+    reduce_motion: Res<ReduceMotion>,
+    easing: Res<DefaultLetterboxEasing>,
+    mut zoom: ResMut<ScrollZoom>,
+    mut camera_query: Query<&mut Transform>,
+    mut state: Local<GestureState>,
+) {
+    if !gestures.enabled {
+        state.accepted.clear();
+        state.last_pair = None;
+        state.snap = None;
+        return;
+    }
+
+    let framed_px = layout.to_window_px(layout.framed);
+    for touch in touches.iter_just_pressed() {
+        if framed_px.contains(touch.position() * layout.dpi_scale) {
+            state.accepted.insert(touch.id());
+        }
+    }
+    for touch in touches.iter_just_released().chain(touches.iter_just_cancelled()) {
+        state.accepted.remove(&touch.id());
+    }
+
+    let mut pair: Vec<_> = touches.iter().filter(|touch| state.accepted.contains(&touch.id())).collect();
+    pair.sort_by_key(|touch| touch.id());
+    let (first, second) = match pair[..] {
+        [first, second, ..] => (first, second),
+        _ => {
+            state.last_pair = None;
+            // Only borrowed mutably when needed, so ScrollZoom isn't marked changed every frame.
+            let outside = zoom.zoom() < zoom.min_zoom || zoom.zoom() > zoom.max_zoom;
+            if outside || state.snap.is_some() {
+                snap_back(&time, &gestures, &reduce_motion, &easing, &mut zoom, &mut state);
+            }
+            return;
+        },
+    };
+    state.snap = None;
+    let ids = (first.id(), second.id());
+    let continued = state.last_pair == Some(ids);
+    state.last_pair = Some(ids);
+    if !continued {
+        return;
+    }
+
+    let center = (first.position() + second.position()) / 2.0;
+    let previous_center = (first.previous_position() + second.previous_position()) / 2.0;
+    let mut offset = Vec2::ZERO;
+    if gestures.pan {
+        // The world follows the fingers, so the camera moves the other way.
+        offset -= layout.window_point_to_units(center) - layout.window_point_to_units(previous_center);
+    }
+    let distance = first.position().distance(second.position());
+    let previous_distance = first.previous_position().distance(second.previous_position());
+    if gestures.pinch && distance >= MIN_PINCH_DISTANCE && previous_distance >= MIN_PINCH_DISTANCE {
+        let overshoot = 1.0 + gestures.overshoot.max(0.0);
+        let target = (zoom.zoom() * distance / previous_distance)
+            .clamp(zoom.min_zoom / overshoot, zoom.max_zoom * overshoot);
+        // Keep the world point between the fingers in place, the same way ScrollZoom keeps the cursor's.
+        let ratio = target / zoom.zoom();
+        offset += layout.window_point_to_units(center) * (1.0 - 1.0 / ratio);
+        if target != zoom.zoom() {
+            zoom.set_zoom_unclamped(target);
+        }
+    }
+
+    if offset != Vec2::ZERO {
+        if let Some(mut transform) = managed_camera.entity.and_then(|entity| camera_query.get_mut(entity).ok()) {
+            transform.translation.x += offset.x;
+            transform.translation.y += offset.y;
+        }
+    }
+}
+
+// Eases a zoom left past the limits by a pinch back within them.
+fn snap_back(
+    time: &Time,
+    gestures: &TouchGestures,
+    reduce_motion: &ReduceMotion,
+    easing: &DefaultLetterboxEasing,
+    zoom: &mut ScrollZoom,
+    state: &mut GestureState,
+) {
+    let current = zoom.zoom();
+    let limited = current.clamp(zoom.min_zoom, zoom.max_zoom);
+    if state.snap.is_none() && current != limited {
+        state.snap = Some(SnapBack {
+            from: current,
+            to: limited,
+            elapsed: 0.0,
+        });
+    }
+    let snap = match &mut state.snap {
+        Some(snap) => snap,
+        None => return,
+    };
+    snap.elapsed += time.delta_seconds();
+    let t = if reduce_motion.0 || gestures.snap_back <= 0.0 { 1.0 } else { snap.elapsed / gestures.snap_back };
+    let progress = easing.0.ease(t);
+    zoom.set_zoom_unclamped(snap.from + (snap.to - snap.from) * progress);
+    if t >= 1.0 {
+        state.snap = None;
+    }
+}
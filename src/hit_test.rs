@@ -0,0 +1,317 @@
+use std::marker::PhantomData;
+
+use bevy::ecs::schedule::ShouldRun;
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+use crate::{LetterboxBackend, LetterboxLayout, LetterboxRect, LetterboxSide, ManagedCamera, WindowRegion};
+
+// System parameter for asking whether window points, such as the cursor, are over the game picture
+// or a letterbox. Everything goes through the LetterboxLayout so all callers agree.
+#[derive(SystemParam)]
+pub struct SafeAreaTest<'w, 's> {
+    layout: Res<'w, LetterboxLayout>,
+    #[system_param(ignore)]
+    marker: PhantomData<&'s ()>,
+}
+
+impl<'w, 's> SafeAreaTest<'w, 's> {
+    // Whether a point in logical pixels from the bottom left of the window is over the safe area.
+    pub fn contains_window_point(&self, point: Vec2) -> bool {
+        self.classify(point) == WindowRegion::SafeArea
+    }
+
+    pub fn classify(&self, point: Vec2) -> WindowRegion {
+        self.layout.classify_window_point(point)
+    }
+}
+
+// A world space ray, e.g. from the cursor into a 3D scene.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct LetterboxRay {
+    pub origin: Vec3,
+    // Normalized.
+    pub direction: Vec3,
+}
+
+impl LetterboxRay {
+    // Where the ray crosses a plane, if it does in front of the origin.
+    pub fn intersect_plane(&self, plane_point: Vec3, plane_normal: Vec3) -> Option<Vec3> {
+        let facing = self.direction.dot(plane_normal);
+        if facing.abs() <= f32::EPSILON {
+            return None;
+        }
+        let distance = (plane_point - self.origin).dot(plane_normal) / facing;
+        (distance >= 0.0).then(|| self.origin + self.direction * distance)
+    }
+}
+
+// System parameter for casting rays from window points, such as the cursor, through a camera, the 3D
+// counterpart of LetterboxLayout::window_point_to_units. Points over a letterbox give no ray. A camera
+// rendering to the window covers all of it, bars included, while the managed camera with the render
+// texture backend only covers the shown image, and the ray accounts for either.
+#[derive(SystemParam)]
+pub struct CursorRay<'w, 's> {
+    layout: Res<'w, LetterboxLayout>,
+    backend: Res<'w, LetterboxBackend>,
+    managed_camera: Res<'w, ManagedCamera>,
+    windows: Res<'w, Windows>,
+    camera_query: Query<'w, 's, (&'static Camera, &'static GlobalTransform)>,
+}
+
+impl<'w, 's> CursorRay<'w, 's> {
+    // The ray under the cursor through the managed camera.
+    pub fn cursor_ray(&self) -> Option<LetterboxRay> {
+        self.cursor_ray_through(self.managed_camera.entity?)
+    }
+
+    // The ray under the cursor through any camera drawing to the managed window, e.g. a 3D camera
+    // under the 2D one.
+    pub fn cursor_ray_through(&self, camera: Entity) -> Option<LetterboxRay> {
+        let cursor = self.windows.get(self.managed_camera.window)?.cursor_position()?;
+        self.window_point_ray(camera, cursor)
+    }
+
+    // The ray through a camera under a point in logical pixels from the bottom left of the window.
+    pub fn window_point_ray(&self, camera: Entity, point: Vec2) -> Option<LetterboxRay> {
+        let point = point * self.layout.dpi_scale;
+        if !self.layout.to_window_px(self.layout.framed).contains(point) {
+            return None;
+        }
+        let (camera_component, transform) = self.camera_query.get(camera).ok()?;
+
+        // The part of the window the camera's projection is stretched over, in layout pixels.
+        let target = match *self.backend {
+            LetterboxBackend::RenderTexture(settings) if Some(camera) == self.managed_camera.entity => {
+                settings.blit_rect(&self.layout)
+            },
+            _ => LetterboxRect::new(Vec2::ZERO, self.layout.window_size),
+        };
+        let size = target.size();
+        if size.min_element() <= 0.0 {
+            return None;
+        }
+        let ndc = (point - target.min) / size * 2.0 - Vec2::ONE;
+
+        // Bevy's projections put the near plane at a depth of 1, with depth falling towards the far plane.
+        let ndc_to_world = transform.compute_matrix() * camera_component.projection_matrix.inverse();
+        let near = ndc_to_world.project_point3(ndc.extend(1.0));
+        let far = ndc_to_world.project_point3(ndc.extend(0.5));
+        let direction = (far - near).try_normalize()?;
+        Some(LetterboxRay { origin: near, direction })
+    }
+}
+
+impl WindowRegion {
+    // The letterbox a region is over, if any.
+    pub fn bar_side(&self) -> Option<LetterboxSide> {
+        match self {
+            WindowRegion::BarLeft => Some(LetterboxSide::Left),
+            WindowRegion::BarRight => Some(LetterboxSide::Right),
+            WindowRegion::BarTop => Some(LetterboxSide::Top),
+            WindowRegion::BarBottom => Some(LetterboxSide::Bottom),
+            WindowRegion::SafeArea | WindowRegion::Outside => None,
+        }
+    }
+}
+
+// Event sent when the left mouse button or a touch is pressed and released over the same letterbox,
+// e.g. for skipping cutscenes. A drag from the picture onto a bar, or between bars, doesn't count.
+// The position is where it was released, in logical pixels from the bottom left of the window.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct BarClicked {
+    pub side: LetterboxSide,
+    pub window_position: Vec2,
+}
+
+// Run criterion for detect_bar_clicks, which has nothing to do between presses and releases. Input is
+// updated before the update stage, so it's already current when the criterion runs.
+pub(crate) fn pointer_pressed_or_released(mouse_buttons: Res<Input<MouseButton>>, touches: Res<Touches>) -> ShouldRun {
+    let mouse = mouse_buttons.just_pressed(MouseButton::Left) || mouse_buttons.just_released(MouseButton::Left);
+    let touch = touches.iter_just_pressed().next().is_some()
+        || touches.iter_just_released().next().is_some()
+        || touches.iter_just_cancelled().next().is_some();
+    if mouse || touch {
+        ShouldRun::Yes
+    } else {
+        ShouldRun::No
+    }
+}
+
+pub(crate) fn detect_bar_clicks(
+    test: SafeAreaTest,
+    managed_camera: Res<ManagedCamera>,
+    windows: Res<Windows>,
+    mouse_buttons: Res<Input<MouseButton>>,
+    touches: Res<Touches>,
+    // Where each pointer was pressed: None for the mouse, or a touch's id.
+    mut presses: Local<HashMap<Option<u64>, WindowRegion>>,
+    mut clicked_events: EventWriter<BarClicked>,
+) {
+    let mut releases = Vec::new();
+
+    let cursor = windows.get(managed_camera.window).and_then(|window| window.cursor_position());
+    if let Some(position) = cursor {
+        if mouse_buttons.just_pressed(MouseButton::Left) {
+            presses.insert(None, test.classify(position));
+        }
+        if mouse_buttons.just_released(MouseButton::Left) {
+            releases.push((None, position));
+        }
+    }
+    for touch in touches.iter_just_pressed() {
+        presses.insert(Some(touch.id()), test.classify(touch.position()));
+    }
+    for touch in touches.iter_just_released() {
+        releases.push((Some(touch.id()), touch.position()));
+    }
+
+    for (pointer, position) in releases {
+        let pressed = match presses.remove(&pointer) {
+            Some(pressed) => pressed,
+            None => continue,
+        };
+        let released = test.classify(position);
+        if pressed != released {
+            continue;
+        }
+        if let Some(side) = released.bar_side() {
+            clicked_events.send(BarClicked {
+                side,
+                window_position: position,
+            });
+        }
+    }
+    // Presses which left the window or were cancelled are forgotten.
+    if !mouse_buttons.pressed(MouseButton::Left) {
+        presses.remove(&None);
+    }
+    for touch in touches.iter_just_cancelled() {
+        presses.remove(&Some(touch.id()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::SystemState;
+    use bevy::render::camera::CameraProjection;
+
+    use super::*;
+    use crate::test_utils::*;
+    use crate::{LetterboxPlugin, RenderTextureSettings};
+
+    fn managed_app() -> App {
+        LetterboxTestApp::new()
+            .plugin(LetterboxPlugin {
+                spawn_camera: true,
+                ..default()
+            })
+            .window(1600.0, 900.0, 1.0)
+            .build()
+    }
+
+    // The headless app has no camera systems, so the projection matrices are brought up to date here,
+    // with perspective projections following the window's aspect.
+    fn update_projection_matrices(app: &mut App) {
+        let window_size = layout(app).window_size;
+        let mut query = app
+            .world
+            .query::<(&mut Camera, Option<&OrthographicProjection>, Option<&mut PerspectiveProjection>)>();
+        for (mut camera, orthographic, perspective) in query.iter_mut(&mut app.world) {
+            if let Some(orthographic) = orthographic {
+                camera.projection_matrix = orthographic.get_projection_matrix();
+            }
+            if let Some(mut perspective) = perspective {
+                perspective.update(window_size.x, window_size.y);
+                camera.projection_matrix = perspective.get_projection_matrix();
+            }
+        }
+    }
+
+    fn window_point_ray(app: &mut App, camera: Entity, point: Vec2) -> Option<LetterboxRay> {
+        let mut state = SystemState::<CursorRay>::new(&mut app.world);
+        state.get_mut(&mut app.world).window_point_ray(camera, point)
+    }
+
+    #[test]
+    fn intersects_planes_in_front_of_the_ray() {
+        let ray = LetterboxRay {
+            origin: Vec3::new(1.0, 10.0, 0.0),
+            direction: Vec3::new(0.6, -0.8, 0.0),
+        };
+        assert_eq!(ray.intersect_plane(Vec3::ZERO, Vec3::Y), Some(Vec3::new(8.5, 0.0, 0.0)));
+        // The plane's normal may face either way.
+        assert_eq!(ray.intersect_plane(Vec3::ZERO, -Vec3::Y), Some(Vec3::new(8.5, 0.0, 0.0)));
+        assert_eq!(ray.intersect_plane(Vec3::new(0.0, 20.0, 0.0), Vec3::Y), None);
+        assert_eq!(ray.intersect_plane(Vec3::ZERO, Vec3::Z), None);
+    }
+
+    #[test]
+    fn rays_hit_the_ground_under_the_picture() {
+        let mut app = managed_app();
+        // Looking straight down at the ground from 10 units up, with the top of the window towards -z.
+        let camera = app
+            .world
+            .spawn()
+            .insert_bundle(PerspectiveCameraBundle {
+                transform: Transform::from_xyz(0.0, 10.0, 0.0).looking_at(Vec3::ZERO, -Vec3::Z),
+                ..default()
+            })
+            .id();
+
+        for (width, height) in [(1600.0, 900.0), (2000.0, 900.0), (1600.0, 1200.0), (1000.0, 1000.0)] {
+            resize(&mut app, width, height);
+            update_projection_matrices(&mut app);
+            let layout = layout(&app);
+            let framed = layout.to_window_px(layout.framed);
+            let half_extent = 10.0 * (PerspectiveProjection::default().fov / 2.0).tan();
+            for point in [framed.center(), framed.min, framed.max, Vec2::new(framed.min.x, framed.max.y)] {
+                let ray = window_point_ray(&mut app, camera, point).unwrap();
+                let hit = ray.intersect_plane(Vec3::ZERO, Vec3::Y).unwrap();
+                let ndc = point / layout.window_size * 2.0 - Vec2::ONE;
+                let expected = Vec3::new(ndc.x * half_extent * width / height, 0.0, -ndc.y * half_extent);
+                assert!(hit.abs_diff_eq(expected, 1e-3), "{:?} in {}x{}: {:?}", point, width, height, hit);
+            }
+            // Over a letterbox there's no ray.
+            if framed.min.x > 0.0 {
+                assert_eq!(window_point_ray(&mut app, camera, Vec2::new(framed.min.x - 1.0, height / 2.0)), None);
+            }
+            if framed.min.y > 0.0 {
+                assert_eq!(window_point_ray(&mut app, camera, Vec2::new(width / 2.0, framed.min.y - 1.0)), None);
+            }
+        }
+    }
+
+    #[test]
+    fn rays_through_the_render_texture_follow_the_shown_image() {
+        let mut app = managed_app();
+        let managed = app.world.resource::<ManagedCamera>().entity.unwrap();
+
+        // A 4:3 image of the 16:9 screen units has bars of its own, so it's shown taller than the safe area.
+        for settings in [RenderTextureSettings::new(320, 180), RenderTextureSettings::new(320, 240)] {
+            app.insert_resource(LetterboxBackend::RenderTexture(settings));
+            for (width, height) in [(2000.0, 900.0), (1600.0, 1200.0)] {
+                resize(&mut app, width, height);
+                update_projection_matrices(&mut app);
+                let layout = layout(&app);
+                let safe_area_px = layout.safe_area_px;
+                for point in [safe_area_px.center(), safe_area_px.min, safe_area_px.max] {
+                    let ray = window_point_ray(&mut app, managed, point).unwrap();
+                    let hit = ray.intersect_plane(Vec3::ZERO, Vec3::Z).unwrap();
+                    // Whatever the image, the picture under a point is where the sprite backend would show it.
+                    let expected = (point - safe_area_px.center()) / layout.pixels_per_unit;
+                    assert!(
+                        hit.truncate().abs_diff_eq(expected, 1e-3),
+                        "{:?} in {}x{} with {:?}: {:?}",
+                        point,
+                        width,
+                        height,
+                        settings,
+                        hit
+                    );
+                }
+            }
+        }
+    }
+}
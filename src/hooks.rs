@@ -0,0 +1,38 @@
+use std::panic::{self, AssertUnwindSafe};
+
+use bevy::ecs::event::{Events, ManualEventReader};
+use bevy::prelude::*;
+
+use crate::{LetterboxLayout, LetterboxLayoutChanged};
+
+// Function called with the new layout after it's been applied, with full access to the world.
+pub type LayoutHook = fn(&LetterboxLayout, &mut World);
+
+pub(crate) struct LayoutHooks {
+    pub(crate) hooks: Vec<LayoutHook>,
+    pub(crate) reader: ManualEventReader<LetterboxLayoutChanged>,
+}
+
+// Runs at the end of the update stage, after every letterbox system has applied the layout and before
+// transforms are propagated. Hooks run once per layout change, in the order they were registered.
+pub(crate) fn run_layout_hooks(world: &mut World) {
+    world.resource_scope(|world, mut hooks: Mut<LayoutHooks>| {
+        let hooks = &mut *hooks;
+        let layout = match world.get_resource::<Events<LetterboxLayoutChanged>>() {
+            Some(events) => hooks.reader.iter(events).last().map(|event| event.0),
+            None => None,
+        };
+        let layout = match layout {
+            Some(layout) => layout,
+            None => return,
+        };
+
+        for (index, hook) in hooks.hooks.iter().enumerate() {
+            // Name the failing hook before the panic carries on, since the backtrace only shows a fn pointer.
+            if let Err(payload) = panic::catch_unwind(AssertUnwindSafe(|| hook(&layout, world))) {
+                error!("Letterbox layout hook {} of {} panicked", index + 1, hooks.hooks.len());
+                panic::resume_unwind(payload);
+            }
+        }
+    });
+}
@@ -0,0 +1,74 @@
+use bevy::prelude::*;
+
+use crate::backend::ui_rect_style;
+use crate::{
+    LetterboxBackend, LetterboxLayout, LetterboxSide, LetterboxStyle, LetterboxSuspend, UiLetterbox, LETTERBOX_Z,
+};
+
+// Resource for whether the letterboxes cover bevy_ui, e.g. to hide a menu overflowing the safe area.
+// It can be switched at any time. Bevy 0.7 draws UI after everything else, so above UI the bars are
+// drawn as UI nodes raised over the other nodes: with the sprite and scissor backends extra nodes are
+// laid over the bars, and the render texture backend's nodes are raised as they are. The extra nodes
+// have the style's color and texture but not its slices or layers, and are drawn by the app's UI camera.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub enum BarLayering {
+    #[default]
+    BelowUi,
+    AboveUi,
+}
+
+// Component for the UI nodes covering the letterbox sprites while they're drawn above UI.
+#[derive(Component)]
+pub struct UiLetterboxOverlay {
+    pub side: LetterboxSide,
+}
+
+pub(crate) fn update_letterbox_overlays(
+    mut commands: Commands,
+    layering: Res<BarLayering>,
+    backend: Res<LetterboxBackend>,
+    layout: Res<LetterboxLayout>,
+    style: Res<LetterboxStyle>,
+    suspend: Res<LetterboxSuspend>,
+    mut overlay_query: Query<(Entity, &UiLetterboxOverlay, &mut Style, &mut UiColor, &mut UiImage, &mut Visibility)>,
+) {
+    if *layering != BarLayering::AboveUi || !backend.renders_to_camera_target() {
+        for (entity, ..) in overlay_query.iter() {
+            commands.entity(entity).despawn();
+        }
+        return;
+    }
+    if overlay_query.is_empty() {
+        for side in LetterboxSide::ALL {
+            commands.spawn_bundle(NodeBundle::default()).insert(UiLetterboxOverlay { side });
+        }
+        // The new nodes are laid out next frame once they exist.
+        return;
+    }
+
+    if !layering.is_changed() && !layout.is_changed() && !style.is_changed() && !suspend.is_changed() {
+        return;
+    }
+    for (_, overlay, mut node_style, mut color, mut image, mut visibility) in overlay_query.iter_mut() {
+        // UI is positioned in logical pixels. Collapsed letterboxes get nodes too thin to be drawn.
+        let rect = layout.to_window_px(layout.bar(overlay.side)).scaled(1.0 / layout.dpi_scale);
+        *node_style = ui_rect_style(rect);
+        color.0 = style.color_for(overlay.side);
+        image.0 = style.texture.clone().unwrap_or_default();
+        visibility.is_visible = style.visible && !suspend.is_suspended();
+    }
+}
+
+// Lifts the letterbox UI nodes over every other node. bevy_ui stacks nodes by depth, which it assigns
+// before transforms propagate, so the depth is raised on the propagated transforms.
+pub(crate) fn raise_letterboxes_above_ui(
+    layering: Res<BarLayering>,
+    mut node_query: Query<&mut GlobalTransform, Or<(With<UiLetterbox>, With<UiLetterboxOverlay>)>>,
+) {
+    if *layering != BarLayering::AboveUi {
+        return;
+    }
+    for mut transform in node_query.iter_mut() {
+        transform.translation.z = LETTERBOX_Z;
+    }
+}
@@ -0,0 +1,1088 @@
+use bevy::prelude::*;
+
+use crate::ScreenUnits;
+
+// An axis aligned rectangle. Depending on where it is used it is either in world units relative to the
+// camera or in window pixels measured from the bottom left of the window.
+#[derive(Default, Clone, Copy, PartialEq, Debug)]
+pub struct LetterboxRect {
+    pub min: Vec2,
+    pub max: Vec2,
+}
+
+impl LetterboxRect {
+    pub fn new(min: Vec2, max: Vec2) -> Self {
+        Self { min, max }
+    }
+
+    pub fn approx_eq(&self, other: &LetterboxRect, epsilon: f32) -> bool {
+        (self.min - other.min).abs().max_element() <= epsilon && (self.max - other.max).abs().max_element() <= epsilon
+    }
+
+    pub fn size(&self) -> Vec2 {
+        self.max - self.min
+    }
+
+    pub fn center(&self) -> Vec2 {
+        (self.min + self.max) / 2.0
+    }
+
+    // The overlap of two rectangles, or an empty rectangle at the nearest point if they don't overlap.
+    pub fn intersect(&self, other: LetterboxRect) -> LetterboxRect {
+        let min = self.min.max(other.min);
+        LetterboxRect::new(min, self.max.min(other.max).max(min))
+    }
+
+    pub fn contains(&self, point: Vec2) -> bool {
+        point.x >= self.min.x && point.x <= self.max.x && point.y >= self.min.y && point.y <= self.max.y
+    }
+
+    // Both corners multiplied by a factor, e.g. to convert between kinds of pixels.
+    pub fn scaled(&self, factor: f32) -> LetterboxRect {
+        LetterboxRect::new(self.min * factor, self.max * factor)
+    }
+
+    // The part of this rectangle on one side of a hole inside it. Left and right parts span the full
+    // height and top and bottom parts fill the gap between them, so the four parts and the hole tile it.
+    pub fn frame_side(&self, hole: LetterboxRect, side: LetterboxSide) -> LetterboxRect {
+        match side {
+            LetterboxSide::Left => LetterboxRect::new(self.min, Vec2::new(hole.min.x, self.max.y)),
+            LetterboxSide::Right => LetterboxRect::new(Vec2::new(hole.max.x, self.min.y), self.max),
+            LetterboxSide::Top => LetterboxRect::new(Vec2::new(hole.min.x, hole.max.y), Vec2::new(hole.max.x, self.max.y)),
+            LetterboxSide::Bottom => LetterboxRect::new(Vec2::new(hole.min.x, self.min.y), Vec2::new(hole.max.x, hole.min.y)),
+        }
+    }
+}
+
+// The edge of the window a letterbox covers.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum LetterboxSide {
+    Left,
+    Right,
+    Top,
+    Bottom,
+}
+
+impl LetterboxSide {
+    pub const ALL: [LetterboxSide; 4] = [
+        LetterboxSide::Left,
+        LetterboxSide::Right,
+        LetterboxSide::Top,
+        LetterboxSide::Bottom,
+    ];
+}
+
+// Resource for how ScreenUnits is fitted into a window of a different aspect ratio.
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FillMode {
+    // Show all of ScreenUnits and cover the rest of the window with letterboxes.
+    #[default]
+    Bars,
+    // Fill the window and cut off whatever of ScreenUnits doesn't fit along one axis. No letterboxes
+    // are shown, and the layout's visible area is smaller than its safe area on the cropped axis.
+    Crop,
+    // Show all of ScreenUnits and as much more of the world as the window allows, as long as the shown
+    // area's aspect ratio (width / height) stays within the range. Letterboxes cover the rest.
+    Expand { min_aspect: f32, max_aspect: f32 },
+    // Choose per axis. When the window's shape differs from ScreenUnits, fitting leaves room to spare
+    // along one axis and filling overflows the other. The window is filled whenever the overflowing
+    // axis allows Crop. Otherwise it's fitted and the axis with room to spare shows more of the world
+    // with Extend, or bars with Bars or Crop, since there's nothing to crop along it.
+    PerAxis { horizontal: AxisFill, vertical: AxisFill },
+    // Stretch ScreenUnits over the whole window, distorting it when the shapes differ. No letterboxes
+    // are shown and the layout is anisotropic, see LetterboxLayout::stretch. MinimumScale still applies
+    // and lays out undistorted once the smaller axis falls below it.
+    Stretch,
+}
+
+// How one axis of FillMode::PerAxis handles a window of a different shape.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AxisFill {
+    Bars,
+    Extend,
+    Crop,
+}
+
+// Resource for a floor on how small the world is drawn, so text stays readable in tiny windows. Below
+// it the safe area no longer fits in the window and is truncated, and pan moves the shown part around
+// the safe area, in world units. Zero pixels per unit turns it off.
+#[derive(Default, Clone, Copy, PartialEq, Debug)]
+pub struct MinimumScale {
+    pub pixels_per_unit: f32,
+    pub pan: Vec2,
+}
+
+// Resource for where the safe area sits in the space the letterboxes leave, as the share of that space
+// before it on each axis, from (0, 0) at the top left to (1, 1) at the bottom right like window pixels.
+// (0.5, 0.5) centers it, (0.5, 0.35) raises it and moves bar height from the top letterbox to the
+// bottom one, e.g. to leave room for dialogue below. It's clamped so the picture never leaves that
+// space, and has no effect along axes without letterboxes.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct SafeAreaPlacement(pub Vec2);
+
+impl Default for SafeAreaPlacement {
+    fn default() -> Self {
+        SafeAreaPlacement(Vec2::splat(0.5))
+    }
+}
+
+// Which part of the window a point is over.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum WindowRegion {
+    SafeArea,
+    BarLeft,
+    BarRight,
+    BarTop,
+    BarBottom,
+    Outside,
+}
+
+// Resource for insets the platform reports inside the window, such as a phone's notch or gesture bar.
+// Values are in logical pixels. The letterboxes grow to cover them and the safe area shrinks to avoid them.
+#[derive(Default, Clone, Copy, PartialEq, Debug)]
+pub struct PlatformInsets {
+    pub left: f32,
+    pub right: f32,
+    pub top: f32,
+    pub bottom: f32,
+}
+
+impl PlatformInsets {
+    // The insets multiplied by a factor, e.g. to convert them into layout pixels.
+    pub fn scaled(&self, factor: f32) -> Self {
+        Self {
+            left: self.left * factor,
+            right: self.right * factor,
+            top: self.top * factor,
+            bottom: self.bottom * factor,
+        }
+    }
+}
+
+// Resource for bands along the edges of the window the game reserves for itself, e.g. on-screen touch
+// controls. They're added to the platform insets: the picture is fitted into what's left and the
+// letterbox on that side grows to cover the band.
+#[derive(Default, Clone, Copy, PartialEq, Debug)]
+pub struct ReservedBands {
+    pub left: BandSize,
+    pub right: BandSize,
+    pub top: BandSize,
+    pub bottom: BandSize,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum BandSize {
+    Logical(f32),
+    Physical(f32),
+    // World units at the scale the picture is drawn when letterboxed. Cropping and expanding draw it
+    // at a different scale, so there the band is only approximately this many units.
+    Units(f32),
+}
+
+impl Default for BandSize {
+    fn default() -> Self {
+        BandSize::Logical(0.0)
+    }
+}
+
+impl ReservedBands {
+    // Adds the bands to insets in layout pixels. Layout pixels per logical pixel follow DpiPolicy.
+    pub fn add_to(
+        &self,
+        insets: &PlatformInsets,
+        window_size: Vec2,
+        screen_units: &ScreenUnits,
+        dpi_scale: f32,
+        scale_factor: f64,
+    ) -> PlatformInsets {
+        let pixels = |band: BandSize| match band {
+            BandSize::Logical(pixels) => pixels.max(0.0) * dpi_scale,
+            BandSize::Physical(pixels) => pixels.max(0.0) * dpi_scale / scale_factor as f32,
+            BandSize::Units(_) => 0.0,
+        };
+        let units = |band: BandSize| match band {
+            BandSize::Units(units) => units.max(0.0),
+            _ => 0.0,
+        };
+        let mut insets = PlatformInsets {
+            left: insets.left + pixels(self.left),
+            right: insets.right + pixels(self.right),
+            top: insets.top + pixels(self.top),
+            bottom: insets.bottom + pixels(self.bottom),
+        };
+
+        // Fit the screen units plus the unit bands into what the pixel bands leave, which gives the
+        // scale the bands are measured at.
+        let available_size = available_size(window_size, &insets);
+        let units_size = Vec2::new(
+            screen_units.width + units(self.left) + units(self.right),
+            screen_units.height + units(self.top) + units(self.bottom),
+        );
+        let pixels_per_unit = (available_size / units_size).min_element();
+        if pixels_per_unit.is_finite() && pixels_per_unit > 0.0 {
+            insets.left += units(self.left) * pixels_per_unit;
+            insets.right += units(self.right) * pixels_per_unit;
+            insets.top += units(self.top) * pixels_per_unit;
+            insets.bottom += units(self.bottom) * pixels_per_unit;
+        }
+        insets
+    }
+}
+
+// Resource for which pixels the layout is computed in. Logical pixels keep the framing the same
+// across displays, physical pixels let rounding and integer scaling line up with real screen pixels.
+// Either way UI positions and cursor positions given to the layout stay in logical pixels.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub enum DpiPolicy {
+    #[default]
+    Logical,
+    Physical,
+}
+
+impl DpiPolicy {
+    // How many layout pixels one logical pixel covers in a window with this scale factor.
+    pub fn layout_pixels_per_logical(&self, scale_factor: f64) -> f32 {
+        match self {
+            DpiPolicy::Logical => 1.0,
+            DpiPolicy::Physical => scale_factor as f32,
+        }
+    }
+}
+
+// Resource describing the current framing. It is recomputed whenever the window, ScreenUnits or
+// anything else affecting the layout changes, and everything else is derived from it. Its pixel
+// values are layout pixels, which are logical pixels unless DpiPolicy::Physical is used.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct LetterboxLayout {
+    // Size of the window in layout pixels.
+    pub window_size: Vec2,
+    // How many layout pixels one world unit covers. With FillMode::Stretch this is for the less
+    // stretched axis.
+    pub pixels_per_unit: f32,
+    // How many times more layout pixels one world unit covers along each axis than pixels_per_unit
+    // says. Both are 1 unless FillMode::Stretch distorts the picture.
+    pub stretch: Vec2,
+    // How many layout pixels one logical pixel covers, following DpiPolicy.
+    pub dpi_scale: f32,
+    // The platform insets and reserved bands the picture was fitted around, in layout pixels.
+    pub insets: PlatformInsets,
+    // The whole window in world units, relative to the camera.
+    pub visible: LetterboxRect,
+    // The area which shows ScreenUnits, in world units relative to the camera. Centered on the camera
+    // unless BarCoverage reaches into it, e.g. with cinematic bars. When cropping it extends past the
+    // visible area.
+    pub safe_area: LetterboxRect,
+    // The safe area in layout pixels.
+    pub safe_area_px: LetterboxRect,
+    // The area not covered by letterboxes, in world units relative to the camera. It's the safe area
+    // with bars, the visible area when cropping, and in between the two when expanding.
+    pub framed: LetterboxRect,
+    // Whether this layout has been applied to the projection and letterboxes. The resource is false
+    // until the first layout lands, and computed layouts are always false. See letterbox_ready.
+    pub ready: bool,
+}
+
+impl Default for LetterboxLayout {
+    fn default() -> Self {
+        Self {
+            window_size: Vec2::ZERO,
+            pixels_per_unit: 0.0,
+            stretch: Vec2::ONE,
+            dpi_scale: 1.0,
+            insets: PlatformInsets::default(),
+            visible: LetterboxRect::default(),
+            safe_area: LetterboxRect::default(),
+            safe_area_px: LetterboxRect::default(),
+            framed: LetterboxRect::default(),
+            ready: false,
+        }
+    }
+}
+
+impl LetterboxLayout {
+    // Whether two layouts match to within epsilon in every field. Whether they're ready isn't compared,
+    // so an applied layout matches the same layout freshly computed.
+    pub fn approx_eq(&self, other: &LetterboxLayout, epsilon: f32) -> bool {
+        (self.window_size - other.window_size).abs().max_element() <= epsilon
+            && (self.pixels_per_unit - other.pixels_per_unit).abs() <= epsilon
+            && (self.stretch - other.stretch).abs().max_element() <= epsilon
+            && (self.dpi_scale - other.dpi_scale).abs() <= epsilon
+            && (self.insets.left - other.insets.left).abs() <= epsilon
+            && (self.insets.right - other.insets.right).abs() <= epsilon
+            && (self.insets.top - other.insets.top).abs() <= epsilon
+            && (self.insets.bottom - other.insets.bottom).abs() <= epsilon
+            && self.visible.approx_eq(&other.visible, epsilon)
+            && self.safe_area.approx_eq(&other.safe_area, epsilon)
+            && self.safe_area_px.approx_eq(&other.safe_area_px, epsilon)
+            && self.framed.approx_eq(&other.framed, epsilon)
+    }
+
+    // How many layout pixels one world unit covers along each axis.
+    pub fn pixels_per_unit_axes(&self) -> Vec2 {
+        self.stretch * self.pixels_per_unit
+    }
+
+    // Whether world units cover a different number of pixels horizontally and vertically, so things
+    // relying on square pixels, like pixel snapping, should turn themselves off.
+    pub fn is_anisotropic(&self) -> bool {
+        (self.stretch.x - self.stretch.y).abs() > 1e-4
+    }
+
+    // The world space rectangle a letterbox has to cover. Left and right bars span the full window height,
+    // top and bottom bars fill the gap between them, so the bars and the framed area tile the window.
+    pub fn bar(&self, side: LetterboxSide) -> LetterboxRect {
+        self.visible.frame_side(self.framed, side)
+    }
+
+    // Where the camera is in layout pixels from the bottom left of the window.
+    pub fn origin_px(&self) -> Vec2 {
+        -self.visible.min * self.pixels_per_unit_axes()
+    }
+
+    // Converts a point in logical pixels from the bottom left of the window, like a cursor position,
+    // into world units relative to the camera.
+    pub fn window_point_to_units(&self, point: Vec2) -> Vec2 {
+        (point * self.dpi_scale - self.origin_px()) / self.pixels_per_unit_axes()
+    }
+
+    // Converts a rectangle in world units relative to the camera into layout pixels from the bottom
+    // left of the window.
+    pub fn to_window_px(&self, rect: LetterboxRect) -> LetterboxRect {
+        let origin_px = self.origin_px();
+        let pixels_per_unit = self.pixels_per_unit_axes();
+        LetterboxRect::new(origin_px + rect.min * pixels_per_unit, origin_px + rect.max * pixels_per_unit)
+    }
+
+    // The area not covered by letterboxes in physical pixels, rounded like SafeAreaPx. The scale factor
+    // passed in is the window's.
+    pub fn framed_physical(&self, scale_factor: f64) -> PhysicalRect {
+        PhysicalRect::from_layout_px(self, self.to_window_px(self.framed), scale_factor)
+    }
+
+    // The part of the safe area in the window if some of it is cut off, by cropping or MinimumScale.
+    pub fn safe_area_truncation(&self) -> Option<LetterboxRect> {
+        let shown = self.shown_safe_area();
+        if shown.approx_eq(&self.safe_area, 1e-4) {
+            None
+        } else {
+            Some(shown)
+        }
+    }
+
+    // The part of the safe area which is actually in the window. It's the whole safe area unless cropping.
+    pub fn shown_safe_area(&self) -> LetterboxRect {
+        self.safe_area.intersect(self.visible)
+    }
+
+    // The shown safe area inset by margin world units, stopping at its center.
+    #[cfg(feature = "random")]
+    fn shown_safe_area_inset(&self, margin: f32) -> LetterboxRect {
+        let shown = self.shown_safe_area();
+        let margin = Vec2::splat(margin.max(0.0)).min(shown.size() / 2.0);
+        LetterboxRect::new(shown.min + margin, shown.max - margin)
+    }
+
+    // A uniformly random point in the shown safe area, e.g. for spawning pickups where they can be seen.
+    #[cfg(feature = "random")]
+    pub fn random_point(&self, rng: &mut impl rand::Rng) -> Vec2 {
+        self.random_point_with_margin(rng, 0.0)
+    }
+
+    // A uniformly random point at least margin world units inside the shown safe area.
+    #[cfg(feature = "random")]
+    pub fn random_point_with_margin(&self, rng: &mut impl rand::Rng, margin: f32) -> Vec2 {
+        let bounds = self.shown_safe_area_inset(margin);
+        Vec2::new(
+            rng.gen_range(bounds.min.x..=bounds.max.x),
+            rng.gen_range(bounds.min.y..=bounds.max.y),
+        )
+    }
+
+    // The points of a square lattice through the camera with the given spacing which are in the shown
+    // safe area, row by row from the bottom left. Nothing if spacing isn't positive.
+    pub fn grid_points(&self, spacing: f32) -> impl Iterator<Item = Vec2> {
+        let shown = self.shown_safe_area();
+        let (columns, rows) = if spacing > 0.0 && spacing.is_finite() {
+            (
+                (shown.min.x / spacing).ceil() as i64..(shown.max.x / spacing).floor() as i64 + 1,
+                (shown.min.y / spacing).ceil() as i64..(shown.max.y / spacing).floor() as i64 + 1,
+            )
+        } else {
+            (0..0, 0..0)
+        };
+        rows.flat_map(move |row| columns.clone().map(move |column| Vec2::new(column as f32, row as f32) * spacing))
+    }
+
+    // Where subtitles should go, in world units relative to the camera: the bottom letterbox when it's
+    // at least min_bar_height units tall, otherwise a strip of that height inside the bottom of the safe area.
+    pub fn subtitle_area(&self, min_bar_height: f32) -> LetterboxRect {
+        let bar = self.bar(LetterboxSide::Bottom);
+        if bar.size().y >= min_bar_height {
+            bar
+        } else {
+            let safe = self.safe_area;
+            LetterboxRect::new(safe.min, Vec2::new(safe.max.x, safe.min.y + min_bar_height))
+        }
+    }
+
+    // Classifies a point in logical pixels from the bottom left of the window, like cursor positions.
+    // Points exactly on the edge of the safe area count as inside it, and points on the edge of the
+    // window count as inside the window. Left and right bars take the corners, matching bar().
+    pub fn classify_window_point(&self, point: Vec2) -> WindowRegion {
+        let point = point * self.dpi_scale;
+        let window = LetterboxRect::new(Vec2::ZERO, self.window_size);
+        let safe = self.safe_area_px;
+        if !window.contains(point) {
+            WindowRegion::Outside
+        } else if safe.contains(point) {
+            WindowRegion::SafeArea
+        } else if point.x < safe.min.x {
+            WindowRegion::BarLeft
+        } else if point.x > safe.max.x {
+            WindowRegion::BarRight
+        } else if point.y > safe.max.y {
+            WindowRegion::BarTop
+        } else {
+            WindowRegion::BarBottom
+        }
+    }
+
+    // Checks the properties every fitted layout should have, before BarCoverage's cinematic and custom
+    // sources cut into it, for use in tests over arbitrary inputs:
+    // no bar has a negative size, the safe area has the aspect ratio of the screen units, the framed
+    // area holds all of the safe area in the window, and the bars plus the framed area exactly cover the window.
+    pub fn check_invariants(&self, screen_units: &ScreenUnits) -> Result<(), String> {
+        const EPSILON: f32 = 1e-3;
+
+        for side in LetterboxSide::ALL {
+            let size = self.bar(side).size();
+            if size.x < -EPSILON || size.y < -EPSILON {
+                return Err(format!("{:?} bar has a negative size {:?}", side, size));
+            }
+        }
+
+        let safe_size = self.safe_area.size();
+        let framed_size = self.framed.size();
+        let aspect_error = safe_size.x * screen_units.height - safe_size.y * screen_units.width;
+        if aspect_error.abs() > EPSILON * screen_units.width.max(screen_units.height) {
+            return Err(format!("safe area {:?} doesn't match the aspect of {:?}", safe_size, screen_units));
+        }
+
+        let shown_safe_area = self.shown_safe_area();
+        if (shown_safe_area.min - self.framed.min).min_element() < -EPSILON
+            || (self.framed.max - shown_safe_area.max).min_element() < -EPSILON
+        {
+            return Err(format!("framed area {:?} doesn't hold the safe area {:?}", self.framed, shown_safe_area));
+        }
+
+        let covered_area = LetterboxSide::ALL
+            .iter()
+            .map(|side| {
+                let size = self.bar(*side).size();
+                size.x * size.y
+            })
+            .sum::<f32>()
+            + framed_size.x * framed_size.y;
+        let visible_size = self.visible.size();
+        let window_area = visible_size.x * visible_size.y;
+        if (covered_area - window_area).abs() > EPSILON * window_area.max(1.0) {
+            return Err(format!("bars and framed area cover {} units but the window is {}", covered_area, window_area));
+        }
+
+        let window_units = self.window_size / self.pixels_per_unit_axes();
+        if (window_units - visible_size).abs().max_element() > EPSILON * window_units.max_element().max(1.0) {
+            return Err(format!("window is {:?} units but the layout covers {:?}", window_units, visible_size));
+        }
+
+        Ok(())
+    }
+}
+
+// Resource holding the safe area in physical window pixels, with the origin at the top left like a
+// captured frame. Edges are rounded to whole pixels. The scale factor passed in is the window's.
+#[derive(Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct SafeAreaPx {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl SafeAreaPx {
+    pub fn from_layout(layout: &LetterboxLayout, scale_factor: f64) -> Self {
+        // Only the part of the safe area inside the window can be captured, and the conversion clips to it.
+        let rect = PhysicalRect::from_layout_px(layout, layout.safe_area_px, scale_factor);
+        Self {
+            x: rect.x,
+            y: rect.y,
+            width: rect.width,
+            height: rect.height,
+        }
+    }
+}
+
+// A rectangle in physical window pixels, with the origin at the top left like SafeAreaPx.
+#[derive(Default, Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PhysicalRect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl PhysicalRect {
+    // Converts a rectangle in layout pixels, clipped to the window. Each edge is rounded to a whole pixel
+    // on its own, so rectangles sharing an edge still tile. The scale factor passed in is the window's.
+    pub fn from_layout_px(layout: &LetterboxLayout, rect: LetterboxRect, scale_factor: f64) -> Self {
+        let scale_factor = scale_factor as f32 / layout.dpi_scale;
+        let rect = rect.intersect(LetterboxRect::new(Vec2::ZERO, layout.window_size));
+        let left = (rect.min.x * scale_factor).round().max(0.0) as u32;
+        let right = (rect.max.x * scale_factor).round().max(0.0) as u32;
+        let top = ((layout.window_size.y - rect.max.y) * scale_factor).round().max(0.0) as u32;
+        let bottom = ((layout.window_size.y - rect.min.y) * scale_factor).round().max(0.0) as u32;
+        Self {
+            x: left,
+            y: top,
+            width: right.saturating_sub(left),
+            height: bottom.saturating_sub(top),
+        }
+    }
+}
+
+// Fits the screen units into the window, minus any platform insets, as large as possible.
+pub fn compute_letterbox_layout(
+    window_size: Vec2,
+    screen_units: &ScreenUnits,
+    insets: &PlatformInsets,
+) -> LetterboxLayout {
+    // The constrained axis determines how many pixels one unit covers.
+    let available_size = available_size(window_size, insets);
+    let pixels_per_unit = (available_size.x / screen_units.width).min(available_size.y / screen_units.height);
+
+    // Degenerate inputs, like insets covering the whole window, would otherwise divide by zero below.
+    let pixels_per_unit = if pixels_per_unit.is_finite() && pixels_per_unit > 0.0 {
+        pixels_per_unit
+    } else {
+        f32::EPSILON
+    };
+
+    layout_with_pixels_per_unit(window_size, screen_units, insets, pixels_per_unit)
+}
+
+// Fits the screen units into the window, minus any platform insets, following a fill mode.
+pub fn compute_fill_layout(
+    window_size: Vec2,
+    screen_units: &ScreenUnits,
+    insets: &PlatformInsets,
+    fill_mode: FillMode,
+) -> LetterboxLayout {
+    match fill_mode {
+        FillMode::Bars => compute_letterbox_layout(window_size, screen_units, insets),
+        FillMode::PerAxis { horizontal, vertical } => {
+            let available_size = available_size(window_size, insets);
+            let wider = available_size.x * screen_units.height >= available_size.y * screen_units.width;
+            let (spare_axis, overflowing_axis) = if wider { (horizontal, vertical) } else { (vertical, horizontal) };
+            if overflowing_axis == AxisFill::Crop {
+                compute_fill_layout(window_size, screen_units, insets, FillMode::Crop)
+            } else if spare_axis == AxisFill::Extend {
+                compute_fill_layout(window_size, screen_units, insets, FillMode::Expand {
+                    min_aspect: 0.0,
+                    max_aspect: f32::INFINITY,
+                })
+            } else {
+                compute_letterbox_layout(window_size, screen_units, insets)
+            }
+        },
+        FillMode::Crop => {
+            // The unconstrained axis determines how many pixels one unit covers, so the other overflows.
+            let available_size = available_size(window_size, insets);
+            let pixels_per_unit = (available_size.x / screen_units.width).max(available_size.y / screen_units.height);
+            let pixels_per_unit = if pixels_per_unit.is_finite() && pixels_per_unit > 0.0 {
+                pixels_per_unit
+            } else {
+                f32::EPSILON
+            };
+            layout_with_pixels_per_unit(window_size, screen_units, insets, pixels_per_unit)
+        },
+        FillMode::Expand { min_aspect, max_aspect } => {
+            let mut layout = compute_letterbox_layout(window_size, screen_units, insets);
+            layout.framed = expanded_area(&layout, insets, min_aspect, max_aspect);
+            layout
+        },
+        FillMode::Stretch => compute_stretched_layout(window_size, screen_units, insets),
+    }
+}
+
+// Stretches the screen units over the window, minus any platform insets, scaling each axis separately.
+fn compute_stretched_layout(window_size: Vec2, screen_units: &ScreenUnits, insets: &PlatformInsets) -> LetterboxLayout {
+    let units = Vec2::new(screen_units.width, screen_units.height);
+    let pixels_per_unit_axes = available_size(window_size, insets) / units;
+    // Degenerate inputs fall back to the same tiny scale as compute_letterbox_layout.
+    if !(pixels_per_unit_axes.min_element() > 0.0 && pixels_per_unit_axes.is_finite()) {
+        return compute_letterbox_layout(window_size, screen_units, insets);
+    }
+    let pixels_per_unit = pixels_per_unit_axes.min_element();
+
+    let safe_min_px = Vec2::new(insets.left, insets.bottom);
+    let safe_area_px = LetterboxRect::new(safe_min_px, safe_min_px + units * pixels_per_unit_axes);
+    let safe_center_px = safe_area_px.center();
+    let visible = LetterboxRect::new(
+        -safe_center_px / pixels_per_unit_axes,
+        (window_size - safe_center_px) / pixels_per_unit_axes,
+    );
+    let safe_area = LetterboxRect::new(-units / 2.0, units / 2.0);
+
+    LetterboxLayout {
+        window_size,
+        pixels_per_unit,
+        stretch: pixels_per_unit_axes / pixels_per_unit,
+        dpi_scale: 1.0,
+        insets: *insets,
+        visible,
+        safe_area,
+        safe_area_px,
+        framed: safe_area.intersect(visible),
+        ready: false,
+    }
+}
+
+// Keeps a layout from drawing the world smaller than the minimum scale, truncating the safe area instead.
+pub fn apply_minimum_scale(
+    layout: LetterboxLayout,
+    screen_units: &ScreenUnits,
+    insets: &PlatformInsets,
+    minimum: &MinimumScale,
+) -> LetterboxLayout {
+    if layout.pixels_per_unit >= minimum.pixels_per_unit {
+        return layout;
+    }
+    let mut layout = layout_with_pixels_per_unit(layout.window_size, screen_units, insets, minimum.pixels_per_unit);
+
+    // Pan the window over the safe area, without showing past its edges along the truncated axes.
+    let slack = ((layout.safe_area.size() - layout.visible.size()) / 2.0).max(Vec2::ZERO);
+    let pan = minimum.pan.clamp(-slack, slack);
+    let pan_px = pan * layout.pixels_per_unit;
+    layout.visible = LetterboxRect::new(layout.visible.min + pan, layout.visible.max + pan);
+    layout.safe_area_px = LetterboxRect::new(layout.safe_area_px.min - pan_px, layout.safe_area_px.max - pan_px);
+    layout.framed = layout.safe_area.intersect(layout.visible);
+    layout
+}
+
+// Moves a centered layout's safe area within the space around it following a placement. The camera stays
+// on the safe area's center, so the window moves the other way in world units.
+pub fn apply_placement(layout: LetterboxLayout, placement: &SafeAreaPlacement) -> LetterboxLayout {
+    let available = LetterboxRect::new(
+        Vec2::new(layout.insets.left, layout.insets.bottom),
+        layout.window_size - Vec2::new(layout.insets.right, layout.insets.top),
+    );
+    let slack = (available.size() - layout.safe_area_px.size()).max(Vec2::ZERO);
+    // Placements are measured down from the top, layouts up from the bottom.
+    let placement = placement.0.clamp(Vec2::ZERO, Vec2::ONE);
+    let offset_px = Vec2::new(placement.x - 0.5, 0.5 - placement.y) * slack;
+    if offset_px == Vec2::ZERO {
+        return layout;
+    }
+
+    let mut layout = layout;
+    let offset = offset_px / layout.pixels_per_unit;
+    layout.safe_area_px = LetterboxRect::new(layout.safe_area_px.min + offset_px, layout.safe_area_px.max + offset_px);
+    layout.visible = LetterboxRect::new(layout.visible.min - offset, layout.visible.max - offset);
+
+    // What's framed moves with the window, but never so far that it stops holding the shown safe area.
+    let shown = layout.shown_safe_area();
+    let framed = LetterboxRect::new(layout.framed.min - offset, layout.framed.max - offset);
+    let push = (shown.min - framed.min).min(Vec2::ZERO) + (shown.max - framed.max).max(Vec2::ZERO);
+    layout.framed = LetterboxRect::new(framed.min + push, framed.max + push);
+    layout
+}
+
+// Whether the window, minus any platform insets, is outside an aspect range, so expanding shows bars.
+pub fn is_outside_aspect_range(window_size: Vec2, insets: &PlatformInsets, min_aspect: f32, max_aspect: f32) -> bool {
+    let available_size = available_size(window_size, insets);
+    let aspect = available_size.x / available_size.y;
+    aspect < min_aspect || aspect > max_aspect
+}
+
+// Centers the screen units in the window, minus any platform insets, at a given size. The pixels per
+// unit shouldn't be larger than what compute_letterbox_layout would pick or the safe area won't fit.
+pub fn layout_with_pixels_per_unit(
+    window_size: Vec2,
+    screen_units: &ScreenUnits,
+    insets: &PlatformInsets,
+    pixels_per_unit: f32,
+) -> LetterboxLayout {
+    // Center the safe area within the region of the window which isn't covered by insets.
+    let available_min = Vec2::new(insets.left, insets.bottom);
+    let available_size = available_size(window_size, insets);
+    let safe_size_px = Vec2::new(screen_units.width, screen_units.height) * pixels_per_unit;
+    let safe_min_px = available_min + (available_size - safe_size_px) / 2.0;
+    let safe_area_px = LetterboxRect::new(safe_min_px, safe_min_px + safe_size_px);
+
+    // Convert the window into world units with the safe area's center at the camera.
+    let safe_center_px = safe_area_px.center();
+    let visible = LetterboxRect::new(
+        -safe_center_px / pixels_per_unit,
+        (window_size - safe_center_px) / pixels_per_unit,
+    );
+    let half_units = Vec2::new(screen_units.width, screen_units.height) / 2.0;
+    let safe_area = LetterboxRect::new(-half_units, half_units);
+
+    LetterboxLayout {
+        window_size,
+        pixels_per_unit,
+        stretch: Vec2::ONE,
+        dpi_scale: 1.0,
+        insets: *insets,
+        visible,
+        safe_area,
+        safe_area_px,
+        framed: safe_area.intersect(visible),
+        ready: false,
+    }
+}
+
+// The area shown around the safe area when expanding: everything not under an inset, narrowed to the
+// aspect range. It always holds the safe area, even when ScreenUnits itself is outside the range.
+fn expanded_area(layout: &LetterboxLayout, insets: &PlatformInsets, min_aspect: f32, max_aspect: f32) -> LetterboxRect {
+    // The safe area is centered in the region not under insets, so that region is centered on the camera.
+    let mut size = available_size(layout.window_size, insets) / layout.pixels_per_unit;
+    if size.x > size.y * max_aspect {
+        size.x = size.y * max_aspect;
+    }
+    if size.x < size.y * min_aspect {
+        size.y = size.x / min_aspect;
+    }
+    let size = size.max(layout.safe_area.size());
+    LetterboxRect::new(-size / 2.0, size / 2.0)
+}
+
+// The size of the window which isn't covered by insets.
+fn available_size(window_size: Vec2, insets: &PlatformInsets) -> Vec2 {
+    (window_size - Vec2::new(insets.left + insets.right, insets.top + insets.bottom)).max(Vec2::ZERO)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A small xorshift generator, so the sweeps over arbitrary inputs repeat exactly.
+    struct Inputs(u32);
+
+    impl Inputs {
+        fn next(&mut self, min: f32, max: f32) -> f32 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 17;
+            self.0 ^= self.0 << 5;
+            min + (max - min) * (self.0 as f32 / u32::MAX as f32)
+        }
+
+        fn window_size(&mut self) -> Vec2 {
+            Vec2::new(self.next(1.0, 4000.0).round(), self.next(1.0, 4000.0).round())
+        }
+
+        fn screen_units(&mut self) -> ScreenUnits {
+            ScreenUnits {
+                width: self.next(0.5, 100.0),
+                height: self.next(0.5, 100.0),
+            }
+        }
+
+        fn insets(&mut self, window_size: Vec2) -> PlatformInsets {
+            PlatformInsets {
+                left: self.next(0.0, window_size.x / 4.0),
+                right: self.next(0.0, window_size.x / 4.0),
+                top: self.next(0.0, window_size.y / 4.0),
+                bottom: self.next(0.0, window_size.y / 4.0),
+            }
+        }
+    }
+
+    fn assert_invariants(layout: &LetterboxLayout, screen_units: &ScreenUnits) {
+        if let Err(problem) = layout.check_invariants(screen_units) {
+            panic!("{} for {:?} in {:?}", problem, screen_units, layout);
+        }
+    }
+
+    #[test]
+    fn invariants_hold_for_arbitrary_inputs() {
+        let mut inputs = Inputs(0x5eed);
+        for _ in 0..2000 {
+            let window_size = inputs.window_size();
+            let screen_units = inputs.screen_units();
+            let insets = inputs.insets(window_size);
+            for fill_mode in [
+                FillMode::Bars,
+                FillMode::Crop,
+                FillMode::Expand {
+                    min_aspect: 4.0 / 3.0,
+                    max_aspect: 21.0 / 9.0,
+                },
+            ] {
+                assert_invariants(&compute_fill_layout(window_size, &screen_units, &insets, fill_mode), &screen_units);
+            }
+        }
+    }
+
+    #[test]
+    fn invariants_hold_with_integer_scaling() {
+        let mut inputs = Inputs(0x1e77e7);
+        for _ in 0..2000 {
+            let window_size = inputs.window_size();
+            let screen_units = inputs.screen_units();
+            let insets = PlatformInsets::default();
+            let fitted = compute_letterbox_layout(window_size, &screen_units, &insets);
+            if fitted.pixels_per_unit < 1.0 {
+                continue;
+            }
+            let layout = layout_with_pixels_per_unit(window_size, &screen_units, &insets, fitted.pixels_per_unit.floor());
+            assert_invariants(&layout, &screen_units);
+        }
+    }
+
+    #[test]
+    fn invariants_hold_when_both_axes_fit_exactly() {
+        let screen_units = ScreenUnits {
+            width: 16.0,
+            height: 9.0,
+        };
+        for scale in [1.0, 1.5, 2.0, 2.25, 3.0] {
+            let window_size = Vec2::new(1280.0, 720.0) * scale;
+            let layout = compute_letterbox_layout(window_size, &screen_units, &PlatformInsets::default());
+            assert_invariants(&layout, &screen_units);
+            for side in LetterboxSide::ALL {
+                assert_eq!(layout.bar(side).size().min_element(), 0.0);
+            }
+        }
+    }
+
+    #[test]
+    fn placement_is_measured_from_the_top_left() {
+        let screen_units = ScreenUnits {
+            width: 16.0,
+            height: 9.0,
+        };
+        let layout = compute_letterbox_layout(Vec2::new(1000.0, 1000.0), &screen_units, &PlatformInsets::default());
+        // 437.5 pixels of letterboxes, 35% of them above the picture.
+        let raised = apply_placement(layout, &SafeAreaPlacement(Vec2::new(0.5, 0.35)));
+        assert_invariants(&raised, &screen_units);
+        assert!((raised.safe_area_px.max.y - (1000.0 - 153.125)).abs() < 1e-3);
+        assert!((raised.safe_area_px.min.y - 284.375).abs() < 1e-3);
+        let clamped = apply_placement(layout, &SafeAreaPlacement(Vec2::new(0.5, -1.0)));
+        assert!((clamped.safe_area_px.max.y - 1000.0).abs() < 1e-3);
+        // Without pillarboxes there's nowhere to move sideways.
+        assert_eq!(apply_placement(layout, &SafeAreaPlacement(Vec2::new(0.0, 0.5))), layout);
+    }
+
+    #[test]
+    fn extreme_windows_lay_out_finitely() {
+        let window_sizes = [(1.0, 1.0), (1.0, 10000.0), (10000.0, 1.0), (16384.0, 16384.0)];
+        let screen_units = [(16.0, 9.0), (1e-3, 1e-3), (1e5, 1.0), (1.0, 1e5)];
+        for (width, height) in window_sizes {
+            for (units_width, units_height) in screen_units {
+                let screen_units = ScreenUnits {
+                    width: units_width,
+                    height: units_height,
+                };
+                let layout = compute_letterbox_layout(Vec2::new(width, height), &screen_units, &PlatformInsets::default());
+                let values = [layout.visible, layout.safe_area, layout.safe_area_px, layout.framed];
+                assert!(layout.pixels_per_unit.is_finite() && layout.pixels_per_unit > 0.0, "{:?}", layout);
+                assert!(values.iter().all(|rect| rect.min.is_finite() && rect.max.is_finite()), "{:?}", layout);
+                assert_invariants(&layout, &screen_units);
+            }
+        }
+    }
+
+    #[derive(Clone, Copy, PartialEq, Debug)]
+    enum Fill {
+        // The window is covered and part of the safe area is cut off.
+        Cropped,
+        // The window is covered and all of the safe area is shown.
+        Extended,
+        // Bars cover the room to spare.
+        Barred,
+    }
+
+    #[test]
+    fn per_axis_fill_combinations() {
+        use AxisFill::*;
+        use Fill::*;
+
+        let screen_units = ScreenUnits {
+            width: 16.0,
+            height: 9.0,
+        };
+        let wide = Vec2::new(2000.0, 900.0);
+        let tall = Vec2::new(1600.0, 1200.0);
+        // The fill for a wide and a tall window with each combination of horizontal and vertical fill.
+        let cases = [
+            (Bars, Bars, Barred, Barred),
+            (Bars, Extend, Barred, Extended),
+            (Bars, Crop, Cropped, Barred),
+            (Extend, Bars, Extended, Barred),
+            (Extend, Extend, Extended, Extended),
+            (Extend, Crop, Cropped, Barred),
+            (Crop, Bars, Barred, Cropped),
+            (Crop, Extend, Barred, Cropped),
+            (Crop, Crop, Cropped, Cropped),
+        ];
+        for (horizontal, vertical, wide_fill, tall_fill) in cases {
+            for (window_size, fill, bar_sides) in [
+                (wide, wide_fill, [LetterboxSide::Left, LetterboxSide::Right]),
+                (tall, tall_fill, [LetterboxSide::Top, LetterboxSide::Bottom]),
+            ] {
+                let fill_mode = FillMode::PerAxis { horizontal, vertical };
+                let layout = compute_fill_layout(window_size, &screen_units, &PlatformInsets::default(), fill_mode);
+                let case = format!("{:?} in a {:?} window", fill_mode, window_size);
+                assert_invariants(&layout, &screen_units);
+
+                let covered = layout.framed.approx_eq(&layout.visible, 1e-4);
+                let truncated = layout.safe_area_truncation().is_some();
+                let barred = bar_sides.iter().all(|side| layout.bar(*side).size().min_element() > 0.0);
+                match fill {
+                    Cropped => assert!(covered && truncated, "{} isn't cropped", case),
+                    Extended => assert!(covered && !truncated, "{} isn't extended", case),
+                    Barred => assert!(barred && layout.framed.approx_eq(&layout.safe_area, 1e-4), "{} isn't barred", case),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn corrupted_layouts_fail_the_invariants() {
+        let screen_units = ScreenUnits {
+            width: 16.0,
+            height: 9.0,
+        };
+        let layout = compute_letterbox_layout(Vec2::new(1600.0, 1200.0), &screen_units, &PlatformInsets::default());
+        assert_eq!(layout.check_invariants(&screen_units), Ok(()));
+
+        let mut wrong_aspect = layout;
+        wrong_aspect.safe_area.max.x += 1.0;
+        assert!(wrong_aspect.check_invariants(&screen_units).is_err());
+
+        let mut shrunk_frame = layout;
+        shrunk_frame.framed = LetterboxRect::new(layout.framed.min / 2.0, layout.framed.max / 2.0);
+        assert!(shrunk_frame.check_invariants(&screen_units).is_err());
+
+        let mut wrong_window = layout;
+        wrong_window.window_size.x += 100.0;
+        assert!(wrong_window.check_invariants(&screen_units).is_err());
+    }
+
+    #[test]
+    fn framed_physical_rects() {
+        let screen_units = ScreenUnits {
+            width: 16.0,
+            height: 9.0,
+        };
+        let rect = |x, y, width, height| PhysicalRect { x, y, width, height };
+        // Window size in logical pixels, scale factor, fill mode and the framed area in physical pixels.
+        let cases = [
+            ((1600.0, 900.0), 1.0, FillMode::Bars, rect(0, 0, 1600, 900)),
+            ((2000.0, 900.0), 1.0, FillMode::Bars, rect(200, 0, 1600, 900)),
+            ((1280.0, 1024.0), 1.5, FillMode::Bars, rect(0, 228, 1920, 1080)),
+            // Edges landing on half pixels round outwards on their own.
+            ((1000.0, 1000.0), 1.0, FillMode::Bars, rect(0, 219, 1000, 562)),
+            ((1000.0, 1000.0), 2.0, FillMode::Bars, rect(0, 438, 2000, 1125)),
+            ((2000.0, 900.0), 2.0, FillMode::Crop, rect(0, 0, 4000, 1800)),
+        ];
+        for ((width, height), scale_factor, fill_mode, expected) in cases {
+            let layout = compute_fill_layout(Vec2::new(width, height), &screen_units, &PlatformInsets::default(), fill_mode);
+            assert_eq!(layout.framed_physical(scale_factor), expected, "{}x{} at {}", width, height, scale_factor);
+        }
+    }
+
+    #[test]
+    fn classifies_window_points_on_the_edges() {
+        let screen_units = ScreenUnits {
+            width: 16.0,
+            height: 9.0,
+        };
+        // Window size in logical pixels and the expected region for points in logical pixels.
+        let cases = [
+            (Vec2::new(2000.0, 900.0), Vec2::new(200.0, 450.0), WindowRegion::SafeArea),
+            (Vec2::new(2000.0, 900.0), Vec2::new(1800.0, 450.0), WindowRegion::SafeArea),
+            (Vec2::new(2000.0, 900.0), Vec2::new(1000.0, 0.0), WindowRegion::SafeArea),
+            (Vec2::new(2000.0, 900.0), Vec2::new(199.5, 450.0), WindowRegion::BarLeft),
+            (Vec2::new(2000.0, 900.0), Vec2::new(1800.5, 450.0), WindowRegion::BarRight),
+            (Vec2::new(2000.0, 900.0), Vec2::new(0.0, 0.0), WindowRegion::BarLeft),
+            (Vec2::new(2000.0, 900.0), Vec2::new(2000.0, 900.0), WindowRegion::BarRight),
+            (Vec2::new(2000.0, 900.0), Vec2::new(-0.5, 450.0), WindowRegion::Outside),
+            (Vec2::new(2000.0, 900.0), Vec2::new(2000.5, 450.0), WindowRegion::Outside),
+            (Vec2::new(2000.0, 900.0), Vec2::new(1000.0, 900.5), WindowRegion::Outside),
+            (Vec2::new(2000.0, 900.0), Vec2::new(1000.0, -0.5), WindowRegion::Outside),
+            (Vec2::new(1600.0, 1200.0), Vec2::new(800.0, 150.0), WindowRegion::SafeArea),
+            (Vec2::new(1600.0, 1200.0), Vec2::new(800.0, 1050.0), WindowRegion::SafeArea),
+            (Vec2::new(1600.0, 1200.0), Vec2::new(800.0, 149.5), WindowRegion::BarBottom),
+            (Vec2::new(1600.0, 1200.0), Vec2::new(800.0, 1050.5), WindowRegion::BarTop),
+            (Vec2::new(1600.0, 1200.0), Vec2::new(0.0, 0.0), WindowRegion::BarBottom),
+            (Vec2::new(1600.0, 1200.0), Vec2::new(1600.0, 1200.0), WindowRegion::BarTop),
+            (Vec2::new(1600.0, 1200.0), Vec2::new(1600.5, 1200.0), WindowRegion::Outside),
+        ];
+        // With a dpi scale of 2 the layout is in physical pixels, while the points stay logical.
+        for dpi_scale in [1.0, 2.0] {
+            for (window_size, point, expected) in cases {
+                let mut layout =
+                    compute_letterbox_layout(window_size * dpi_scale, &screen_units, &PlatformInsets::default());
+                layout.dpi_scale = dpi_scale;
+                assert_eq!(
+                    layout.classify_window_point(point),
+                    expected,
+                    "{:?} in {:?} at {}",
+                    point,
+                    window_size,
+                    dpi_scale
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn framed_physical_rects_follow_the_scale_factor_override() {
+        use crate::test_utils::*;
+        use crate::LetterboxPlugin;
+
+        // A window of 2400x1080 physical pixels, whatever the override says it is in logical pixels.
+        let expected = PhysicalRect {
+            x: 240,
+            y: 0,
+            width: 1920,
+            height: 1080,
+        };
+        for dpi_policy in [DpiPolicy::Logical, DpiPolicy::Physical] {
+            let mut app = LetterboxTestApp::new()
+                .plugin(LetterboxPlugin {
+                    spawn_camera: true,
+                    ..default()
+                })
+                .window(2400.0, 1080.0, 1.0)
+                .build();
+            app.insert_resource(dpi_policy);
+            app.update();
+            for scale_factor in [1.0, 1.5, 2.0] {
+                set_scale_factor_override(&mut app, Some(scale_factor));
+                let layout = layout(&app);
+                let dpi_scale = dpi_policy.layout_pixels_per_logical(scale_factor);
+                assert_eq!(layout.dpi_scale, dpi_scale, "{:?} at {}", dpi_policy, scale_factor);
+                assert!(
+                    layout.window_size.abs_diff_eq(Vec2::new(2400.0, 1080.0) / scale_factor as f32 * dpi_scale, 1e-3),
+                    "{:?} at {}",
+                    dpi_policy,
+                    scale_factor
+                );
+                assert_eq!(layout.framed_physical(scale_factor), expected, "{:?} at {}", dpi_policy, scale_factor);
+                let safe_area_px = *app.world.resource::<SafeAreaPx>();
+                assert_eq!(
+                    (safe_area_px.x, safe_area_px.y, safe_area_px.width, safe_area_px.height),
+                    (expected.x, expected.y, expected.width, expected.height),
+                    "{:?} at {}",
+                    dpi_policy,
+                    scale_factor
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,149 @@
+use std::path::{Path, PathBuf};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{FillMode, LetterboxLayout, LetterboxSide, ManagedCamera, PhysicalRect};
+
+// Version of the ExportedLayout format, raised whenever fields change meaning or are removed.
+pub const LAYOUT_EXPORT_VERSION: u32 = 1;
+
+// The live layout in window pixels, for tools outside the game like stream overlays. Rectangles are in
+// physical pixels from the top left of the window, rounded like SafeAreaPx.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub struct ExportedLayout {
+    pub version: u32,
+    // Size of the window in physical pixels.
+    pub window_width: u32,
+    pub window_height: u32,
+    pub scale_factor: f64,
+    pub fill_mode: FillMode,
+    // World units per physical pixel along each axis. They only differ with FillMode::Stretch.
+    pub units_per_pixel_x: f32,
+    pub units_per_pixel_y: f32,
+    // The part of the safe area inside the window.
+    pub safe_area: PhysicalRect,
+    pub bars: ExportedBars,
+}
+
+// Each letterbox in physical pixels. Hidden bars have no width or height.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub struct ExportedBars {
+    pub left: PhysicalRect,
+    pub right: PhysicalRect,
+    pub top: PhysicalRect,
+    pub bottom: PhysicalRect,
+}
+
+impl ExportedLayout {
+    pub fn from_layout(layout: &LetterboxLayout, fill_mode: FillMode, scale_factor: f64) -> Self {
+        let bar = |side| PhysicalRect::from_layout_px(layout, layout.to_window_px(layout.bar(side)), scale_factor);
+        let physical_per_layout = scale_factor as f32 / layout.dpi_scale;
+        let units_per_pixel = 1.0 / (layout.pixels_per_unit_axes() * physical_per_layout);
+        Self {
+            version: LAYOUT_EXPORT_VERSION,
+            window_width: (layout.window_size.x * physical_per_layout).round() as u32,
+            window_height: (layout.window_size.y * physical_per_layout).round() as u32,
+            scale_factor,
+            fill_mode,
+            units_per_pixel_x: units_per_pixel.x,
+            units_per_pixel_y: units_per_pixel.y,
+            safe_area: PhysicalRect::from_layout_px(layout, layout.safe_area_px, scale_factor),
+            bars: ExportedBars {
+                left: bar(LetterboxSide::Left),
+                right: bar(LetterboxSide::Right),
+                top: bar(LetterboxSide::Top),
+                bottom: bar(LetterboxSide::Bottom),
+            },
+        }
+    }
+
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(self).map_err(|error| error.to_string())
+    }
+}
+
+// Resource for publishing the layout once it has stayed the same for the debounce time, so a window
+// being dragged is published once. LayoutExported is sent for every published layout, e.g. to forward
+// it over a websocket, and with a path it's also written there as JSON.
+pub struct LayoutExport {
+    pub path: Option<PathBuf>,
+    // Seconds the layout has to stay unchanged before it's published.
+    pub debounce: f32,
+}
+
+impl Default for LayoutExport {
+    fn default() -> Self {
+        Self {
+            path: None,
+            debounce: 0.25,
+        }
+    }
+}
+
+impl LayoutExport {
+    pub fn to_file(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: Some(path.into()),
+            ..default()
+        }
+    }
+}
+
+// Event sent whenever LayoutExport publishes a new layout.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct LayoutExported(pub ExportedLayout);
+
+#[derive(Default)]
+pub(crate) struct ExportState {
+    published: Option<ExportedLayout>,
+    pending: Option<ExportedLayout>,
+    unchanged_for: f32,
+}
+
+pub(crate) fn export_layout(
+    time: Res<Time>,
+    export: Res<LayoutExport>,
+    layout: Res<LetterboxLayout>,
+    fill_mode: Res<FillMode>,
+    windows: Res<Windows>,
+    managed: Res<ManagedCamera>,
+    mut exported_events: EventWriter<LayoutExported>,
+    mut state: Local<ExportState>,
+) {
+    // Nothing is laid out until the window exists. The scale factor can change without the layout changing.
+    let window = match windows.get(managed.window) {
+        Some(window) => window,
+        None => return,
+    };
+    let current = ExportedLayout::from_layout(&layout, *fill_mode, window.scale_factor());
+
+    if state.pending != Some(current) {
+        state.pending = Some(current);
+        state.unchanged_for = 0.0;
+        return;
+    }
+    state.unchanged_for += time.delta_seconds();
+    if state.published == Some(current) || state.unchanged_for < export.debounce {
+        return;
+    }
+    state.published = Some(current);
+    exported_events.send(LayoutExported(current));
+
+    // Failures are only logged, the layout is written again when it next changes.
+    if let Some(path) = &export.path {
+        if let Err(error) = current.to_json().and_then(|json| write_atomically(path, &json)) {
+            warn!("Couldn't export the letterbox layout to {:?}: {}", path, error);
+        }
+    }
+}
+
+// Writes next to the file and renames it over, so tools polling the file never read half of it.
+fn write_atomically(path: &Path, contents: &str) -> Result<(), String> {
+    if let Some(directory) = path.parent() {
+        std::fs::create_dir_all(directory).map_err(|error| error.to_string())?;
+    }
+    let temporary = path.with_extension("json.tmp");
+    std::fs::write(&temporary, contents).map_err(|error| error.to_string())?;
+    std::fs::rename(&temporary, path).map_err(|error| error.to_string())
+}
@@ -0,0 +1,43 @@
+// Paints the letterbox colors over everything outside a rectangle of the target, leaving the inside as
+// it was. Drawn as one triangle covering the whole target.
+struct LetterboxMask {
+    // The part of the target not covered by letterboxes, as the left, top, right and bottom edges in
+    // physical pixels from the top left, the same way as PhysicalRect.
+    rect: vec4<f32>;
+    left: vec4<f32>;
+    right: vec4<f32>;
+    top: vec4<f32>;
+    bottom: vec4<f32>;
+};
+
+[[group(0), binding(0)]]
+var<uniform> mask: LetterboxMask;
+
+[[stage(vertex)]]
+fn vertex([[builtin(vertex_index)]] index: u32) -> [[builtin(position)]] vec4<f32> {
+    let uv = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+    return vec4<f32>(uv * 2.0 - 1.0, 0.0, 1.0);
+}
+
+[[stage(fragment)]]
+fn fragment([[builtin(position)]] position: vec4<f32>) -> [[location(0)]] vec4<f32> {
+    let inside = position.x >= mask.rect.x && position.x < mask.rect.z
+        && position.y >= mask.rect.y && position.y < mask.rect.w;
+    if (inside) {
+        discard;
+    }
+
+    // Left and right span the full height and top and bottom fill the gap between them, like
+    // LetterboxRect::frame_side.
+    var color = mask.bottom;
+    if (position.y < mask.rect.y) {
+        color = mask.top;
+    }
+    if (position.x >= mask.rect.z) {
+        color = mask.right;
+    }
+    if (position.x < mask.rect.x) {
+        color = mask.left;
+    }
+    return color;
+}
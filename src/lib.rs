@@ -0,0 +1,1830 @@
+// Bevy systems take their resources and queries as arguments, so long parameter lists are expected.
+#![allow(clippy::too_many_arguments, clippy::type_complexity)]
+
+use std::marker::PhantomData;
+
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy::render::camera::*;
+use bevy::transform::TransformSystem;
+use bevy::window::{WindowCreated, WindowId, WindowMoved, WindowResized, WindowScaleFactorChanged};
+
+use crate::camera::{ManagedCameraPosition, TrackManagedCamera};
+
+mod accessibility;
+#[cfg(feature = "ambient-bars")]
+mod ambient;
+mod anchor;
+#[cfg(feature = "debug-tools")]
+mod aspect_preview;
+mod audit;
+mod backend;
+mod background;
+mod bar_query;
+mod bleed;
+mod borderless;
+mod border;
+mod bounds;
+mod camera;
+mod cinematic;
+mod clear_color;
+mod confine;
+mod coverage;
+mod cursor;
+#[cfg(feature = "debug-tools")]
+mod debug_tools;
+mod demo;
+mod easing;
+mod env_overrides;
+mod focus;
+mod gestures;
+mod hit_test;
+mod hooks;
+mod layering;
+mod layout;
+#[cfg(any(feature = "scissor", feature = "post-process"))]
+mod mask;
+#[cfg(feature = "layout-export")]
+mod layout_export;
+mod offscreen;
+#[cfg(feature = "persistence")]
+mod persistence;
+mod pixel_snap;
+mod pixels;
+#[cfg(feature = "post-process")]
+mod post_process;
+mod progress;
+mod ready;
+#[cfg(feature = "event-recording")]
+mod recording;
+mod regions;
+#[cfg(feature = "scissor")]
+mod scissor;
+#[cfg(feature = "screenshot")]
+mod screenshot;
+mod split;
+mod status;
+mod style;
+mod subtitles;
+mod suspend;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test_utils;
+mod theme;
+mod transition;
+mod transparency;
+#[cfg(feature = "tweak-panel")]
+mod tweak_panel;
+#[cfg(feature = "tweening")]
+mod tweening;
+mod validation;
+#[cfg(feature = "video-modes")]
+mod video_modes;
+mod virtual_resolution;
+mod zoom;
+mod zoom_levels;
+
+pub use accessibility::{ReduceMotion, SafeAreaIndicator, SafeAreaIndicatorEdge};
+#[cfg(feature = "ambient-bars")]
+pub use ambient::{AmbientBars, AMBIENT_BARS_PASS};
+pub use anchor::SafeAnchor;
+#[cfg(feature = "debug-tools")]
+pub use aspect_preview::{AspectPreview, AspectPreviewStrip, PREVIEW_ASPECT_RATIOS};
+pub use audit::LetterboxAudit;
+pub use backend::{LetterboxBackend, RenderTextureBlit, RenderTextureSettings, UiLetterbox};
+pub use background::{BackgroundLayers, LetterboxBackgroundCamera};
+pub use bar_query::{BarInfo, LetterboxQuery, WithLetterbox, WithUiLetterbox};
+pub use bleed::{AllowBleed, BLEED_Z};
+pub use borderless::BorderlessSettling;
+pub use border::{SafeAreaBorder, SafeAreaBorderEdge};
+pub use bounds::{ClampCameraToBounds, ClampToSafeArea};
+pub use cinematic::{
+    CinematicBar, CinematicBars, LetterboxKeyframe, LetterboxTrack, LetterboxTrackFinished, LetterboxTrackPlayer,
+};
+pub use clear_color::LetterboxClearColor;
+pub use confine::{ConfinementMethod, CursorConfinement};
+pub use coverage::{BarCoverage, CoverageChanged, CoverageSource, SideCoverage};
+pub use cursor::{VirtualCursor, VirtualCursorSprite};
+#[cfg(feature = "debug-tools")]
+pub use debug_tools::*;
+pub use demo::DemoObject;
+pub use easing::{DefaultLetterboxEasing, LetterboxEasing};
+pub use env_overrides::{parse_screen_units, MODE_VARIABLE, UNITS_VARIABLE};
+pub use focus::{FocusOverlay, FocusOverlayChanged, FocusOverlayCover};
+pub use gestures::TouchGestures;
+pub use hit_test::{BarClicked, CursorRay, LetterboxRay, SafeAreaTest};
+pub use hooks::LayoutHook;
+pub use layering::{BarLayering, UiLetterboxOverlay};
+pub use layout::*;
+#[cfg(feature = "layout-export")]
+pub use layout_export::{ExportedBars, ExportedLayout, LayoutExport, LayoutExported, LAYOUT_EXPORT_VERSION};
+pub use offscreen::{OffscreenIndicator, SafeAreaEdgePoint, SafeAreaPoint, SafeAreaProjection};
+#[cfg(feature = "persistence")]
+pub use persistence::{FileStorage, PreferenceStorage, WindowPersistence, WindowPreferences};
+pub use pixel_snap::PixelSnap;
+pub use pixels::PixelsPerUnit;
+#[cfg(feature = "post-process")]
+pub use post_process::LETTERBOX_MASK_PASS;
+pub use progress::{BarProgress, BarProgressIndicator, BarProgressPlacement};
+pub use ready::{letterbox_ready, ReadyCover};
+#[cfg(feature = "event-recording")]
+pub use recording::*;
+pub use regions::{HudRegions, Region, RegionFn, RegionId};
+#[cfg(feature = "screenshot")]
+pub use screenshot::*;
+pub use split::{SplitDirection, SplitLayout, SplitPlayerCamera, SplitView, SplitViewNode, SplitViews};
+pub use status::{LetterboxError, LetterboxStatus};
+pub use style::{
+    BarLayer, BarSlices, LetterboxCommands, LetterboxCommandsExt, LetterboxFalloff, LetterboxLayer, LetterboxSlice,
+    LetterboxStyle, SideColors,
+};
+pub use subtitles::{SubtitleAnchor, SubtitleArea};
+pub use suspend::LetterboxSuspend;
+pub use theme::{apply_state_bar_theme, BarFlash, BarTheme, BarThemes};
+pub use transition::{
+    LetterboxTransition, ReframeAborted, ReframeCovered, ReframeWithFade, StartTransition, TransitionBar,
+    TransitionCovered, TransitionFinished, TransitionKind,
+};
+pub use transparency::{TransparencyBackdrop, TransparentLetterboxes};
+#[cfg(feature = "tweak-panel")]
+pub use tweak_panel::{TweakPanel, TweakPanelNode};
+#[cfg(feature = "tweening")]
+pub use tweening::{CinematicColorLens, CinematicFractionLens, LetterboxTweenTarget, ScreenUnitsLens};
+pub use validation::ScreenUnitsValidation;
+#[cfg(feature = "video-modes")]
+pub use video_modes::{FullscreenVideoModes, VideoModeCandidate};
+pub use virtual_resolution::{FramingAuthority, VirtualResolution};
+pub use zoom::ScrollZoom;
+pub use zoom_levels::{ChangeZoomLevel, ZoomLevels};
+
+// Plugin which keeps the camera framed to ScreenUnits and covers the rest of the window with letterboxes.
+#[derive(Default)]
+pub struct LetterboxPlugin {
+    // Lets QA automation override ScreenUnits through the LETTERBOX_UNITS and LETTERBOX_MODE environment variables.
+    pub env_overrides: bool,
+    // Spawns a 2D camera for the letterboxes to manage. Otherwise an existing camera marked with
+    // LetterboxCamera, or failing that the existing 2D camera, is adopted.
+    pub spawn_camera: bool,
+    // How much the plugin does. ComputeOnly never spawns a camera, even with spawn_camera set.
+    pub mode: LetterboxMode,
+    // Called after each layout change is applied, in order. See on_layout_changed.
+    pub layout_hooks: Vec<LayoutHook>,
+    // Spawns objects which move across every letterbox edge, for manually testing backends.
+    pub demo_scene: bool,
+    // Covers the managed camera's view in the letterbox color until the first layout is applied, so the
+    // first frame shown is already framed. See letterbox_ready.
+    pub cover_until_ready: bool,
+}
+
+impl LetterboxPlugin {
+    // Registers a function to call with the new layout once it's been applied to the projection and
+    // letterboxes, for reacting from outside the ECS. Hooks run at the end of CoreStage::Update.
+    pub fn on_layout_changed(mut self, hook: LayoutHook) -> Self {
+        self.layout_hooks.push(hook);
+        self
+    }
+
+    pub fn with_demo_scene(mut self, demo_scene: bool) -> Self {
+        self.demo_scene = demo_scene;
+        self
+    }
+}
+
+impl Plugin for LetterboxPlugin {
+    fn build(&self, app: &mut App) {
+        if self.env_overrides {
+            env_overrides::apply_env_overrides(app);
+        }
+
+        #[cfg(feature = "persistence")]
+        persistence::apply_saved_preferences(app);
+
+        virtual_resolution::insert_initial_screen_units(app);
+        validation::validate_initial_screen_units(app);
+
+        if self.spawn_camera && self.mode == LetterboxMode::Managed {
+            app.add_startup_system(setup_camera);
+        }
+        if self.demo_scene {
+            app
+                .add_startup_system(demo::spawn_demo_scene)
+                .add_system(demo::move_demo_objects.before(LetterboxSystem::Layout));
+        }
+
+        if self.cover_until_ready && self.mode == LetterboxMode::Managed {
+            app.add_system(ready::cover_until_ready.after(LetterboxSystem::Layout));
+        }
+
+        // Nothing is drawn in compute only mode, so none of the bars are spawned.
+        if self.mode == LetterboxMode::Managed {
+            split::add_player_cameras(app);
+            #[cfg(feature = "scissor")]
+            scissor::add_scissor_pass(app);
+            background::add_background_camera(app);
+            #[cfg(feature = "post-process")]
+            post_process::add_post_process_pass(app);
+            #[cfg(feature = "ambient-bars")]
+            ambient::add_ambient_bars(app);
+            app
+                .add_startup_system(spawn_letterboxes)
+                .add_startup_system(border::spawn_safe_area_border)
+                .add_startup_system(cinematic::spawn_cinematic_bars)
+                .add_startup_system(transition::spawn_transition_bars)
+                .add_startup_system(progress::spawn_bar_progress)
+                .add_startup_system(focus::spawn_focus_overlay)
+                .add_startup_system(cursor::spawn_virtual_cursor)
+                .add_startup_system(accessibility::spawn_safe_area_indicator)
+                .add_system(place_letterboxes.after(TrackManagedCamera))
+                .add_system(
+                    hide_unclaimed_letterboxes
+                        .after(LetterboxSystem::Layout)
+                        .before(LetterboxSystem::Style)
+                        .before(TrackManagedCamera),
+                )
+                .add_system(accessibility::update_safe_area_indicator.after(LetterboxSystem::Layout))
+                .add_system(style::update_sliced_letterboxes.after(TrackManagedCamera))
+                .add_system(style::update_letterbox_layers.after(TrackManagedCamera))
+                .add_system(style::update_inner_falloff.after(TrackManagedCamera))
+                .add_system(backend::update_render_texture_backend.after(LetterboxSystem::Layout))
+                .add_system(layering::update_letterbox_overlays.after(LetterboxSystem::Layout))
+                .add_system_to_stage(
+                    CoreStage::PostUpdate,
+                    layering::raise_letterboxes_above_ui.after(TransformSystem::TransformPropagate),
+                )
+                .add_system(clear_color::sync_clear_color.after(LetterboxSystem::Style))
+                .add_system(transparency::apply_transparent_letterboxes.after(LetterboxSystem::Layout))
+                .add_system(split::update_split_layout.after(LetterboxSystem::Layout))
+                .add_system(background::update_background_camera.after(LetterboxSystem::PixelSnap));
+        }
+
+        app
+            .insert_resource(self.mode)
+            .add_event::<LetterboxLayoutChanged>()
+            .add_event::<AspectRangeCrossed>()
+            .add_event::<BarChanged>()
+            .init_resource::<SafeAreaOverride>()
+            .init_resource::<PlatformInsets>()
+            .init_resource::<FillMode>()
+            .init_resource::<MinimumScale>()
+            .init_resource::<ReservedBands>()
+            .init_resource::<SafeAreaPlacement>()
+            .init_resource::<HudRegions>()
+            .init_resource::<CollapsedLetterboxes>()
+            .init_resource::<DpiPolicy>()
+            .init_resource::<LetterboxLayout>()
+            .init_resource::<ProjectionLayout>()
+            .init_resource::<FittedLayout>()
+            .init_resource::<ManagedCameraPosition>()
+            .init_resource::<SafeAreaPx>()
+            .init_resource::<SafeAreaBorder>()
+            .init_resource::<LetterboxStyle>()
+            .init_resource::<LetterboxBackend>()
+            .init_resource::<BackgroundLayers>()
+            .init_resource::<BarLayering>()
+            .init_resource::<ManagedCamera>()
+            .init_resource::<CinematicBars>()
+            .init_resource::<BarCoverage>()
+            .add_event::<CoverageChanged>()
+            .init_resource::<LetterboxTrackPlayer>()
+            .add_event::<LetterboxTrackFinished>()
+            .init_resource::<LetterboxTransition>()
+            .add_event::<StartTransition>()
+            .add_event::<TransitionCovered>()
+            .add_event::<TransitionFinished>()
+            .add_event::<ReframeCovered>()
+            .add_event::<ReframeAborted>()
+            .add_event::<BarClicked>()
+            .init_resource::<FocusOverlay>()
+            .init_resource::<VirtualCursor>()
+            .init_resource::<CursorConfinement>()
+            .add_event::<FocusOverlayChanged>()
+            .init_resource::<BarProgress>()
+            .init_resource::<SubtitleArea>()
+            .init_resource::<LetterboxSuspend>()
+            .init_resource::<ReduceMotion>()
+            .init_resource::<SafeAreaIndicator>()
+            .init_resource::<LetterboxAudit>()
+            .init_resource::<BarFlash>()
+            .init_resource::<LetterboxClearColor>()
+            .init_resource::<TransparentLetterboxes>()
+            .init_resource::<SplitLayout>()
+            .init_resource::<SplitViews>()
+            .init_resource::<LetterboxStatus>()
+            .init_resource::<ScreenUnitsValidation>()
+            .init_resource::<FramingAuthority>()
+            .init_resource::<PixelsPerUnit>()
+            .init_resource::<DefaultLetterboxEasing>()
+            .init_resource::<ScrollZoom>()
+            .init_resource::<ZoomLevels>()
+            .add_event::<ChangeZoomLevel>()
+            .init_resource::<BorderlessSettling>()
+            .init_resource::<TouchGestures>()
+            .add_system(gestures::apply_touch_gestures.before(zoom::ApplyScrollZoom))
+            .add_system(zoom_levels::apply_zoom_levels.before(zoom::ApplyScrollZoom))
+            .add_system(
+                zoom::apply_scroll_zoom
+                    .label(zoom::ApplyScrollZoom)
+                    .before(LetterboxSystem::Framing),
+            )
+            .add_system(
+                virtual_resolution::sync_virtual_resolution
+                    .label(LetterboxSystem::Framing)
+                    .before(LetterboxSystem::Layout),
+            )
+            .add_system(
+                validation::validate_screen_units
+                    .after(LetterboxSystem::Framing)
+                    .before(LetterboxSystem::Layout),
+            )
+            .add_system(suspend::apply_suspend.before(LetterboxSystem::Layout))
+            .add_system(change_camera_scaling.label(LetterboxSystem::Layout))
+            .add_system(report_duplicate_letterboxes)
+            .add_system(camera::check_camera_setup)
+            .add_system(camera::rebind_closed_window.before(LetterboxSystem::Layout))
+            .add_system(status::update_letterbox_status.after(LetterboxSystem::Layout))
+            .add_system(report_aspect_range_crossings.after(LetterboxSystem::Layout))
+            .add_system(report_bar_changes.after(LetterboxSystem::Layout))
+            .add_system(bleed::lift_bleeding_entities)
+            .add_system(theme::apply_bar_theme.before(LetterboxSystem::Style))
+            .add_system(
+                style::apply_letterbox_style
+                    .label(LetterboxSystem::Style)
+                    .after(LetterboxSystem::Layout),
+            )
+            .add_system(
+                bounds::clamp_cameras_to_bounds
+                    .label(LetterboxSystem::CameraClamp)
+                    .after(LetterboxSystem::Layout),
+            )
+            .add_system(anchor::apply_safe_anchors.after(LetterboxSystem::Layout))
+            .add_system(offscreen::update_offscreen_indicators.after(LetterboxSystem::CameraClamp))
+            .add_system(regions::update_hud_regions.after(LetterboxSystem::Layout))
+            .add_system(
+                hit_test::detect_bar_clicks
+                    .with_run_criteria(hit_test::pointer_pressed_or_released)
+                    .after(LetterboxSystem::Layout),
+            )
+            .add_system(
+                pixels::update_pixels_per_unit
+                    .after(LetterboxSystem::Layout)
+                    .before(LetterboxSystem::PixelSnap),
+            )
+            .add_system_to_stage(CoreStage::PreUpdate, pixel_snap::restore_unsnapped_cameras)
+            .add_system(
+                pixel_snap::snap_cameras
+                    .label(LetterboxSystem::PixelSnap)
+                    .after(LetterboxSystem::CameraClamp),
+            )
+            .add_system(
+                camera::track_managed_camera
+                    .label(TrackManagedCamera)
+                    .after(LetterboxSystem::PixelSnap),
+            )
+            .add_system(cinematic::play_letterbox_track.before(LetterboxSystem::Layout))
+            .add_system(cinematic::update_cinematic_bars.after(TrackManagedCamera))
+            .add_system(coverage::update_bar_coverage.after(LetterboxSystem::Layout))
+            .add_system(transition::run_transitions.before(LetterboxSystem::Layout))
+            .add_system(transition::update_transition_bars.after(TrackManagedCamera))
+            .add_system(progress::update_bar_progress.after(TrackManagedCamera))
+            .add_system(focus::track_window_focus.before(LetterboxSystem::Layout))
+            .add_system(focus::update_focus_overlay.after(TrackManagedCamera))
+            .add_system(
+                cursor::move_virtual_cursor
+                    .after(LetterboxSystem::Layout)
+                    .before(LetterboxSystem::CameraClamp),
+            )
+            .add_system(cursor::update_virtual_cursor_sprite.after(TrackManagedCamera))
+            .add_system(confine::confine_cursor.after(LetterboxSystem::Layout))
+            .add_system(subtitles::anchor_subtitles.after(TrackManagedCamera))
+            .add_system_to_stage(
+                CoreStage::PostUpdate,
+                bounds::clamp_to_safe_area.before(TransformSystem::TransformPropagate),
+            )
+            .add_system_to_stage(
+                CoreStage::PostUpdate,
+                audit::audit_letterboxes.with_run_criteria(audit::audit_enabled),
+            )
+            .add_system(border::update_safe_area_border.after(TrackManagedCamera));
+
+        if !self.layout_hooks.is_empty() {
+            app
+                .insert_resource(hooks::LayoutHooks {
+                    hooks: self.layout_hooks.clone(),
+                    reader: default(),
+                })
+                .add_system(hooks::run_layout_hooks.exclusive_system().at_end());
+        }
+
+        // Animators run before their values are copied, and both before the layout, so the layout
+        // reflects the tweened values in the same frame.
+        #[cfg(feature = "tweening")]
+        app
+            .add_system(
+                bevy_tweening::component_animator_system::<LetterboxTweenTarget>
+                    .before(tweening::ApplyLetterboxTweens),
+            )
+            .add_system(
+                tweening::apply_letterbox_tweens
+                    .label(tweening::ApplyLetterboxTweens)
+                    .before(LetterboxSystem::Layout),
+            );
+
+        #[cfg(feature = "debug-tools")]
+        aspect_preview::add_preview_camera(app);
+        #[cfg(feature = "debug-tools")]
+        app
+            .init_resource::<AspectRatioSimulator>()
+            .init_resource::<UnitGrid>()
+            .init_resource::<CoordinateReadout>()
+            .add_system(simulate_aspect_ratios)
+            .add_system(log_simulated_layout)
+            .add_system(update_unit_grid.after(TrackManagedCamera))
+            .add_system(update_coordinate_readout.after(LetterboxSystem::PixelSnap))
+            .init_resource::<AspectPreview>()
+            .add_system(aspect_preview::update_aspect_preview.after(LetterboxSystem::PixelSnap));
+
+        #[cfg(feature = "layout-export")]
+        app
+            .init_resource::<LayoutExport>()
+            .add_event::<LayoutExported>()
+            .add_system(layout_export::export_layout.after(LetterboxSystem::Layout));
+
+        // Settings changed in the panel are applied in the same frame.
+        #[cfg(feature = "tweak-panel")]
+        app
+            .init_resource::<TweakPanel>()
+            .add_system(tweak_panel::run_tweak_panel.before(LetterboxSystem::Framing));
+
+        #[cfg(feature = "persistence")]
+        app.add_system(persistence::save_window_preferences.after(LetterboxSystem::Layout));
+
+        #[cfg(feature = "video-modes")]
+        app
+            .init_resource::<video_modes::FullscreenVideoModes>()
+            .add_system(video_modes::update_fullscreen_video_modes.before(LetterboxSystem::Layout));
+
+        // Replayed events are sent before the camera scaling system reads them in the same frame.
+        #[cfg(feature = "event-recording")]
+        app
+            .add_system_to_stage(CoreStage::PreUpdate, replay_window_events)
+            .add_system(record_window_events);
+
+        #[cfg(feature = "screenshot")]
+        app
+            .init_resource::<ScreenshotSettings>()
+            .add_event::<LetterboxScreenshot>()
+            .add_event::<CaptureSafeArea>()
+            .add_event::<SafeAreaScreenshot>()
+            .add_system(crop_captured_frames.after(LetterboxSystem::Layout));
+    }
+}
+
+// Labels for ordering systems relative to the letterboxes. The labelled systems always run and check
+// for changes themselves, because Bevy evaluates a stage's run criteria before any of its systems run:
+// a criterion couldn't see a ScreenUnits or style change made earlier in the same frame, and the
+// letterboxes would lag a frame behind. Only systems whose inputs are settled before the update stage,
+// such as input handling and the post update audit, are skipped with run criteria.
+#[derive(SystemLabel, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum LetterboxSystem {
+    // Derives ScreenUnits from other configuration, like VirtualResolution, before it's validated.
+    Framing,
+    // Computes the LetterboxLayout and applies it to the camera and letterboxes.
+    Layout,
+    // Applies LetterboxStyle to the letterboxes.
+    Style,
+    // Clamps cameras with ClampCameraToBounds, after the layout.
+    CameraClamp,
+    // Snaps cameras with PixelSnap to the pixel grid, after clamping.
+    PixelSnap,
+}
+
+// Component for marking the camera the letterboxes should manage, when there's more than one candidate.
+// Only the projection's scaling is changed, everything else about the camera is left as it is. A camera
+// rendering into an image is letterboxed within the image, which is measured again whenever its size
+// changes. The letterboxes are ordinary sprites which only the 2D camera draws, so with the sprite
+// backend the marked camera should be the 2D camera. LetterboxStatus reports when it isn't. Any camera
+// with an OrthographicProjection component can be managed, including OrthographicCameraBundle::new_3d.
+#[derive(Component)]
+pub struct LetterboxCamera;
+
+// Resource selecting how much the plugin does. It's set from the plugin's configuration.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub enum LetterboxMode {
+    // Manage a camera's projection and draw letterboxes.
+    #[default]
+    Managed,
+    // Only maintain the LetterboxLayout and related resources and send LetterboxLayoutChanged. No
+    // letterboxes are spawned, no camera is created and no projection is touched, for setups which
+    // composite the picture themselves. The layout follows the window in ManagedCamera.
+    ComputeOnly,
+}
+
+// Event sent every time the layout is recomputed.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct LetterboxLayoutChanged(pub LetterboxLayout);
+
+// Event sent for each letterbox whose rectangle changed when a layout was applied, in the order of
+// LetterboxSide::ALL. Rectangles are in world units relative to the camera and in layout pixels.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct BarChanged {
+    pub side: LetterboxSide,
+    pub old: LetterboxRect,
+    pub new: LetterboxRect,
+    pub old_px: LetterboxRect,
+    pub new_px: LetterboxRect,
+}
+
+// Event sent with FillMode::Expand when the window's aspect ratio moves out of the range, so bars
+// appear, or back into it.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct AspectRangeCrossed {
+    pub outside: bool,
+}
+
+// Component claiming a camera's projection for the letterboxes. The managed camera gets it when it's
+// first adopted, and the projection is only written while it's present. Remove it to take over the
+// projection, e.g. to use ScalingMode::WindowSize for a special sequence; inserting it again makes the
+// letterboxes reassert their layout on the next update. The sprite letterboxes are laid out for the
+// managed projection, so they're hidden while the claim is removed and shown again once it's reasserted.
+#[derive(Component)]
+pub struct LetterboxManaged;
+
+// Resource recording which camera the letterboxes manage and the window it renders to. The window is
+// remembered separately because the render texture backend points the camera at an image.
+pub struct ManagedCamera {
+    pub entity: Option<Entity>,
+    pub window: WindowId,
+}
+
+impl Default for ManagedCamera {
+    fn default() -> Self {
+        Self {
+            entity: None,
+            window: WindowId::primary(),
+        }
+    }
+}
+
+// Component for identifying letterbox entities
+#[derive(Component)]
+pub struct Letterbox { pub side: LetterboxSide }
+
+// Component on every letterbox entity, sprites and UI nodes alike, with the part of the window it
+// covers. It's updated in the same frame as the entity. Rounded the same way as SafeAreaPx, so the bars
+// and the area between them tile the window. With the render texture backend that area is the shown image.
+#[derive(Component, Default, Clone, Copy, PartialEq, Debug)]
+pub struct BarRect {
+    // In world units relative to the camera.
+    pub units: LetterboxRect,
+    pub pixels: PhysicalRect,
+}
+
+// Depth the letterbox sprites are drawn at. Bevy 0.7 draws every 3D camera before the 2D camera, and
+// the letterboxes are sprites on the 2D camera, so they always cover 3D scenes. On the 2D camera they
+// cover anything below this depth, so HUD sprites meant to be hidden by the bars go below it and ones
+// meant to show on top of them, like a skip prompt, go between it and the camera's own depth. The
+// plugin's own overlays use that space too, each at an offset above it: the cinematic bars at 0.08,
+// bleeding entities at BLEED_Z, then the progress bar, transitions, subtitles and the virtual cursor on
+// top, at 0.2, 0.5, 0.8 and 0.9 above. bevy_ui is drawn after the 2D camera, above the bars.
+pub const LETTERBOX_Z: f32 = 999.0;
+
+// Resource listing the letterboxes thinner than a pixel in the applied layout. They're hidden rather
+// than drawn as degenerate quads, and their transforms aren't written until they're needed again. While
+// the managed camera's LetterboxManaged claim is removed every letterbox is listed.
+#[derive(Default, PartialEq, Debug)]
+pub(crate) struct CollapsedLetterboxes(pub(crate) Vec<LetterboxSide>);
+
+// Resource holding the applied layout the camera's projection and the letterbox sprites use. It's the
+// LetterboxLayout, except with the render texture backend, where the camera frames the image.
+#[derive(Default, PartialEq, Debug)]
+pub(crate) struct ProjectionLayout(pub(crate) LetterboxLayout);
+
+// Resource holding the applied window layout before the cinematic bars and custom BarCoverage sources
+// cut into it, which they're measured against.
+#[derive(Default, PartialEq, Debug)]
+pub(crate) struct FittedLayout(pub(crate) LetterboxLayout);
+
+// Resource which defines the dimensions of the camera's view.
+#[derive(Default, Clone, Copy, PartialEq, Debug)]
+pub struct ScreenUnits {
+    pub width: f32,
+    pub height: f32,
+}
+
+impl ScreenUnits {
+    // Largest dimension accepted. Beyond this a unit is smaller than the precision of f32 world positions.
+    pub const MAX_DIMENSION: f32 = 1.0e6;
+
+    // Checks that both dimensions are finite, positive and at most MAX_DIMENSION.
+    pub fn validate(&self) -> Result<(), String> {
+        for (name, value) in [("width", self.width), ("height", self.height)] {
+            if !value.is_finite() {
+                return Err(format!("{} is {}, it must be a finite number", name, value));
+            }
+            if value <= 0.0 {
+                return Err(format!("{} is {}, it must be positive", name, value));
+            }
+            if value > Self::MAX_DIMENSION {
+                return Err(format!("{} is {}, it can be at most {}", name, value, Self::MAX_DIMENSION));
+            }
+        }
+        Ok(())
+    }
+}
+
+// Resource for temporarily replacing ScreenUnits, e.g. zooming the framing out during a cutscene.
+// Overrides form a stack and the most recently pushed one is presented. ScreenUnits itself is never
+// modified, so popping the last override restores the base framing exactly.
+#[derive(Default)]
+pub struct SafeAreaOverride {
+    stack: Vec<(SafeAreaOverrideHandle, SafeAreaChange)>,
+    next_handle: u32,
+}
+
+// Identifies a pushed override so it can be popped even if other overrides were pushed after it.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct SafeAreaOverrideHandle(u32);
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum SafeAreaChange {
+    // Present these units instead of ScreenUnits.
+    Units(ScreenUnits),
+    // Multiply both dimensions of ScreenUnits, e.g. 1.2 zooms out by 20%.
+    Scale(f32),
+}
+
+impl SafeAreaOverride {
+    pub fn push(&mut self, change: SafeAreaChange) -> SafeAreaOverrideHandle {
+        let handle = SafeAreaOverrideHandle(self.next_handle);
+        self.next_handle = self.next_handle.wrapping_add(1);
+        self.stack.push((handle, change));
+        handle
+    }
+
+    // Removes the override wherever it is in the stack. Returns false if it was already popped.
+    pub fn pop(&mut self, handle: SafeAreaOverrideHandle) -> bool {
+        match self.stack.iter().position(|(pushed, _)| *pushed == handle) {
+            Some(index) => {
+                self.stack.remove(index);
+                true
+            },
+            None => false,
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        !self.stack.is_empty()
+    }
+
+    // The units which should currently be presented for the given base units.
+    pub fn apply(&self, base: &ScreenUnits) -> ScreenUnits {
+        match self.stack.last() {
+            Some((_, SafeAreaChange::Units(units))) => *units,
+            Some((_, SafeAreaChange::Scale(scale))) => ScreenUnits {
+                width: base.width * scale,
+                height: base.height * scale,
+            },
+            None => *base,
+        }
+    }
+}
+
+fn setup_camera(mut commands: Commands) {
+    commands
+        .spawn_bundle(OrthographicCameraBundle::new_2d())
+        .insert(LetterboxCamera)
+        .insert(LetterboxManaged);
+}
+
+// System to add letterboxes to the world. 
+// When the window is created a window created or resize event will be triggered so we don't need to calculate their values right now.
+fn spawn_letterboxes(mut commands: Commands, style: Res<LetterboxStyle>) {
+    for side in LetterboxSide::ALL {
+        spawn_letterbox(&mut commands, side, style.color_for(side));
+    }
+}
+
+fn spawn_letterbox(
+    commands: &mut Commands,
+    side: LetterboxSide,
+    color: Color
+) {
+    commands
+        .spawn_bundle(SpriteBundle {
+        sprite: Sprite {
+            color,
+            custom_size: Some(Vec2::ZERO),
+            ..default()
+        },
+        ..default()
+    })
+    .insert(Letterbox { side })
+    .insert(BarRect::default());
+}
+
+// The resources the layout is computed from, so the layout system can tell when to recompute.
+#[derive(SystemParam)]
+pub(crate) struct LayoutInputs<'w, 's> {
+    screen_units: Res<'w, ScreenUnits>,
+    safe_area_override: Res<'w, SafeAreaOverride>,
+    insets: Res<'w, PlatformInsets>,
+    reserved_bands: Res<'w, ReservedBands>,
+    pub(crate) backend: Res<'w, LetterboxBackend>,
+    fill_mode: Res<'w, FillMode>,
+    minimum_scale: Res<'w, MinimumScale>,
+    dpi_policy: Res<'w, DpiPolicy>,
+    placement: Res<'w, SafeAreaPlacement>,
+    pub(crate) suspend: Res<'w, LetterboxSuspend>,
+    coverage: Res<'w, BarCoverage>,
+    cinematic_bars: Res<'w, CinematicBars>,
+    #[system_param(ignore)]
+    marker: PhantomData<&'s ()>,
+}
+
+// The sources of the managed target's size.
+#[derive(SystemParam)]
+struct TargetSizes<'w, 's> {
+    windows: Res<'w, Windows>,
+    images: Res<'w, Assets<Image>>,
+    resize_events: EventReader<'w, 's, WindowResized>,
+    created_events: EventReader<'w, 's, WindowCreated>,
+    scale_factor_events: EventReader<'w, 's, WindowScaleFactorChanged>,
+    moved_events: EventReader<'w, 's, WindowMoved>,
+    time: Res<'w, Time>,
+    borderless_settling: Res<'w, BorderlessSettling>,
+    borderless_state: Local<'s, borderless::BorderlessState>,
+}
+
+impl<'w, 's> TargetSizes<'w, 's> {
+    fn window_size(&self, id: WindowId) -> Option<Vec2> {
+        self.windows.get(id).map(|window| Vec2::new(window.width(), window.height()))
+    }
+
+    // Holds a window's new size back while it settles into borderless fullscreen.
+    fn settle_borderless(&mut self, id: WindowId, new_size: Option<Vec2>) -> borderless::Settle {
+        let now = self.time.seconds_since_startup();
+        self.borderless_state.settle(&self.borderless_settling, self.windows.get(id), now, new_size)
+    }
+
+    fn image_size(&self, handle: &Handle<Image>) -> Option<Vec2> {
+        self.images.get(handle).map(|image| {
+            Vec2::new(image.texture_descriptor.size.width as f32, image.texture_descriptor.size.height as f32)
+        })
+    }
+}
+
+impl<'w, 's> LayoutInputs<'w, 's> {
+    pub(crate) fn is_changed(&self) -> bool {
+        self.screen_units.is_changed()
+            || self.safe_area_override.is_changed()
+            || self.insets.is_changed()
+            || self.reserved_bands.is_changed()
+            || self.backend.is_changed()
+            || self.fill_mode.is_changed()
+            || self.minimum_scale.is_changed()
+            || self.dpi_policy.is_changed()
+            || self.placement.is_changed()
+            || self.suspend.is_changed()
+            || self.coverage.is_changed()
+            || self.cinematic_bars.is_changed()
+    }
+
+    // The screen units currently presented, taking overrides into account.
+    pub(crate) fn screen_units(&self) -> ScreenUnits {
+        self.safe_area_override.apply(&self.screen_units)
+    }
+
+    // Computes the layouts for a target size in logical pixels. Window sizes and insets are converted
+    // into layout pixels by the DPI policy, and the reserved bands are added to the insets.
+    // The layouts with BarCoverage applied, as the layout system applies them.
+    pub(crate) fn compute(
+        &self,
+        window_size: Vec2,
+        screen_units: &ScreenUnits,
+        scale_factor: f64,
+    ) -> (LetterboxLayout, LetterboxLayout) {
+        let (window_layout, projection_layout) = self.compute_fitted(window_size, screen_units, scale_factor);
+        (self.cover(window_layout), self.cover(projection_layout))
+    }
+
+    // The layouts fitted around the letterboxes and insets, before BarCoverage's other sources cut into them.
+    fn compute_fitted(
+        &self,
+        window_size: Vec2,
+        screen_units: &ScreenUnits,
+        scale_factor: f64,
+    ) -> (LetterboxLayout, LetterboxLayout) {
+        let dpi_scale = self.dpi_policy.layout_pixels_per_logical(scale_factor);
+        let window_size = window_size * dpi_scale;
+        let insets = self.reserved_bands.add_to(
+            &self.insets.scaled(dpi_scale),
+            window_size,
+            screen_units,
+            dpi_scale,
+            scale_factor,
+        );
+        let (mut window_layout, mut projection_layout) = compute_layouts(
+            window_size,
+            screen_units,
+            &insets,
+            &self.backend,
+            *self.fill_mode,
+            &self.minimum_scale,
+            &self.placement,
+        );
+        window_layout.dpi_scale = dpi_scale;
+        if self.backend.renders_to_camera_target() {
+            projection_layout.dpi_scale = dpi_scale;
+        }
+        (window_layout, projection_layout)
+    }
+
+    // The render texture backend's image always holds the whole safe area, so it isn't covered.
+    fn cover(&self, layout: LetterboxLayout) -> LetterboxLayout {
+        if !self.backend.renders_to_camera_target() {
+            return layout;
+        }
+        coverage::cover_layout(layout, self.coverage.extra(&layout, &self.cinematic_bars))
+    }
+}
+
+fn change_camera_scaling(
+    mut commands: Commands,
+    mut camera_query: Query<
+        (
+            Entity,
+            &Camera,
+            &mut OrthographicProjection,
+            Option<&LetterboxCamera>,
+            Option<ChangeTrackers<LetterboxManaged>>,
+        ),
+        Or<(With<LetterboxCamera>, With<Camera2d>)>,
+    >,
+    mut target_sizes: TargetSizes,
+    mut letterbox_query: Query<(&Letterbox, &mut BarRect)>,
+    inputs: LayoutInputs,
+    mut layout: ResMut<LetterboxLayout>,
+    mut applied_projection: ResMut<ProjectionLayout>,
+    mut fitted: ResMut<FittedLayout>,
+    mut safe_area_px: ResMut<SafeAreaPx>,
+    mut collapsed: ResMut<CollapsedLetterboxes>,
+    mut managed: ResMut<ManagedCamera>,
+    mode: Res<LetterboxMode>,
+    mut layout_events: EventWriter<LetterboxLayoutChanged>,
+    mut last_window_size: Local<Option<Vec2>>,
+    mut last_target: Local<Option<RenderTarget>>,
+    mut last_applied: Local<Option<(LetterboxLayout, LetterboxLayout)>>,
+) {
+    // Manage the camera marked with LetterboxCamera, or the 2D camera if none are marked. In compute
+    // only mode no camera is touched and the layout follows the window in ManagedCamera.
+    let mut managed_camera = None;
+    if *mode == LetterboxMode::Managed {
+        managed_camera = camera_query
+            .iter_mut()
+            .max_by_key(|(_, _, _, marker, _)| marker.is_some());
+        // LetterboxStatus reports the missing camera.
+        if managed_camera.is_none() {
+            return;
+        }
+    }
+
+    let mut newly_adopted = false;
+    let mut reasserted = false;
+    let mut target_image = None;
+    if let Some((camera_entity, camera, _, _, ownership)) = &managed_camera {
+        // The managed window is whichever window the camera renders to, so retargeting the camera at a
+        // newly created window rebinds the letterboxes to it. A camera rendering into an image is laid
+        // out within the image instead, unless the image is the render texture backend's own.
+        let target_window = match &camera.target {
+            RenderTarget::Window(id) => *id,
+            RenderTarget::Image(handle) => {
+                if inputs.backend.renders_to_camera_target() {
+                    target_image = Some(handle.clone());
+                }
+                managed.window
+            },
+        };
+        if managed.window != target_window {
+            managed.window = target_window;
+        }
+
+        newly_adopted = managed.entity != Some(*camera_entity);
+        if newly_adopted {
+            // A newly adopted camera is claimed once. Removing the claim later hands the projection back.
+            commands.entity(*camera_entity).insert(LetterboxManaged);
+            managed.entity = Some(*camera_entity);
+        }
+        reasserted = ownership.as_ref().is_some_and(|ownership| ownership.is_added());
+    }
+    let target_window = managed.window;
+    let target = match &target_image {
+        Some(handle) => RenderTarget::Image(handle.clone()),
+        None => RenderTarget::Window(target_window),
+    };
+    let retargeted = last_target.as_ref() != Some(&target);
+    *last_target = Some(target);
+
+    // Find the most recent size of the managed window. A newly created window doesn't always get a
+    // resize event, so take its initial size from the window itself, as with a window which was just
+    // retargeted.
+    let mut new_window_size = None;
+    for window in target_sizes.resize_events.iter() {
+        if window.id == target_window {
+            new_window_size = Some(Vec2::new(window.width, window.height));
+        }
+    }
+    let mut reread = retargeted;
+    reread |= target_sizes.created_events.iter().any(|event| event.id == target_window);
+    // Also fired when scale_factor_override changes. The logical size may stay the same while the
+    // physical pixels, and the layout under DpiPolicy::Physical, don't.
+    reread |= target_sizes.scale_factor_events.iter().any(|event| event.id == target_window);
+    // Moving between monitors can change the size and scale without a resize event reaching us first.
+    // The window is read once however many events arrived, and an unchanged layout isn't reapplied,
+    // so dragging doesn't thrash.
+    reread |= target_sizes.moved_events.iter().any(|event| event.id == target_window);
+    if reread {
+        new_window_size = target_sizes.window_size(target_window);
+    }
+    if target_image.is_none() {
+        match target_sizes.settle_borderless(target_window, new_window_size) {
+            borderless::Settle::Waiting => return,
+            borderless::Settle::Ready(size) => new_window_size = size,
+        }
+    }
+
+    // Window events don't affect image targets. Images have no resize events, so the image is measured
+    // every frame and only a size different from the last one counts as new, e.g. after the asset
+    // was modified.
+    if let Some(handle) = &target_image {
+        new_window_size = target_sizes
+            .image_size(handle)
+            .filter(|size| retargeted || Some(*size) != *last_window_size);
+    }
+    // Minimized windows can report a size of zero, which can't be laid out, so keep the last layout.
+    let new_window_size = new_window_size.filter(|size| size.x > 0.0 && size.y > 0.0);
+
+    // Without a new window size we still need to recompute if anything else affecting the layout changed,
+    // or the camera was claimed again and needs the layout reasserted.
+    let window_size = match new_window_size.or(*last_window_size) {
+        Some(size) => size,
+        None => return,
+    };
+    // While suspended the window size is remembered for when the layout is applied again on resume.
+    if inputs.suspend.is_suspended() {
+        *last_window_size = Some(window_size);
+        return;
+    }
+    if new_window_size.is_none() && !inputs.is_changed() && !reasserted {
+        return;
+    }
+    *last_window_size = Some(window_size);
+
+    // Overrides can scale valid units into invalid ones, which would produce a garbage layout.
+    let screen_units = inputs.screen_units();
+    if screen_units.validate().is_err() {
+        return;
+    }
+
+    // Images are measured in their own pixels.
+    let scale_factor = match target_image {
+        Some(_) => 1.0,
+        None => target_sizes.windows.get(target_window).map_or(1.0, |window| window.scale_factor()),
+    };
+    let (fitted_layout, fitted_projection) = inputs.compute_fitted(window_size, &screen_units, scale_factor);
+    let window_layout = inputs.cover(fitted_layout);
+    let projection_layout = inputs.cover(fitted_projection);
+
+    // The scale factor can change without the layout changing.
+    let new_safe_area_px = SafeAreaPx::from_layout(&window_layout, scale_factor);
+    if *safe_area_px != new_safe_area_px {
+        *safe_area_px = new_safe_area_px;
+    }
+    for (letterbox, mut bar_rect) in letterbox_query.iter_mut() {
+        let bar = window_layout.bar(letterbox.side);
+        let new_bar_rect = BarRect {
+            units: projection_layout.bar(letterbox.side),
+            pixels: PhysicalRect::from_layout_px(&window_layout, window_layout.to_window_px(bar), scale_factor),
+        };
+        if *bar_rect != new_bar_rect {
+            *bar_rect = new_bar_rect;
+        }
+    }
+
+    // Repeated resize events often produce the same layout, so nothing is written unless it changed,
+    // keeping Changed filters on the letterboxes and projection quiet. A reclaimed camera or resuming
+    // from a suspension always reapplies it.
+    const LAYOUT_EPSILON: f32 = 1e-4;
+    let unchanged = last_applied.is_some_and(|(last_window, last_projection)| {
+        last_window.approx_eq(&window_layout, LAYOUT_EPSILON)
+            && last_projection.approx_eq(&projection_layout, LAYOUT_EPSILON)
+    });
+    if unchanged && !reasserted && !newly_adopted && !inputs.suspend.is_changed() {
+        return;
+    }
+    *last_applied = Some((window_layout, projection_layout));
+
+    *layout = LetterboxLayout {
+        ready: true,
+        ..window_layout
+    };
+    layout_events.send(LetterboxLayoutChanged(*layout));
+    applied_projection.0 = projection_layout;
+    fitted.0 = fitted_layout;
+
+    // The letterboxes are moved over the part of the window they cover once the camera has settled.
+    // The style system hides the collapsed ones.
+    let new_collapsed = LetterboxSide::ALL
+        .iter()
+        .copied()
+        .filter(|side| (projection_layout.bar(*side).size() * projection_layout.pixels_per_unit_axes()).min_element() < 1.0)
+        .collect();
+    let new_collapsed = CollapsedLetterboxes(new_collapsed);
+    if *collapsed != new_collapsed {
+        *collapsed = new_collapsed;
+    }
+
+    // Change the camera's values, unless its claim was removed. Insets can make the safe area off
+    // center in the window, so the projection's bounds are set directly rather than through a fixed
+    // scaling mode.
+    let (_, _, mut orthographic_projection, _, ownership) = match managed_camera {
+        Some(camera) => camera,
+        None => return,
+    };
+    if ownership.is_none() && !newly_adopted {
+        return;
+    }
+    orthographic_projection.scaling_mode = ScalingMode::None;
+    orthographic_projection.left = projection_layout.visible.min.x;
+    orthographic_projection.right = projection_layout.visible.max.x;
+    orthographic_projection.bottom = projection_layout.visible.min.y;
+    orthographic_projection.top = projection_layout.visible.max.y;
+    orthographic_projection.scale = 1.0;
+}
+
+// Computes the layout of the window, and the layout the camera's projection and letterbox sprites use.
+// They're the same except with the render texture backend, where the camera frames the image.
+pub fn compute_layouts(
+    window_size: Vec2,
+    screen_units: &ScreenUnits,
+    insets: &PlatformInsets,
+    backend: &LetterboxBackend,
+    fill_mode: FillMode,
+    minimum_scale: &MinimumScale,
+    placement: &SafeAreaPlacement,
+) -> (LetterboxLayout, LetterboxLayout) {
+    match *backend {
+        // The image always holds exactly ScreenUnits, so it's letterboxed whatever the fill mode or
+        // minimum scale.
+        LetterboxBackend::RenderTexture(settings) => {
+            let layout = compute_letterbox_layout(window_size, screen_units, insets);
+            let image_layout = settings.image_layout(screen_units);
+            let blit_scale = layout.pixels_per_unit / image_layout.pixels_per_unit;
+            if settings.integer_scaling && blit_scale >= 1.0 {
+                let scaled_layout = layout_with_pixels_per_unit(
+                    window_size,
+                    screen_units,
+                    insets,
+                    image_layout.pixels_per_unit * blit_scale.floor(),
+                );
+                (apply_placement(scaled_layout, placement), image_layout)
+            } else {
+                (apply_placement(layout, placement), image_layout)
+            }
+        },
+        _ => {
+            let layout = compute_fill_layout(window_size, screen_units, insets, fill_mode);
+            let layout = apply_minimum_scale(layout, screen_units, insets, minimum_scale);
+            let layout = apply_placement(layout, placement);
+            (layout, layout)
+        },
+    }
+}
+
+// Sends AspectRangeCrossed when the window moves into or out of FillMode::Expand's aspect range.
+fn report_aspect_range_crossings(
+    fill_mode: Res<FillMode>,
+    mut layout_events: EventReader<LetterboxLayoutChanged>,
+    mut crossed_events: EventWriter<AspectRangeCrossed>,
+    mut was_outside: Local<Option<bool>>,
+) {
+    let (min_aspect, max_aspect) = match *fill_mode {
+        FillMode::Expand { min_aspect, max_aspect } => (min_aspect, max_aspect),
+        _ => {
+            *was_outside = None;
+            return;
+        },
+    };
+
+    // The first layout in this mode only sets the starting point.
+    if let Some(LetterboxLayoutChanged(layout)) = layout_events.iter().last() {
+        let outside = is_outside_aspect_range(layout.window_size, &layout.insets, min_aspect, max_aspect);
+        if was_outside.is_some_and(|was_outside| was_outside != outside) {
+            crossed_events.send(AspectRangeCrossed { outside });
+        }
+        *was_outside = Some(outside);
+    }
+}
+
+// Runs after the layout system, so the letterboxes have already moved when the events are read.
+fn report_bar_changes(
+    mut layout_events: EventReader<LetterboxLayoutChanged>,
+    mut bar_events: EventWriter<BarChanged>,
+    mut last_layout: Local<LetterboxLayout>,
+) {
+    let layout = match layout_events.iter().last() {
+        Some(LetterboxLayoutChanged(layout)) => *layout,
+        None => return,
+    };
+
+    for side in LetterboxSide::ALL {
+        let old = last_layout.bar(side);
+        let new = layout.bar(side);
+        if old != new {
+            bar_events.send(BarChanged {
+                side,
+                old,
+                new,
+                old_px: last_layout.to_window_px(old),
+                new_px: layout.to_window_px(new),
+            });
+        }
+    }
+    *last_layout = layout;
+}
+
+// Every letterbox entity for a side is driven by the layout, but more than one per side just
+// z-fights, so warn once when that happens (e.g. a scene file spawned extra bars).
+fn report_duplicate_letterboxes(
+    added_query: Query<(), Added<Letterbox>>,
+    letterbox_query: Query<&Letterbox>,
+    mut warned: Local<bool>,
+) {
+    if *warned || added_query.is_empty() {
+        return;
+    }
+
+    for side in LetterboxSide::ALL {
+        let count = letterbox_query.iter().filter(|letterbox| letterbox.side == side).count();
+        if count > 1 {
+            warn!("Found {} letterbox entities for the {:?} side, they will all be drawn in the same place", count, side);
+            *warned = true;
+        }
+    }
+}
+
+// Hides every letterbox while the managed camera's claim is removed. Reasserting the layout when it's
+// inserted again works out which ones to show.
+fn hide_unclaimed_letterboxes(
+    managed: Res<ManagedCamera>,
+    claim_query: Query<(), With<LetterboxManaged>>,
+    mut collapsed: ResMut<CollapsedLetterboxes>,
+) {
+    // A newly adopted camera's claim is only inserted at the end of the stage.
+    if managed.is_changed() {
+        return;
+    }
+    let unclaimed = managed.entity.is_some_and(|entity| claim_query.get(entity).is_err());
+    if unclaimed && collapsed.0.len() != LetterboxSide::ALL.len() {
+        collapsed.0 = LetterboxSide::ALL.to_vec();
+    }
+}
+
+// Moves each letterbox sprite over the part of the window it covers, relative to wherever the camera
+// ended up this frame. Collapsed letterboxes are left where they were.
+fn place_letterboxes(
+    projection_layout: Res<ProjectionLayout>,
+    collapsed: Res<CollapsedLetterboxes>,
+    camera_position: Res<ManagedCameraPosition>,
+    mut letterbox_query: Query<(&Letterbox, &mut Transform, &mut Sprite)>,
+    added_query: Query<(), Added<Letterbox>>,
+) {
+    let changed = projection_layout.is_changed() || collapsed.is_changed() || camera_position.is_changed();
+    if !changed && added_query.is_empty() {
+        return;
+    }
+    for (letterbox, mut transform, mut sprite) in letterbox_query.iter_mut() {
+        if !collapsed.0.contains(&letterbox.side) {
+            set_letterbox(&mut transform, &mut sprite, projection_layout.0.bar(letterbox.side), camera_position.0);
+        }
+    }
+}
+
+// Bars are sized through the sprite rather than the transform so textured bars don't scale by the image size.
+// Only what changed is written, so Changed filters on the letterboxes stay quiet while nothing moves.
+fn set_letterbox(
+    transform: &mut Mut<Transform>,
+    sprite: &mut Mut<Sprite>,
+    rect: LetterboxRect,
+    camera_position: Vec2,
+) {
+    let size = rect.size();
+    let center = camera_position + rect.center();
+    let translation = Vec3::new(center.x, center.y, LETTERBOX_Z);
+    if sprite.custom_size != Some(size) {
+        sprite.custom_size = Some(size);
+    }
+    if transform.translation != translation {
+        transform.translation = translation;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::window::WindowMode;
+
+    use super::*;
+    use crate::test_utils::*;
+
+    // The units LetterboxTestApp uses unless told otherwise.
+    const UNITS: ScreenUnits = ScreenUnits {
+        width: 16.0,
+        height: 9.0,
+    };
+
+    fn expected_layout(width: f32, height: f32) -> LetterboxLayout {
+        compute_letterbox_layout(Vec2::new(width, height), &UNITS, &PlatformInsets::default())
+    }
+
+    fn managed_app() -> App {
+        LetterboxTestApp::new()
+            .plugin(LetterboxPlugin {
+                spawn_camera: true,
+                ..default()
+            })
+            .window(1600.0, 900.0, 1.0)
+            .build()
+    }
+
+    // The sprite letterboxes for a side, as their translation, size and visibility.
+    fn letterboxes(app: &mut App, side: LetterboxSide) -> Vec<(Vec3, Option<Vec2>, bool)> {
+        let mut query = app.world.query::<(&Letterbox, &Transform, &Sprite, &Visibility)>();
+        query
+            .iter(&app.world)
+            .filter(|(letterbox, ..)| letterbox.side == side)
+            .map(|(_, transform, sprite, visibility)| (transform.translation, sprite.custom_size, visibility.is_visible))
+            .collect()
+    }
+
+    fn visible_sides(app: &mut App) -> Vec<LetterboxSide> {
+        LetterboxSide::ALL
+            .into_iter()
+            .filter(|side| letterboxes(app, *side).iter().all(|(_, _, visible)| *visible))
+            .collect()
+    }
+
+    #[test]
+    fn lays_out_the_created_window() {
+        let app = managed_app();
+        assert_layout(&app, &expected_layout(1600.0, 900.0));
+        assert!(layout(&app).ready);
+    }
+
+    #[test]
+    fn follows_resizes() {
+        let mut app = managed_app();
+        resize(&mut app, 1200.0, 900.0);
+        assert_layout(&app, &expected_layout(1200.0, 900.0));
+    }
+
+    #[test]
+    fn drives_every_letterbox_for_a_side() {
+        let mut app = managed_app();
+        app.world
+            .spawn()
+            .insert_bundle(SpriteBundle::default())
+            .insert(Letterbox { side: LetterboxSide::Left });
+        resize(&mut app, 2000.0, 900.0);
+
+        let expected = layout(&app).bar(LetterboxSide::Left);
+        let bars = letterboxes(&mut app, LetterboxSide::Left);
+        assert_eq!(bars.len(), 2);
+        for (translation, size, _) in bars {
+            assert!(translation.truncate().abs_diff_eq(expected.center(), 1e-4));
+            assert!(size.unwrap().abs_diff_eq(expected.size(), 1e-4));
+        }
+    }
+
+    #[test]
+    fn extreme_windows_and_scale_factors_lay_out() {
+        for scale_factor in [0.5, 1.0, 1.25, 3.75] {
+            let mut app = LetterboxTestApp::new()
+                .plugin(LetterboxPlugin {
+                    spawn_camera: true,
+                    ..default()
+                })
+                .window(1.0, 1.0, scale_factor)
+                .build();
+            for (width, height) in [(1.0, 10000.0), (16384.0, 16384.0), (10000.0, 1.0), (1.0, 1.0), (1280.0, 720.0)] {
+                resize(&mut app, width, height);
+                let layout = layout(&app);
+                assert_eq!(layout.window_size, Vec2::new(width, height));
+                if let Err(problem) = layout.check_invariants(&UNITS) {
+                    panic!("{} at {}x{} and scale factor {}", problem, width, height, scale_factor);
+                }
+            }
+        }
+    }
+
+    // Marks a camera spawned into a windowed app for management and checks its projection frames the layout.
+    fn assert_manages_marked_camera(spawn: impl FnOnce(&mut World) -> Entity) {
+        let mut app = LetterboxTestApp::new().window(1600.0, 900.0, 1.0).build();
+        let camera = spawn(&mut app.world);
+        app.world.entity_mut(camera).insert(LetterboxCamera);
+        resize(&mut app, 2000.0, 900.0);
+
+        assert_eq!(app.world.resource::<ManagedCamera>().entity, Some(camera));
+        assert!(app.world.get::<LetterboxManaged>(camera).is_some());
+        let projection = app.world.get::<OrthographicProjection>(camera).unwrap();
+        let visible = expected_layout(2000.0, 900.0).visible;
+        assert!(matches!(projection.scaling_mode, ScalingMode::None));
+        assert!(Vec2::new(projection.left, projection.bottom).abs_diff_eq(visible.min, 1e-4));
+        assert!(Vec2::new(projection.right, projection.top).abs_diff_eq(visible.max, 1e-4));
+    }
+
+    #[test]
+    fn manages_a_marked_2d_camera() {
+        assert_manages_marked_camera(|world| world.spawn().insert_bundle(OrthographicCameraBundle::new_2d()).id());
+    }
+
+    #[test]
+    fn manages_a_marked_3d_orthographic_camera() {
+        assert_manages_marked_camera(|world| world.spawn().insert_bundle(OrthographicCameraBundle::new_3d()).id());
+    }
+
+    #[test]
+    fn invalid_screen_units_keep_the_last_valid_ones() {
+        let mut app = managed_app();
+        let valid = ScreenUnits {
+            width: 20.0,
+            height: 9.0,
+        };
+        app.insert_resource(valid);
+        app.update();
+        let valid_layout = layout(&app);
+
+        let invalid = [f32::NAN, 0.0, -16.0, ScreenUnits::MAX_DIMENSION * 2.0];
+        for (index, width) in invalid.into_iter().enumerate() {
+            app.insert_resource(ScreenUnits { width, height: 9.0 });
+            // It's reported when it's set, not again every frame after.
+            for _ in 0..3 {
+                app.update();
+            }
+            assert_eq!(*app.world.resource::<ScreenUnits>(), valid, "width {}", width);
+            assert_layout(&app, &valid_layout);
+            assert_eq!(app.world.resource::<ScreenUnitsValidation>().reported, index as u32 + 1);
+        }
+    }
+
+    #[test]
+    fn hands_the_projection_over_while_unclaimed() {
+        let mut app = managed_app();
+        let managed = app.world.resource::<ManagedCamera>().entity.unwrap();
+        resize(&mut app, 2000.0, 900.0);
+        assert_eq!(visible_sides(&mut app), [LetterboxSide::Left, LetterboxSide::Right]);
+
+        app.world.entity_mut(managed).remove::<LetterboxManaged>();
+        app.world.get_mut::<OrthographicProjection>(managed).unwrap().scaling_mode = ScalingMode::WindowSize;
+        app.update();
+        assert_eq!(visible_sides(&mut app), []);
+        resize(&mut app, 2400.0, 900.0);
+        assert_eq!(visible_sides(&mut app), []);
+        let projection = app.world.get::<OrthographicProjection>(managed).unwrap();
+        assert!(matches!(projection.scaling_mode, ScalingMode::WindowSize));
+
+        app.world.entity_mut(managed).insert(LetterboxManaged);
+        app.update();
+        assert_eq!(visible_sides(&mut app), [LetterboxSide::Left, LetterboxSide::Right]);
+        let projection = app.world.get::<OrthographicProjection>(managed).unwrap();
+        assert!(matches!(projection.scaling_mode, ScalingMode::None));
+        assert_eq!(projection.left, expected_layout(2400.0, 900.0).visible.min.x);
+        let (checked, _) = assert_letterboxes_on_camera(&mut app);
+        assert_eq!(checked, 2);
+    }
+
+    #[test]
+    fn virtual_resolution_frames_like_screen_units() {
+        // 640x360 at 32 pixels per unit is 20x11.25 units.
+        let plugin = || LetterboxPlugin {
+            spawn_camera: true,
+            ..default()
+        };
+        let mut by_units =
+            LetterboxTestApp::new().plugin(plugin()).screen_units(20.0, 11.25).window(2000.0, 900.0, 1.0).build();
+        // The VirtualResolution wins over the default 16x9 units on the first frame.
+        let mut by_resolution = LetterboxTestApp::new()
+            .plugin(plugin())
+            .virtual_resolution(640, 360, 32.0)
+            .window(2000.0, 900.0, 1.0)
+            .build();
+        assert!(layout(&by_resolution).ready);
+        assert_eq!(*by_resolution.world.resource::<FramingAuthority>(), FramingAuthority::VirtualResolution);
+        assert_eq!(
+            *by_resolution.world.resource::<ScreenUnits>(),
+            ScreenUnits {
+                width: 20.0,
+                height: 11.25
+            }
+        );
+        assert_layout(&by_resolution, &layout(&by_units));
+
+        for (width, height) in [(1200.0, 900.0), (1600.0, 900.0)] {
+            resize(&mut by_units, width, height);
+            resize(&mut by_resolution, width, height);
+            assert_layout(&by_resolution, &layout(&by_units));
+        }
+
+        // Changing either one reframes the same way.
+        by_units.insert_resource(ScreenUnits {
+            width: 16.0,
+            height: 9.0,
+        });
+        by_units.update();
+        by_resolution.insert_resource(VirtualResolution {
+            width: 512,
+            height: 288,
+            pixels_per_unit: 32.0,
+        });
+        by_resolution.update();
+        assert_layout(&by_resolution, &layout(&by_units));
+        assert_layout(&by_units, &expected_layout(1600.0, 900.0));
+    }
+
+    #[test]
+    fn hides_bars_through_the_exact_aspect() {
+        let mut app = managed_app();
+        assert_eq!(visible_sides(&mut app), []);
+        resize(&mut app, 1600.0, 1200.0);
+        assert_eq!(visible_sides(&mut app), [LetterboxSide::Top, LetterboxSide::Bottom]);
+        resize(&mut app, 1600.0, 900.0);
+        assert_eq!(visible_sides(&mut app), []);
+        resize(&mut app, 2000.0, 900.0);
+        assert_eq!(visible_sides(&mut app), [LetterboxSide::Left, LetterboxSide::Right]);
+    }
+
+    // Collects every layout applied.
+    #[derive(Default)]
+    struct AppliedLayouts(Vec<LetterboxLayout>);
+
+    fn collect_applied_layouts(mut layout_events: EventReader<LetterboxLayoutChanged>, mut applied: ResMut<AppliedLayouts>) {
+        applied.0.extend(layout_events.iter().map(|LetterboxLayoutChanged(layout)| *layout));
+    }
+
+    #[test]
+    fn borderless_fullscreen_lays_out_once_settled() {
+        let mut app = managed_app();
+        app.insert_resource(BorderlessSettling {
+            frames: 3,
+            timeout: f32::INFINITY,
+        })
+        .init_resource::<AppliedLayouts>()
+        .add_system_to_stage(CoreStage::Last, collect_applied_layouts);
+        app.update();
+        app.world.resource_mut::<AppliedLayouts>().0.clear();
+
+        // The window first covers the monitor's work area and only then the whole monitor.
+        app.world.resource_mut::<Windows>().get_primary_mut().unwrap().set_mode(WindowMode::BorderlessFullscreen);
+        resize(&mut app, 1920.0, 1040.0);
+        resize(&mut app, 1920.0, 1080.0);
+        for _ in 0..3 {
+            app.update();
+        }
+
+        let applied = &app.world.resource::<AppliedLayouts>().0;
+        assert_eq!(applied.len(), 1, "{:?}", applied);
+        // The same layout as a window which went straight to the final size.
+        let mut direct = managed_app();
+        resize(&mut direct, 1920.0, 1080.0);
+        assert!(applied[0].approx_eq(&layout(&direct), 1e-3));
+    }
+
+    #[test]
+    fn follows_a_window_moved_to_another_monitor() {
+        use bevy::ecs::event::Events;
+
+        let mut app = managed_app();
+        app.init_resource::<AppliedLayouts>().add_system_to_stage(CoreStage::Last, collect_applied_layouts);
+        app.update();
+        app.world.resource_mut::<AppliedLayouts>().0.clear();
+        let id = WindowId::primary();
+
+        // Mid-drag the window reports a new size without a resize event, over several moves in one frame.
+        app.world.resource_mut::<Windows>().get_mut(id).unwrap().update_actual_size_from_backend(2000, 900);
+        for x in [100, 200, 300] {
+            app.world.resource_mut::<Events<WindowMoved>>().send(WindowMoved {
+                id,
+                position: IVec2::new(x, 0),
+            });
+        }
+        app.update();
+        assert_eq!(app.world.resource::<AppliedLayouts>().0.len(), 1);
+        assert_layout(&app, &expected_layout(2000.0, 900.0));
+
+        // Landing on the denser monitor doubles the physical pixels while the logical size stays the same.
+        let mut windows = app.world.resource_mut::<Windows>();
+        let window = windows.get_mut(id).unwrap();
+        window.update_scale_factor_from_backend(2.0);
+        window.update_actual_size_from_backend(4000, 1800);
+        app.world.resource_mut::<Events<WindowMoved>>().send(WindowMoved {
+            id,
+            position: IVec2::new(2000, 0),
+        });
+        app.world.resource_mut::<Events<WindowScaleFactorChanged>>().send(WindowScaleFactorChanged {
+            id,
+            scale_factor: 2.0,
+        });
+        app.update();
+        assert_eq!(app.world.resource::<AppliedLayouts>().0.len(), 1);
+        assert_layout(&app, &expected_layout(2000.0, 900.0));
+        assert_eq!(*app.world.resource::<SafeAreaPx>(), SafeAreaPx {
+            x: 400,
+            y: 0,
+            width: 3200,
+            height: 1800,
+        });
+    }
+
+    // Counts the frames in which any letterbox transform was written.
+    #[derive(Default)]
+    struct TransformWrites(usize);
+
+    fn count_transform_writes(query: Query<(), (With<Letterbox>, Changed<Transform>)>, mut writes: ResMut<TransformWrites>) {
+        if !query.is_empty() {
+            writes.0 += 1;
+        }
+    }
+
+    #[test]
+    fn identical_resizes_write_the_letterboxes_once() {
+        let mut app = managed_app();
+        app.init_resource::<TransformWrites>()
+            .add_system_to_stage(CoreStage::Last, count_transform_writes);
+        resize(&mut app, 2000.0, 900.0);
+        resize(&mut app, 2000.0, 900.0);
+        assert_eq!(app.world.resource::<TransformWrites>().0, 1);
+    }
+
+    // Asserts that every visible letterbox covers its part of the window around wherever the managed
+    // camera was drawn from, and returns how many there were and the camera's position.
+    fn assert_letterboxes_on_camera(app: &mut App) -> (usize, Vec2) {
+        let managed = app.world.resource::<ManagedCamera>().entity.unwrap();
+        let camera_position = app.world.get::<GlobalTransform>(managed).unwrap().translation.truncate();
+        let layout = layout(app);
+        let mut query = app.world.query::<(&Letterbox, &GlobalTransform, &Sprite, &Visibility)>();
+        let mut checked = 0;
+        // Collapsed letterboxes are hidden and left where they were.
+        for (letterbox, transform, sprite, _) in query.iter(&app.world).filter(|(.., visibility)| visibility.is_visible) {
+            checked += 1;
+            let expected = layout.bar(letterbox.side);
+            let half_size = sprite.custom_size.unwrap() / 2.0;
+            let center = transform.translation.truncate();
+            assert!((center - half_size).abs_diff_eq(camera_position + expected.min, 1e-3), "{:?}", letterbox.side);
+            assert!((center + half_size).abs_diff_eq(camera_position + expected.max, 1e-3), "{:?}", letterbox.side);
+        }
+        (checked, camera_position)
+    }
+
+    #[test]
+    fn letterboxes_follow_a_clamped_camera() {
+        let mut app = managed_app();
+        let managed = app.world.resource::<ManagedCamera>().entity.unwrap();
+        app.world.entity_mut(managed).insert(ClampCameraToBounds {
+            rect: LetterboxRect::new(Vec2::new(100.0, 50.0), Vec2::new(140.0, 80.0)),
+        });
+        resize(&mut app, 2000.0, 900.0);
+
+        let (checked, camera_position) = assert_letterboxes_on_camera(&mut app);
+        assert_eq!(checked, 2);
+        assert!(camera_position.x >= 100.0 && camera_position.y >= 50.0);
+    }
+
+    #[test]
+    fn letterboxes_follow_a_zoom_toward_the_cursor() {
+        use bevy::ecs::event::Events;
+        use bevy::input::mouse::{MouseScrollUnit, MouseWheel};
+        use bevy::math::DVec2;
+
+        let mut app = managed_app();
+        app.world.resource_mut::<ScrollZoom>().enabled = true;
+        resize(&mut app, 2000.0, 900.0);
+        // Near the right edge of the safe area, so zooming in moves the camera right.
+        app.world
+            .resource_mut::<Windows>()
+            .get_primary_mut()
+            .unwrap()
+            .update_cursor_physical_position_from_backend(Some(DVec2::new(1700.0, 450.0)));
+        app.world.resource_mut::<Events<MouseWheel>>().send(MouseWheel {
+            unit: MouseScrollUnit::Line,
+            x: 0.0,
+            y: 3.0,
+        });
+        app.update();
+
+        let (checked, camera_position) = assert_letterboxes_on_camera(&mut app);
+        assert_eq!(checked, 2);
+        assert!(camera_position.x > 0.0);
+    }
+
+    #[test]
+    fn letterboxes_follow_a_two_finger_pan() {
+        use bevy::ecs::event::Events;
+        use bevy::input::touch::{TouchInput, TouchPhase};
+
+        let mut app = managed_app();
+        app.insert_resource(TouchGestures {
+            enabled: true,
+            pinch: false,
+            ..default()
+        });
+        resize(&mut app, 2000.0, 900.0);
+        for (phase, x) in [(TouchPhase::Started, 1000.0), (TouchPhase::Moved, 800.0)] {
+            for (id, y) in [(0, 300.0), (1, 600.0)] {
+                app.world.resource_mut::<Events<TouchInput>>().send(TouchInput {
+                    phase,
+                    position: Vec2::new(x, y),
+                    force: None,
+                    id,
+                });
+            }
+            app.update();
+        }
+
+        let (checked, camera_position) = assert_letterboxes_on_camera(&mut app);
+        assert_eq!(checked, 2);
+        assert!(camera_position.x.abs() > 1.0);
+    }
+
+    #[test]
+    fn letterboxes_follow_a_snapped_camera() {
+        let mut app = managed_app();
+        resize(&mut app, 2000.0, 900.0);
+        let managed = app.world.resource::<ManagedCamera>().entity.unwrap();
+        app.world.entity_mut(managed).insert(PixelSnap::default());
+        // A pixel is a hundredth of a unit, so the camera snaps a third of a pixel left and down.
+        app.world.get_mut::<Transform>(managed).unwrap().translation += Vec3::new(5.0133, 2.0033, 0.0);
+        app.update();
+
+        let (checked, camera_position) = assert_letterboxes_on_camera(&mut app);
+        assert_eq!(checked, 2);
+        assert!(camera_position.abs_diff_eq(Vec2::new(5.01, 2.0), 1e-4));
+    }
+
+    #[test]
+    fn coverage_sources_combine_in_the_layout() {
+        use bevy::ecs::event::Events;
+
+        let mut app = managed_app();
+        // Pillarboxes, with a band at the bottom for touch controls.
+        app.insert_resource(ReservedBands {
+            bottom: BandSize::Logical(100.0),
+            ..default()
+        });
+        resize(&mut app, 2400.0, 900.0);
+        let fitted = layout(&app);
+        assert_eq!(visible_sides(&mut app), [LetterboxSide::Left, LetterboxSide::Right, LetterboxSide::Bottom]);
+
+        app.world.resource_mut::<CinematicBars>().fraction = 0.2;
+        app.update();
+        let covered = layout(&app);
+        // The cinematic bars cover a fifth of the fitted safe area's height each, reaching further up
+        // than the band, while the pillarboxes are left as they were.
+        let expected = |rect: LetterboxRect| LetterboxRect::new(Vec2::new(rect.min.x, -2.7), Vec2::new(rect.max.x, 2.7));
+        assert!(covered.safe_area.approx_eq(&expected(fitted.safe_area), 1e-4), "{:?}", covered.safe_area);
+        assert!(covered.framed.approx_eq(&expected(fitted.framed), 1e-4), "{:?}", covered.framed);
+        assert!(covered.safe_area_px.approx_eq(&covered.to_window_px(covered.safe_area), 1e-3));
+        assert_eq!(covered.bar(LetterboxSide::Left), fitted.bar(LetterboxSide::Left));
+        assert_eq!(covered.bar(LetterboxSide::Right), fitted.bar(LetterboxSide::Right));
+        let (checked, _) = assert_letterboxes_on_camera(&mut app);
+        assert_eq!(checked, 4);
+
+        let events = app.world.resource::<Events<LetterboxLayoutChanged>>();
+        let last = events.get_reader().iter(events).last().copied();
+        assert_eq!(last, Some(LetterboxLayoutChanged(covered)));
+        let coverage = app.world.resource::<BarCoverage>();
+        assert!(coverage.uncovered().approx_eq(&covered.framed, 1e-4));
+        assert!((coverage.source(CoverageSource::Insets).unwrap().bottom - 1.125).abs() < 1e-4);
+        assert!((coverage.combined().bottom - 2.925).abs() < 1e-4);
+
+        // Removing the cinematic bars leaves the band and pillarboxes.
+        app.world.resource_mut::<CinematicBars>().fraction = 0.0;
+        app.update();
+        assert_layout(&app, &fitted);
+    }
+
+    #[test]
+    fn render_texture_backend_owns_the_clear_color_by_default() {
+        let mut app = managed_app();
+        let original = Color::rgb(0.4, 0.4, 0.4);
+        app.insert_resource(ClearColor(original));
+        app.insert_resource(LetterboxStyle {
+            color: Color::rgb(0.1, 0.0, 0.2),
+            ..default()
+        });
+        app.update();
+        assert_eq!(app.world.resource::<ClearColor>().0, original);
+
+        app.insert_resource(LetterboxBackend::RenderTexture(RenderTextureSettings::new(320, 180)));
+        app.update();
+        assert_eq!(app.world.resource::<ClearColor>().0, Color::rgb(0.1, 0.0, 0.2));
+
+        // Turning it off explicitly wins over the backend, and restores the original color.
+        app.insert_resource(LetterboxClearColor { enabled: Some(false) });
+        app.update();
+        assert_eq!(app.world.resource::<ClearColor>().0, original);
+    }
+
+    #[test]
+    fn render_texture_backend_reuses_the_ui_camera() {
+        use bevy::ui::entity::CameraUi;
+
+        let mut app = managed_app();
+        let ui_cameras = |app: &mut App| app.world.query_filtered::<Entity, With<CameraUi>>().iter(&app.world).collect::<Vec<_>>();
+        let backend = LetterboxBackend::RenderTexture(RenderTextureSettings::new(320, 180));
+
+        // Without a UI camera the backend brings its own and takes it away again.
+        app.insert_resource(backend);
+        app.update();
+        assert_eq!(ui_cameras(&mut app).len(), 1);
+        app.insert_resource(LetterboxBackend::Sprites);
+        app.update();
+        assert_eq!(ui_cameras(&mut app), []);
+
+        let game_camera = app.world.spawn().insert_bundle(UiCameraBundle::default()).id();
+        app.insert_resource(backend);
+        app.update();
+        assert_eq!(ui_cameras(&mut app), [game_camera]);
+        app.insert_resource(LetterboxBackend::Sprites);
+        app.update();
+        assert_eq!(ui_cameras(&mut app), [game_camera]);
+    }
+
+    #[test]
+    fn bars_take_the_style_color_unchanged_like_ui_nodes() {
+        let mut app = managed_app();
+        let color = Color::rgb(0.02, 0.02, 0.02);
+        app.world.resource_mut::<LetterboxStyle>().color = color;
+        resize(&mut app, 2000.0, 900.0);
+        let mut query = app.world.query::<(&Letterbox, &Sprite)>();
+        for (_, sprite) in query.iter(&app.world) {
+            assert_eq!(sprite.color, color);
+        }
+
+        // The render texture backend's bars are UI nodes, which bevy_ui converts to linear space the same way.
+        app.insert_resource(LetterboxBackend::RenderTexture(RenderTextureSettings::new(320, 180)));
+        app.update();
+        app.update();
+        let mut query = app.world.query::<(&UiLetterbox, &UiColor)>();
+        assert_eq!(query.iter(&app.world).count(), 4);
+        for (_, ui_color) in query.iter(&app.world) {
+            assert_eq!(ui_color.0, color);
+        }
+    }
+
+    #[test]
+    fn background_camera_follows_the_managed_camera() {
+        use bevy::render::view::RenderLayers;
+
+        let mut app = managed_app();
+        app.insert_resource(BackgroundLayers(Some(RenderLayers::layer(1))));
+        app.update();
+        let managed = app.world.resource::<ManagedCamera>().entity.unwrap();
+        app.world.get_mut::<Transform>(managed).unwrap().translation = Vec3::new(3.0, -2.0, 999.0);
+        resize(&mut app, 2000.0, 900.0);
+
+        let mut query = app
+            .world
+            .query_filtered::<(&Camera, &Transform, &OrthographicProjection, &RenderLayers), With<LetterboxBackgroundCamera>>();
+        let cameras: Vec<_> = query.iter(&app.world).collect();
+        assert_eq!(cameras.len(), 1);
+        let (camera, transform, projection, layers) = cameras[0];
+        assert_eq!(camera.target, RenderTarget::Window(WindowId::primary()));
+        assert_eq!(transform.translation, Vec3::new(3.0, -2.0, 999.0));
+        assert_eq!((projection.left, projection.right), (layout(&app).visible.min.x, layout(&app).visible.max.x));
+        assert_eq!(*layers, RenderLayers::layer(1));
+
+        app.insert_resource(BackgroundLayers(None));
+        app.update();
+        assert_eq!(query.iter(&app.world).count(), 0);
+    }
+
+    // A drag resize as the recorder writes it, with several sizes arriving in one frame.
+    #[cfg(feature = "event-recording")]
+    const DRAG_RESIZE: &str = "[
+        (frame: 0, event: Resized(width: 1500.0, height: 900.0)),
+        (frame: 1, event: Resized(width: 1320.0, height: 880.0)),
+        (frame: 1, event: Resized(width: 1180.0, height: 860.0)),
+        (frame: 3, event: Resized(width: 1000.0, height: 900.0)),
+    ]";
+
+    #[cfg(feature = "event-recording")]
+    #[test]
+    fn replayed_resizes_match_live_ones() {
+        let mut replayed = managed_app();
+        replayed.insert_resource(ResizeReplay::from_ron(DRAG_RESIZE).unwrap());
+        while !replayed.world.resource::<ResizeReplay>().is_finished() {
+            replayed.update();
+        }
+
+        let mut live = managed_app();
+        resize(&mut live, 1000.0, 900.0);
+        assert_layout(&replayed, &layout(&live));
+    }
+}
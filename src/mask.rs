@@ -0,0 +1,282 @@
+use bevy::prelude::*;
+use bevy::reflect::TypeUuid;
+use bevy::render::camera::RenderTarget;
+use bevy::render::render_phase::TrackedRenderPass;
+use bevy::render::render_resource::std140::AsStd140;
+use bevy::render::render_resource::*;
+use bevy::render::renderer::{RenderDevice, RenderQueue};
+use bevy::render::texture::BevyDefault;
+use bevy::render::view::{ExtractedView, Msaa};
+use bevy::render::{RenderApp, RenderStage};
+
+#[cfg(feature = "scissor")]
+use crate::BackgroundLayers;
+use crate::{
+    LetterboxBackend, LetterboxLayout, LetterboxSide, LetterboxStyle, LetterboxSuspend, ManagedCamera, PhysicalRect,
+};
+
+const LETTERBOX_MASK_SHADER_HANDLE: HandleUntyped =
+    HandleUntyped::weak_from_u64(Shader::TYPE_UUID, 10413265729624836593);
+
+// The pass which paints a view's letterboxes, following the backend.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum MaskPass {
+    #[cfg(feature = "scissor")]
+    Scissor,
+    #[cfg(feature = "post-process")]
+    PostProcess,
+}
+
+// Component on the managed camera's view in the render world for the letterbox colors to paint around
+// the framed area of its target, in physical pixels.
+#[derive(Component, Clone, Copy)]
+struct ExtractedLetterboxMask {
+    pass: MaskPass,
+    // False when the bar area is left to BackgroundLayers.
+    paint_bars: bool,
+    rect: PhysicalRect,
+    colors: [Color; 4],
+}
+
+// The prepared mask of a view, with the rectangle clipped to its target. The post process pass paints
+// the whole target, so only the scissor pass reads the rectangle.
+#[derive(Component)]
+#[cfg_attr(not(feature = "scissor"), allow(dead_code))]
+pub(crate) struct ViewLetterboxMask {
+    pub(crate) pass: MaskPass,
+    pub(crate) paint_bars: bool,
+    pub(crate) rect: PhysicalRect,
+    uniform_offset: u32,
+    pipeline: CachedRenderPipelineId,
+}
+
+#[derive(Clone, AsStd140)]
+struct LetterboxMaskUniform {
+    rect: Vec4,
+    left: Vec4,
+    right: Vec4,
+    top: Vec4,
+    bottom: Vec4,
+}
+
+#[derive(Default)]
+struct LetterboxMaskUniforms {
+    uniforms: DynamicUniformVec<LetterboxMaskUniform>,
+    bind_group: Option<BindGroup>,
+}
+
+pub(crate) struct LetterboxMaskPipeline {
+    layout: BindGroupLayout,
+}
+
+impl FromWorld for LetterboxMaskPipeline {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+        let layout = render_device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            entries: &[BindGroupLayoutEntry {
+                binding: 0,
+                visibility: ShaderStages::FRAGMENT,
+                ty: BindingType::Buffer {
+                    ty: BufferBindingType::Uniform,
+                    has_dynamic_offset: true,
+                    min_binding_size: BufferSize::new(LetterboxMaskUniform::std140_size_static() as u64),
+                },
+                count: None,
+            }],
+            label: Some("letterbox_mask_layout"),
+        });
+        Self { layout }
+    }
+}
+
+// Specialized by the MSAA sample count, which has to match the view's color attachment.
+impl SpecializedRenderPipeline for LetterboxMaskPipeline {
+    type Key = u32;
+
+    fn specialize(&self, samples: u32) -> RenderPipelineDescriptor {
+        RenderPipelineDescriptor {
+            vertex: VertexState {
+                shader: LETTERBOX_MASK_SHADER_HANDLE.typed::<Shader>(),
+                entry_point: "vertex".into(),
+                shader_defs: Vec::new(),
+                buffers: Vec::new(),
+            },
+            fragment: Some(FragmentState {
+                shader: LETTERBOX_MASK_SHADER_HANDLE.typed::<Shader>(),
+                shader_defs: Vec::new(),
+                entry_point: "fragment".into(),
+                targets: vec![ColorTargetState {
+                    format: TextureFormat::bevy_default(),
+                    // Blended like the sprite letterboxes, so translucent bar colors look the same.
+                    blend: Some(BlendState::ALPHA_BLENDING),
+                    write_mask: ColorWrites::ALL,
+                }],
+            }),
+            layout: Some(vec![self.layout.clone()]),
+            primitive: PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: MultisampleState {
+                count: samples,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            label: Some("letterbox_mask_pipeline".into()),
+        }
+    }
+}
+
+// Backends drawing with the mask call this from their setup, so it can run more than once.
+pub(crate) fn add_mask_pipeline(app: &mut App) {
+    // Headless apps have no renderer.
+    if app.get_sub_app(RenderApp).is_err() {
+        return;
+    }
+    app.world
+        .resource_mut::<Assets<Shader>>()
+        .set_untracked(LETTERBOX_MASK_SHADER_HANDLE, Shader::from_wgsl(include_str!("letterbox_mask.wgsl")));
+    let render_app = app.sub_app_mut(RenderApp);
+    if render_app.world.contains_resource::<LetterboxMaskPipeline>() {
+        return;
+    }
+    render_app
+        .init_resource::<LetterboxMaskPipeline>()
+        .init_resource::<SpecializedRenderPipelines<LetterboxMaskPipeline>>()
+        .init_resource::<LetterboxMaskUniforms>()
+        .add_system_to_stage(RenderStage::Extract, extract_letterbox_mask)
+        .add_system_to_stage(RenderStage::Prepare, prepare_letterbox_masks)
+        .add_system_to_stage(RenderStage::Queue, queue_letterbox_mask_bind_group);
+}
+
+fn extract_letterbox_mask(
+    mut commands: Commands,
+    backend: Res<LetterboxBackend>,
+    #[cfg(feature = "scissor")] background: Res<BackgroundLayers>,
+    layout: Res<LetterboxLayout>,
+    style: Res<LetterboxStyle>,
+    suspend: Res<LetterboxSuspend>,
+    managed_camera: Res<ManagedCamera>,
+    windows: Res<Windows>,
+    camera_query: Query<&Camera>,
+) {
+    // Only the scissor backend leaves the bar area to BackgroundLayers.
+    let (pass, paint_bars) = match *backend {
+        #[cfg(feature = "scissor")]
+        LetterboxBackend::Scissor => (MaskPass::Scissor, background.0.is_none()),
+        #[cfg(feature = "post-process")]
+        LetterboxBackend::PostProcess => (MaskPass::PostProcess, true),
+        _ => return,
+    };
+    // Hidden or suspended bars leave the whole view drawn, like the sprite letterboxes.
+    if !layout.ready || !style.visible || suspend.is_suspended() {
+        return;
+    }
+    let entity = match managed_camera.entity {
+        Some(entity) => entity,
+        None => return,
+    };
+    let camera = match camera_query.get(entity) {
+        Ok(camera) => camera,
+        Err(_) => return,
+    };
+    // Images are measured in their own pixels, like in the layout.
+    let scale_factor = match &camera.target {
+        RenderTarget::Window(id) => windows.get(*id).map_or(1.0, |window| window.scale_factor()),
+        RenderTarget::Image(_) => 1.0,
+    };
+    commands.get_or_spawn(entity).insert(ExtractedLetterboxMask {
+        pass,
+        paint_bars,
+        rect: layout.framed_physical(scale_factor),
+        colors: LetterboxSide::ALL.map(|side| style.color_for(side)),
+    });
+}
+
+fn prepare_letterbox_masks(
+    mut commands: Commands,
+    render_device: Res<RenderDevice>,
+    render_queue: Res<RenderQueue>,
+    pipeline: Res<LetterboxMaskPipeline>,
+    mut pipelines: ResMut<SpecializedRenderPipelines<LetterboxMaskPipeline>>,
+    mut pipeline_cache: ResMut<PipelineCache>,
+    msaa: Res<Msaa>,
+    mut uniforms: ResMut<LetterboxMaskUniforms>,
+    view_query: Query<(Entity, &ExtractedLetterboxMask, &ExtractedView)>,
+) {
+    uniforms.uniforms.clear();
+    for (entity, mask, view) in view_query.iter() {
+        let rect = clip_to_target(mask.rect, view.width, view.height);
+        let [left, right, top, bottom] = mask.colors.map(|color| Vec4::from(color.as_linear_rgba_f32()));
+        let uniform_offset = uniforms.uniforms.push(LetterboxMaskUniform {
+            rect: Vec4::new(
+                rect.x as f32,
+                rect.y as f32,
+                (rect.x + rect.width) as f32,
+                (rect.y + rect.height) as f32,
+            ),
+            left,
+            right,
+            top,
+            bottom,
+        });
+        commands.entity(entity).insert(ViewLetterboxMask {
+            pass: mask.pass,
+            paint_bars: mask.paint_bars,
+            rect,
+            uniform_offset,
+            pipeline: pipelines.specialize(&mut pipeline_cache, &pipeline, msaa.samples),
+        });
+    }
+    uniforms.uniforms.write_buffer(&render_device, &render_queue);
+}
+
+// Rounding can put the far edges a pixel past the target, which wgpu rejects in a scissor rect.
+fn clip_to_target(rect: PhysicalRect, width: u32, height: u32) -> PhysicalRect {
+    let x = rect.x.min(width);
+    let y = rect.y.min(height);
+    PhysicalRect {
+        x,
+        y,
+        width: rect.width.min(width - x),
+        height: rect.height.min(height - y),
+    }
+}
+
+fn queue_letterbox_mask_bind_group(
+    render_device: Res<RenderDevice>,
+    pipeline: Res<LetterboxMaskPipeline>,
+    mut uniforms: ResMut<LetterboxMaskUniforms>,
+) {
+    let uniforms = &mut *uniforms;
+    uniforms.bind_group = uniforms.uniforms.binding().map(|binding| {
+        render_device.create_bind_group(&BindGroupDescriptor {
+            entries: &[BindGroupEntry { binding: 0, resource: binding }],
+            label: Some("letterbox_mask_bind_group"),
+            layout: &pipeline.layout,
+        })
+    });
+}
+
+// Paints the letterbox colors outside the view's mask rectangle. Nothing is drawn until the pipeline
+// has compiled, which takes a few frames after the first mask is prepared.
+pub(crate) fn draw_letterbox_mask<'w>(world: &'w World, pass: &mut TrackedRenderPass<'w>, mask: &ViewLetterboxMask) {
+    let pipeline = world.resource::<PipelineCache>().get_render_pipeline(mask.pipeline);
+    let bind_group = world.resource::<LetterboxMaskUniforms>().bind_group.as_ref();
+    if let (Some(pipeline), Some(bind_group)) = (pipeline, bind_group) {
+        pass.set_render_pipeline(pipeline);
+        pass.set_bind_group(0, bind_group, &[mask.uniform_offset]);
+        pass.draw(0..3, 0..1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clips_rects_to_the_target() {
+        let rect = |x, y, width, height| PhysicalRect { x, y, width, height };
+        assert_eq!(clip_to_target(rect(200, 0, 1600, 900), 2000, 900), rect(200, 0, 1600, 900));
+        assert_eq!(clip_to_target(rect(0, 219, 1001, 563), 1000, 781), rect(0, 219, 1000, 562));
+        assert_eq!(clip_to_target(rect(2100, 950, 10, 10), 2000, 900), rect(2000, 900, 0, 0));
+    }
+}
@@ -0,0 +1,147 @@
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+
+use crate::{LetterboxLayout, LetterboxRect, ManagedCamera};
+
+// Where a point lands relative to the safe area.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct SafeAreaPoint {
+    // In world units relative to the camera.
+    pub camera_position: Vec2,
+    // In logical pixels from the bottom left of the window. It can be outside the window.
+    pub window_position: Vec2,
+    // Whether it's within the part of the safe area shown in the window, rather than under a letterbox
+    // or off screen.
+    pub inside: bool,
+}
+
+impl SafeAreaPoint {
+    pub fn from_camera_position(layout: &LetterboxLayout, camera_position: Vec2) -> Self {
+        Self {
+            camera_position,
+            window_position: (layout.origin_px() + camera_position * layout.pixels_per_unit_axes())
+                / layout.dpi_scale,
+            inside: layout.shown_safe_area().contains(camera_position),
+        }
+    }
+
+    // Moves a point outside the shown safe area, inset by margin world units, onto its edge along the
+    // line from the camera. None if the point is already within it.
+    pub fn clamp_to_edge(&self, layout: &LetterboxLayout, margin: f32) -> Option<SafeAreaEdgePoint> {
+        let shown = layout.shown_safe_area();
+        let margin = Vec2::splat(margin.max(0.0)).min(shown.size() / 2.0);
+        let bounds = LetterboxRect::new(shown.min + margin, shown.max - margin);
+        if bounds.contains(self.camera_position) {
+            return None;
+        }
+
+        // The camera is normally at the center of the safe area, but cropping can leave it outside.
+        let center = Vec2::ZERO.clamp(bounds.min, bounds.max);
+        let offset = self.camera_position - center;
+        let scale = |offset: f32, min: f32, max: f32| {
+            if offset > 0.0 {
+                max / offset
+            } else if offset < 0.0 {
+                min / offset
+            } else {
+                f32::INFINITY
+            }
+        };
+        let scale_x = scale(offset.x, bounds.min.x - center.x, bounds.max.x - center.x);
+        let scale_y = scale(offset.y, bounds.min.y - center.y, bounds.max.y - center.y);
+        let (scale, normal) = if scale_x <= scale_y {
+            (scale_x, Vec2::new(offset.x.signum(), 0.0))
+        } else {
+            (scale_y, Vec2::new(0.0, offset.y.signum()))
+        };
+        Some(SafeAreaEdgePoint {
+            camera_position: center + offset * scale,
+            normal,
+        })
+    }
+}
+
+// A point on the edge of the safe area, for placing indicators.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct SafeAreaEdgePoint {
+    // In world units relative to the camera.
+    pub camera_position: Vec2,
+    // The outward normal of the edge it's on.
+    pub normal: Vec2,
+}
+
+// System parameter for projecting world positions through the managed camera onto the safe area.
+#[derive(SystemParam)]
+pub struct SafeAreaProjection<'w, 's> {
+    layout: Res<'w, LetterboxLayout>,
+    managed_camera: Res<'w, ManagedCamera>,
+    camera_query: Query<'w, 's, &'static GlobalTransform>,
+}
+
+impl<'w, 's> SafeAreaProjection<'w, 's> {
+    pub fn world_to_safe_area(&self, world: Vec2) -> SafeAreaPoint {
+        SafeAreaPoint::from_camera_position(&self.layout, world - self.camera_position())
+    }
+
+    fn camera_position(&self) -> Vec2 {
+        self.managed_camera
+            .entity
+            .and_then(|entity| self.camera_query.get(entity).ok())
+            .map_or(Vec2::ZERO, |transform| transform.translation.truncate())
+    }
+}
+
+// Component which keeps an entity, e.g. an arrow sprite, on the edge of the safe area pointing towards
+// a target outside it. The entity is rotated so its +x axis points at the target, and hidden while the
+// target is inside the safe area or no longer exists.
+#[derive(Component, Clone, Copy, PartialEq, Debug)]
+pub struct OffscreenIndicator {
+    pub target: Entity,
+    // Distance kept from the edges of the safe area, in world units.
+    pub margin: f32,
+}
+
+impl OffscreenIndicator {
+    pub fn new(target: Entity) -> Self {
+        Self { target, margin: 0.0 }
+    }
+}
+
+pub(crate) fn update_offscreen_indicators(
+    layout: Res<LetterboxLayout>,
+    managed_camera: Res<ManagedCamera>,
+    target_query: Query<&GlobalTransform>,
+    mut indicator_query: Query<(&OffscreenIndicator, &mut Transform, &mut Visibility)>,
+) {
+    let camera_position = managed_camera
+        .entity
+        .and_then(|entity| target_query.get(entity).ok())
+        .map_or(Vec2::ZERO, |transform| transform.translation.truncate());
+
+    for (indicator, mut transform, mut visibility) in indicator_query.iter_mut() {
+        let edge = target_query.get(indicator.target).ok().and_then(|target| {
+            let target = target.translation.truncate() - camera_position;
+            let point = SafeAreaPoint::from_camera_position(&layout, target);
+            if point.inside {
+                return None;
+            }
+            point.clamp_to_edge(&layout, indicator.margin).map(|edge| (edge, target))
+        });
+        let (edge, target) = match edge {
+            Some(edge) => edge,
+            None => {
+                if visibility.is_visible {
+                    visibility.is_visible = false;
+                }
+                continue;
+            },
+        };
+
+        let position = camera_position + edge.camera_position;
+        let toward = target - edge.camera_position;
+        visibility.is_visible = true;
+        transform.translation.x = position.x;
+        transform.translation.y = position.y;
+        transform.rotation = Quat::from_rotation_z(toward.y.atan2(toward.x));
+    }
+}
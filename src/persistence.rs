@@ -0,0 +1,144 @@
+use std::path::PathBuf;
+
+use bevy::prelude::*;
+use bevy::window::WindowId;
+use serde::{Deserialize, Serialize};
+
+use crate::{FillMode, ManagedCamera};
+
+// Where window preferences are kept between sessions.
+pub trait PreferenceStorage: Send + Sync + 'static {
+    // The saved contents, or None if nothing has been saved or it can't be read.
+    fn load(&self) -> Option<String>;
+    fn save(&self, contents: &str) -> std::io::Result<()>;
+}
+
+// Keeps preferences in a file, e.g. in the game's config directory.
+pub struct FileStorage {
+    pub path: PathBuf,
+}
+
+impl PreferenceStorage for FileStorage {
+    fn load(&self) -> Option<String> {
+        std::fs::read_to_string(&self.path).ok()
+    }
+
+    fn save(&self, contents: &str) -> std::io::Result<()> {
+        if let Some(directory) = self.path.parent() {
+            std::fs::create_dir_all(directory)?;
+        }
+        std::fs::write(&self.path, contents)
+    }
+}
+
+// The window size and framing the player last used.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub struct WindowPreferences {
+    // Logical size of the window.
+    pub width: f32,
+    pub height: f32,
+    pub scale_factor: f64,
+    pub fill_mode: FillMode,
+}
+
+impl WindowPreferences {
+    // Reads saved preferences. Missing or corrupted preferences are treated as not saved.
+    pub fn load(storage: &dyn PreferenceStorage) -> Option<Self> {
+        let contents = storage.load()?;
+        match ron::from_str(&contents) {
+            Ok(preferences) => Some(preferences),
+            Err(error) => {
+                debug!("Ignoring unreadable window preferences: {}", error);
+                None
+            },
+        }
+    }
+
+    pub fn save(&self, storage: &dyn PreferenceStorage) -> Result<(), String> {
+        let contents =
+            ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default()).map_err(|error| error.to_string())?;
+        storage.save(&contents).map_err(|error| error.to_string())
+    }
+
+    // Sizes a window to the saved size. The primary window is created when DefaultPlugins is added,
+    // so insert the descriptor before then to open at the saved size without a visible resize.
+    pub fn apply_to(&self, descriptor: &mut WindowDescriptor) {
+        descriptor.width = self.width;
+        descriptor.height = self.height;
+    }
+}
+
+// Resource which saves WindowPreferences for the managed window whenever they change and stay the same
+// for the debounce time, so a window being dragged is saved once. If it's present when the plugin is
+// added, the saved fill mode is applied before the first layout.
+pub struct WindowPersistence {
+    pub storage: Box<dyn PreferenceStorage>,
+    // Seconds the preferences have to stay unchanged before they're saved.
+    pub debounce: f32,
+}
+
+impl WindowPersistence {
+    pub fn new(storage: impl PreferenceStorage) -> Self {
+        Self {
+            storage: Box::new(storage),
+            debounce: 1.0,
+        }
+    }
+}
+
+pub(crate) fn apply_saved_preferences(app: &mut App) {
+    let preferences = match app.world.get_resource::<WindowPersistence>() {
+        Some(persistence) => WindowPreferences::load(&*persistence.storage),
+        None => return,
+    };
+    if let Some(preferences) = preferences {
+        app.insert_resource(preferences.fill_mode);
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct PersistenceState {
+    saved: Option<WindowPreferences>,
+    pending: Option<WindowPreferences>,
+    unchanged_for: f32,
+}
+
+pub(crate) fn save_window_preferences(
+    time: Res<Time>,
+    persistence: Option<Res<WindowPersistence>>,
+    windows: Res<Windows>,
+    managed: Res<ManagedCamera>,
+    fill_mode: Res<FillMode>,
+    mut state: Local<PersistenceState>,
+) {
+    let persistence = match persistence {
+        Some(persistence) => persistence,
+        None => return,
+    };
+    let window = match windows.get(managed.window).or_else(|| windows.get(WindowId::primary())) {
+        Some(window) => window,
+        None => return,
+    };
+    let current = WindowPreferences {
+        width: window.width(),
+        height: window.height(),
+        scale_factor: window.scale_factor(),
+        fill_mode: *fill_mode,
+    };
+
+    if state.pending != Some(current) {
+        state.pending = Some(current);
+        state.unchanged_for = 0.0;
+        return;
+    }
+    state.unchanged_for += time.delta_seconds();
+    if state.saved == Some(current) || state.unchanged_for < persistence.debounce {
+        return;
+    }
+
+    // Failures are only logged, the preferences are tried again when they next change.
+    if let Err(error) = current.save(&*persistence.storage) {
+        warn!("Couldn't save window preferences: {}", error);
+    }
+    state.saved = Some(current);
+}
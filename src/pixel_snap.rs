@@ -0,0 +1,64 @@
+use bevy::prelude::*;
+
+use crate::{LetterboxLayout, PixelsPerUnit};
+
+// Component which snaps a camera's translation to whole screen pixels, so pixel art doesn't crawl
+// during slow pans. The unsnapped translation is put back before each update, so code moving the camera
+// never sees the snapped value and rounding doesn't accumulate. The letterboxes are placed from the
+// snapped translation, so their edges stay on the same screen pixels. Perspective cameras aren't
+// snapped, and nothing is while FillMode::Stretch makes screen pixels non-square in world units.
+#[derive(Component, Default, Clone, Copy, PartialEq, Debug)]
+pub struct PixelSnap {
+    unsnapped: Option<Vec3>,
+}
+
+impl PixelSnap {
+    // The translation before it was last snapped.
+    pub fn unsnapped(&self) -> Option<Vec3> {
+        self.unsnapped
+    }
+}
+
+pub(crate) fn restore_unsnapped_cameras(mut camera_query: Query<(&PixelSnap, &mut Transform)>) {
+    for (snap, mut transform) in camera_query.iter_mut() {
+        if let Some(unsnapped) = snap.unsnapped {
+            if transform.translation != unsnapped {
+                transform.translation = unsnapped;
+            }
+        }
+    }
+}
+
+pub(crate) fn snap_cameras(
+    pixels_per_unit: Res<PixelsPerUnit>,
+    layout: Res<LetterboxLayout>,
+    mut camera_query: Query<(&mut PixelSnap, &mut Transform), With<OrthographicProjection>>,
+) {
+    // One physical screen pixel in world units, from the live layout.
+    let pixel = 1.0 / pixels_per_unit.current;
+    if !pixel.is_finite() || pixel <= 0.0 {
+        return;
+    }
+    // Forget the last snap too, so the camera isn't held at it when unsnapped translations are restored.
+    if layout.is_anisotropic() {
+        for (mut snap, _) in camera_query.iter_mut() {
+            if snap.unsnapped.is_some() {
+                snap.unsnapped = None;
+            }
+        }
+        return;
+    }
+
+    for (mut snap, mut transform) in camera_query.iter_mut() {
+        let unsnapped = transform.translation;
+        snap.unsnapped = Some(unsnapped);
+        let snapped = Vec3::new(
+            (unsnapped.x / pixel).round() * pixel,
+            (unsnapped.y / pixel).round() * pixel,
+            unsnapped.z,
+        );
+        if transform.translation != snapped {
+            transform.translation = snapped;
+        }
+    }
+}
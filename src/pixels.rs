@@ -0,0 +1,129 @@
+use bevy::prelude::*;
+use bevy::render::camera::RenderTarget;
+
+use crate::{LetterboxBackend, LetterboxLayout, ManagedCamera, VirtualResolution};
+
+// Resource relating art pixels to world units. The reference is how many texture pixels make up one
+// world unit, e.g. 32 for 32 pixel tiles, and follows VirtualResolution when there is one. The current
+// value is how many physical screen pixels one world unit covers right now and is kept up to date with
+// the layout.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct PixelsPerUnit {
+    pub reference: f32,
+    pub current: f32,
+}
+
+impl Default for PixelsPerUnit {
+    fn default() -> Self {
+        Self {
+            reference: 1.0,
+            current: 1.0,
+        }
+    }
+}
+
+impl PixelsPerUnit {
+    // The world size of a sprite made of this many texture pixels.
+    pub fn sprite_size_for(&self, pixels: Vec2) -> Vec2 {
+        pixels / self.reference
+    }
+
+    // How many physical screen pixels something this large in world units covers at the moment.
+    pub fn current_screen_size_of(&self, world_size: Vec2) -> Vec2 {
+        world_size * self.current
+    }
+
+    // How many physical screen pixels one texture pixel covers at the moment, e.g. 3 when pixel art is
+    // drawn at triple size.
+    pub fn current_scale(&self) -> f32 {
+        self.current / self.reference
+    }
+}
+
+pub(crate) fn update_pixels_per_unit(
+    layout: Res<LetterboxLayout>,
+    managed: Res<ManagedCamera>,
+    backend: Res<LetterboxBackend>,
+    windows: Res<Windows>,
+    camera_query: Query<&Camera>,
+    resolution: Option<Res<VirtualResolution>>,
+    mut pixels_per_unit: ResMut<PixelsPerUnit>,
+) {
+    // Images are measured in physical pixels already, like in the layout. The render texture backend's
+    // own image is laid out within the window.
+    let renders_to_image = backend.renders_to_camera_target()
+        && managed
+        .entity
+        .and_then(|entity| camera_query.get(entity).ok())
+        .is_some_and(|camera| matches!(camera.target, RenderTarget::Image(_)));
+    let scale_factor = if renders_to_image {
+        1.0
+    } else {
+        windows.get(managed.window).map_or(1.0, |window| window.scale_factor() as f32)
+    };
+
+    let reference = resolution.map_or(pixels_per_unit.reference, |resolution| resolution.pixels_per_unit);
+    let current = layout.pixels_per_unit * scale_factor / layout.dpi_scale;
+    if pixels_per_unit.reference != reference || pixels_per_unit.current != current {
+        pixels_per_unit.reference = reference;
+        pixels_per_unit.current = current;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use crate::LetterboxPlugin;
+
+    #[test]
+    fn converts_between_texture_world_and_screen_pixels() {
+        let pixels_per_unit = PixelsPerUnit {
+            reference: 32.0,
+            current: 96.0,
+        };
+        assert_eq!(pixels_per_unit.sprite_size_for(Vec2::new(64.0, 16.0)), Vec2::new(2.0, 0.5));
+        assert_eq!(pixels_per_unit.current_screen_size_of(Vec2::new(2.0, 0.5)), Vec2::new(192.0, 48.0));
+        assert_eq!(pixels_per_unit.current_scale(), 3.0);
+
+        // A sprite's texture pixels cover current_scale screen pixels each.
+        let texture_size = Vec2::new(48.0, 24.0);
+        let screen_size = pixels_per_unit.current_screen_size_of(pixels_per_unit.sprite_size_for(texture_size));
+        assert_eq!(screen_size, texture_size * pixels_per_unit.current_scale());
+    }
+
+    #[test]
+    fn follows_the_layout_in_physical_pixels() {
+        // 640x360 at 32 pixels per unit is 20x11.25 units, filling a 1280x720 window at twice the size.
+        let mut app = LetterboxTestApp::new()
+            .plugin(LetterboxPlugin {
+                spawn_camera: true,
+                ..default()
+            })
+            .virtual_resolution(640, 360, 32.0)
+            .window(1280.0, 720.0, 1.0)
+            .build();
+        let pixels_per_unit = *app.world.resource::<PixelsPerUnit>();
+        assert_eq!(pixels_per_unit.reference, 32.0);
+        assert_eq!(pixels_per_unit.current, 64.0);
+        assert_eq!(pixels_per_unit.current_scale(), 2.0);
+
+        // The bars take the spare width, so a wider window doesn't change the scale.
+        resize(&mut app, 1600.0, 720.0);
+        assert_eq!(app.world.resource::<PixelsPerUnit>().current, 64.0);
+
+        // At a scale factor of 1.5 each logical pixel covers one and a half physical pixels.
+        let mut app = LetterboxTestApp::new()
+            .plugin(LetterboxPlugin {
+                spawn_camera: true,
+                ..default()
+            })
+            .virtual_resolution(640, 360, 32.0)
+            .window(1280.0, 720.0, 1.5)
+            .build();
+        assert_eq!(app.world.resource::<PixelsPerUnit>().current, 96.0);
+        assert_eq!(app.world.resource::<PixelsPerUnit>().current_scale(), 3.0);
+        resize(&mut app, 640.0, 360.0);
+        assert_eq!(app.world.resource::<PixelsPerUnit>().current_scale(), 1.5);
+    }
+}
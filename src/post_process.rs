@@ -0,0 +1,74 @@
+use bevy::core_pipeline::node::MAIN_PASS_DRIVER;
+use bevy::prelude::*;
+use bevy::render::render_graph::{Node, NodeRunError, RenderGraph, RenderGraphContext};
+use bevy::render::render_phase::TrackedRenderPass;
+use bevy::render::render_resource::{LoadOp, Operations, RenderPassDescriptor};
+use bevy::render::renderer::RenderContext;
+use bevy::render::view::ViewTarget;
+use bevy::render::RenderApp;
+use bevy::ui::node::UI_PASS_DRIVER;
+
+use crate::mask::{add_mask_pipeline, draw_letterbox_mask, MaskPass, ViewLetterboxMask};
+
+// Render graph node painting the post process backend's letterboxes. It runs after the main passes and
+// before bevy_ui, so effects drawn by nodes with an edge into it can't spill over the bars.
+pub const LETTERBOX_MASK_PASS: &str = "letterbox_mask_pass";
+
+// The UI pass is only ordered after the mask when bevy_ui was added first, as DefaultPlugins does.
+pub(crate) fn add_post_process_pass(app: &mut App) {
+    add_mask_pipeline(app);
+    // Headless apps have no renderer.
+    let render_app = match app.get_sub_app_mut(RenderApp) {
+        Ok(render_app) => render_app,
+        Err(_) => return,
+    };
+    let pass_node = LetterboxMaskPassNode::new(&mut render_app.world);
+    let mut graph = render_app.world.resource_mut::<RenderGraph>();
+    graph.add_node(LETTERBOX_MASK_PASS, pass_node);
+    graph.add_node_edge(MAIN_PASS_DRIVER, LETTERBOX_MASK_PASS).unwrap();
+    if graph.get_node_id(UI_PASS_DRIVER).is_ok() {
+        graph.add_node_edge(LETTERBOX_MASK_PASS, UI_PASS_DRIVER).unwrap();
+    }
+}
+
+struct LetterboxMaskPassNode {
+    query: QueryState<(&'static ViewTarget, &'static ViewLetterboxMask)>,
+}
+
+impl LetterboxMaskPassNode {
+    fn new(world: &mut World) -> Self {
+        Self {
+            query: QueryState::new(world),
+        }
+    }
+}
+
+impl Node for LetterboxMaskPassNode {
+    fn update(&mut self, world: &mut World) {
+        self.query.update_archetypes(world);
+    }
+
+    fn run(
+        &self,
+        _graph: &mut RenderGraphContext,
+        render_context: &mut RenderContext,
+        world: &World,
+    ) -> Result<(), NodeRunError> {
+        for (target, mask) in self.query.iter_manual(world) {
+            if mask.pass != MaskPass::PostProcess {
+                continue;
+            }
+            let pass_descriptor = RenderPassDescriptor {
+                label: Some("letterbox_mask_pass"),
+                color_attachments: &[target.get_color_attachment(Operations {
+                    load: LoadOp::Load,
+                    store: true,
+                })],
+                depth_stencil_attachment: None,
+            };
+            let render_pass = render_context.command_encoder.begin_render_pass(&pass_descriptor);
+            draw_letterbox_mask(world, &mut TrackedRenderPass::new(render_pass), mask);
+        }
+        Ok(())
+    }
+}
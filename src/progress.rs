@@ -0,0 +1,122 @@
+use bevy::prelude::*;
+
+use crate::camera::ManagedCameraPosition;
+use crate::{LetterboxLayout, LetterboxRect, LetterboxSide, LETTERBOX_Z};
+
+// Resource for a progress bar drawn inside one of the letterboxes, e.g. while streaming a level.
+// Write the fraction every frame from the loading system. When that letterbox is too thin to hold
+// it, the progress bar falls back to a thin strip along the same edge of the safe area.
+pub struct BarProgress {
+    pub visible: bool,
+    pub side: LetterboxSide,
+    // From 0 to 1.
+    pub fraction: f32,
+    pub color: Color,
+    // Thickness of the fallback strip in logical pixels.
+    pub fallback_thickness: f32,
+    placement: BarProgressPlacement,
+}
+
+impl Default for BarProgress {
+    fn default() -> Self {
+        Self {
+            visible: false,
+            side: LetterboxSide::Bottom,
+            fraction: 0.0,
+            color: Color::WHITE,
+            fallback_thickness: 4.0,
+            placement: BarProgressPlacement::Hidden,
+        }
+    }
+}
+
+impl BarProgress {
+    // Where the progress bar was drawn on the last update.
+    pub fn placement(&self) -> BarProgressPlacement {
+        self.placement
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BarProgressPlacement {
+    InBar,
+    Fallback,
+    Hidden,
+}
+
+// Component for identifying the progress bar entity.
+#[derive(Component)]
+pub struct BarProgressIndicator;
+
+// Letterboxes thinner than this, in logical pixels, can't hold the progress bar.
+const MIN_BAR_THICKNESS: f32 = 8.0;
+
+// Height of the progress bar above the letterboxes, just over the bleed.
+const PROGRESS_Z_OFFSET: f32 = 0.2;
+
+pub(crate) fn spawn_bar_progress(mut commands: Commands) {
+    commands
+        .spawn_bundle(SpriteBundle {
+            sprite: Sprite {
+                custom_size: Some(Vec2::ZERO),
+                ..default()
+            },
+            ..default()
+        })
+        .insert(BarProgressIndicator);
+}
+
+pub(crate) fn update_bar_progress(
+    mut progress: ResMut<BarProgress>,
+    layout: Res<LetterboxLayout>,
+    camera_position: Res<ManagedCameraPosition>,
+    mut indicator_query: Query<(&mut Transform, &mut Sprite, &mut Visibility), With<BarProgressIndicator>>,
+) {
+    if !progress.is_changed() && !layout.is_changed() && !camera_position.is_changed() {
+        return;
+    }
+
+    let bar = layout.bar(progress.side);
+    let horizontal = matches!(progress.side, LetterboxSide::Top | LetterboxSide::Bottom);
+    let thickness = if horizontal { bar.size().y } else { bar.size().x };
+
+    // Fit the track in the middle third of the letterbox, or along the inside edge of the safe area.
+    let (track, placement) = if !progress.visible {
+        (LetterboxRect::default(), BarProgressPlacement::Hidden)
+    } else if thickness * layout.pixels_per_unit / layout.dpi_scale >= MIN_BAR_THICKNESS {
+        let inset = Vec2::splat(thickness / 3.0);
+        (LetterboxRect::new(bar.min + inset, bar.max - inset), BarProgressPlacement::InBar)
+    } else {
+        let strip = progress.fallback_thickness * layout.dpi_scale / layout.pixels_per_unit;
+        let safe = layout.safe_area;
+        let rect = match progress.side {
+            LetterboxSide::Left => LetterboxRect::new(safe.min, Vec2::new(safe.min.x + strip, safe.max.y)),
+            LetterboxSide::Right => LetterboxRect::new(Vec2::new(safe.max.x - strip, safe.min.y), safe.max),
+            LetterboxSide::Top => LetterboxRect::new(Vec2::new(safe.min.x, safe.max.y - strip), safe.max),
+            LetterboxSide::Bottom => LetterboxRect::new(safe.min, Vec2::new(safe.max.x, safe.min.y + strip)),
+        };
+        (rect, BarProgressPlacement::Fallback)
+    };
+
+    // Horizontal tracks fill from the left and vertical tracks from the bottom.
+    let fraction = progress.fraction.clamp(0.0, 1.0);
+    let mut filled = track;
+    if horizontal {
+        filled.max.x = track.min.x + track.size().x * fraction;
+    } else {
+        filled.max.y = track.min.y + track.size().y * fraction;
+    }
+
+    if let Ok((mut transform, mut sprite, mut visibility)) = indicator_query.get_single_mut() {
+        let center = camera_position.0 + filled.center();
+        visibility.is_visible = placement != BarProgressPlacement::Hidden;
+        sprite.color = progress.color;
+        sprite.custom_size = Some(filled.size().max(Vec2::ZERO));
+        transform.translation = Vec3::new( center.x, center.y, LETTERBOX_Z + PROGRESS_Z_OFFSET );
+    }
+
+    // Only write on change so the loading system can use change detection on this resource.
+    if progress.placement != placement {
+        progress.placement = placement;
+    }
+}
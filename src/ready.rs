@@ -0,0 +1,56 @@
+use bevy::ecs::schedule::ShouldRun;
+use bevy::prelude::*;
+
+use crate::{LetterboxLayout, LetterboxStyle, ManagedCamera};
+
+// Run criteria for systems which need the layout, e.g. spawning HUD in the safe area. They run once the
+// first layout has been applied to the projection and letterboxes, and every frame after.
+pub fn letterbox_ready(layout: Res<LetterboxLayout>) -> ShouldRun {
+    if layout.ready {
+        ShouldRun::Yes
+    } else {
+        ShouldRun::No
+    }
+}
+
+// Component for the sprite covering the managed camera's view until the layout is ready, with
+// LetterboxPlugin::cover_until_ready.
+#[derive(Component)]
+pub struct ReadyCover;
+
+// The cover is a child of the camera just in front of it, and large enough to fill any projection the
+// camera could have before it's framed.
+const COVER_SIZE: f32 = 1.0e6;
+const COVER_DEPTH: f32 = 0.1;
+
+pub(crate) fn cover_until_ready(
+    mut commands: Commands,
+    layout: Res<LetterboxLayout>,
+    style: Res<LetterboxStyle>,
+    managed_camera: Res<ManagedCamera>,
+    cover_query: Query<Entity, With<ReadyCover>>,
+) {
+    if layout.ready {
+        for entity in cover_query.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+        return;
+    }
+    let camera = match managed_camera.entity {
+        Some(camera) if cover_query.is_empty() => camera,
+        _ => return,
+    };
+    let cover = commands
+        .spawn_bundle(SpriteBundle {
+            sprite: Sprite {
+                color: style.color,
+                custom_size: Some(Vec2::splat(COVER_SIZE)),
+                ..default()
+            },
+            transform: Transform::from_translation(Vec3::new(0.0, 0.0, -COVER_DEPTH)),
+            ..default()
+        })
+        .insert(ReadyCover)
+        .id();
+    commands.entity(camera).add_child(cover);
+}
@@ -0,0 +1,109 @@
+use bevy::prelude::*;
+use bevy::window::{WindowId, WindowResized, WindowScaleFactorChanged};
+use serde::{Deserialize, Serialize};
+
+// A window event captured from the primary window, tagged with the frame it arrived on.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub struct RecordedWindowEvent {
+    pub frame: u64,
+    pub event: RecordedEventKind,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub enum RecordedEventKind {
+    Resized { width: f32, height: f32 },
+    ScaleFactorChanged { scale_factor: f64 },
+}
+
+// Resource which records primary window events while it exists. Insert it to start recording and
+// call to_ron once the interesting part of the session is over.
+#[derive(Default)]
+pub struct ResizeRecorder {
+    pub events: Vec<RecordedWindowEvent>,
+    frame: u64,
+}
+
+impl ResizeRecorder {
+    pub fn to_ron(&self) -> Result<String, ron::Error> {
+        ron::ser::to_string_pretty(&self.events, ron::ser::PrettyConfig::default())
+    }
+}
+
+// Resource which sends recorded events to the primary window at the frame they were recorded on,
+// counting from the frame the replay was inserted.
+#[derive(Default)]
+pub struct ResizeReplay {
+    pub events: Vec<RecordedWindowEvent>,
+    frame: u64,
+    next: usize,
+}
+
+impl ResizeReplay {
+    pub fn from_ron(ron: &str) -> Result<Self, ron::Error> {
+        Ok(Self {
+            events: ron::from_str(ron)?,
+            ..default()
+        })
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.next >= self.events.len()
+    }
+}
+
+pub(crate) fn record_window_events(
+    recorder: Option<ResMut<ResizeRecorder>>,
+    mut resize_events: EventReader<WindowResized>,
+    mut scale_factor_events: EventReader<WindowScaleFactorChanged>,
+) {
+    let mut recorder = match recorder {
+        Some(recorder) => recorder,
+        None => return,
+    };
+    let frame = recorder.frame;
+
+    // Scale factor changes are recorded first as winit sends them before the matching resize.
+    for event in scale_factor_events.iter().filter(|event| event.id.is_primary()) {
+        recorder.events.push(RecordedWindowEvent {
+            frame,
+            event: RecordedEventKind::ScaleFactorChanged { scale_factor: event.scale_factor },
+        });
+    }
+    for event in resize_events.iter().filter(|event| event.id.is_primary()) {
+        recorder.events.push(RecordedWindowEvent {
+            frame,
+            event: RecordedEventKind::Resized { width: event.width, height: event.height },
+        });
+    }
+    recorder.frame += 1;
+}
+
+pub(crate) fn replay_window_events(
+    replay: Option<ResMut<ResizeReplay>>,
+    mut resize_events: EventWriter<WindowResized>,
+    mut scale_factor_events: EventWriter<WindowScaleFactorChanged>,
+) {
+    let mut replay = match replay {
+        Some(replay) => replay,
+        None => return,
+    };
+
+    while let Some(recorded) = replay.events.get(replay.next).copied() {
+        if recorded.frame > replay.frame {
+            break;
+        }
+        match recorded.event {
+            RecordedEventKind::Resized { width, height } => resize_events.send(WindowResized {
+                id: WindowId::primary(),
+                width,
+                height,
+            }),
+            RecordedEventKind::ScaleFactorChanged { scale_factor } => scale_factor_events.send(WindowScaleFactorChanged {
+                id: WindowId::primary(),
+                scale_factor,
+            }),
+        }
+        replay.next += 1;
+    }
+    replay.frame += 1;
+}
@@ -0,0 +1,183 @@
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+use crate::{LetterboxLayout, LetterboxRect, LetterboxSide};
+
+// The regions every layout has.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum RegionId {
+    TopBar,
+    BottomBar,
+    LeftGutter,
+    RightGutter,
+    // The area not covered by letterboxes.
+    Picture,
+}
+
+impl RegionId {
+    pub const ALL: [RegionId; 5] = [
+        RegionId::TopBar,
+        RegionId::BottomBar,
+        RegionId::LeftGutter,
+        RegionId::RightGutter,
+        RegionId::Picture,
+    ];
+}
+
+// A rectangle in world units relative to the camera, and the same rectangle in layout pixels.
+#[derive(Default, Clone, Copy, PartialEq, Debug)]
+pub struct Region {
+    pub world: LetterboxRect,
+    pub px: LetterboxRect,
+}
+
+impl Region {
+    pub fn from_world(layout: &LetterboxLayout, world: LetterboxRect) -> Self {
+        Self {
+            world,
+            px: layout.to_window_px(world),
+        }
+    }
+}
+
+// Function deriving a custom region from the layout, in world units relative to the camera.
+pub type RegionFn = fn(&LetterboxLayout) -> LetterboxRect;
+
+// Resource holding named regions of the current layout for HUD code, recomputed whenever the layout
+// changes. Custom regions added with define are recomputed along with the built in ones.
+#[derive(Default)]
+pub struct HudRegions {
+    builtin: HashMap<RegionId, Region>,
+    custom: HashMap<String, Region>,
+    definitions: Vec<(String, RegionFn)>,
+    // Set when a definition changed, since this resource's own updates would look like changes.
+    redefined: bool,
+}
+
+impl HudRegions {
+    pub fn get(&self, id: RegionId) -> Region {
+        self.builtin.get(&id).copied().unwrap_or_default()
+    }
+
+    // A custom region, if one was defined with this name.
+    pub fn custom(&self, name: &str) -> Option<Region> {
+        self.custom.get(name).copied()
+    }
+
+    // Adds a custom region, replacing any with the same name. It has a value from the next update.
+    pub fn define(&mut self, name: impl Into<String>, region: RegionFn) {
+        let name = name.into();
+        self.definitions.retain(|(defined, _)| *defined != name);
+        self.definitions.push((name, region));
+        self.redefined = true;
+    }
+
+    fn update(&mut self, layout: &LetterboxLayout) {
+        for id in RegionId::ALL {
+            let world = match id {
+                RegionId::TopBar => layout.bar(LetterboxSide::Top),
+                RegionId::BottomBar => layout.bar(LetterboxSide::Bottom),
+                RegionId::LeftGutter => layout.bar(LetterboxSide::Left),
+                RegionId::RightGutter => layout.bar(LetterboxSide::Right),
+                RegionId::Picture => layout.framed,
+            };
+            self.builtin.insert(id, Region::from_world(layout, world));
+        }
+        self.custom = self
+            .definitions
+            .iter()
+            .map(|(name, region)| (name.clone(), Region::from_world(layout, region(layout))))
+            .collect();
+        self.redefined = false;
+    }
+}
+
+pub(crate) fn update_hud_regions(layout: Res<LetterboxLayout>, mut regions: ResMut<HudRegions>) {
+    if layout.is_changed() || regions.redefined {
+        regions.update(&layout);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use crate::{compute_letterbox_layout, LetterboxPlugin, PlatformInsets, ScreenUnits};
+
+    const UNITS: ScreenUnits = ScreenUnits {
+        width: 16.0,
+        height: 9.0,
+    };
+
+    fn rect(min_x: f32, min_y: f32, max_x: f32, max_y: f32) -> LetterboxRect {
+        LetterboxRect::new(Vec2::new(min_x, min_y), Vec2::new(max_x, max_y))
+    }
+
+    // The top right quarter of the safe area, e.g. for a minimap.
+    fn minimap(layout: &LetterboxLayout) -> LetterboxRect {
+        LetterboxRect::new(layout.safe_area.center(), layout.safe_area.max)
+    }
+
+    #[test]
+    fn pins_the_regions_for_representative_windows() {
+        // Window size in logical pixels and each region in world units and in pixels.
+        let cases = [
+            ((2000.0, 900.0), [
+                (RegionId::TopBar, rect(-8.0, 4.5, 8.0, 4.5), rect(200.0, 900.0, 1800.0, 900.0)),
+                (RegionId::BottomBar, rect(-8.0, -4.5, 8.0, -4.5), rect(200.0, 0.0, 1800.0, 0.0)),
+                (RegionId::LeftGutter, rect(-10.0, -4.5, -8.0, 4.5), rect(0.0, 0.0, 200.0, 900.0)),
+                (RegionId::RightGutter, rect(8.0, -4.5, 10.0, 4.5), rect(1800.0, 0.0, 2000.0, 900.0)),
+                (RegionId::Picture, rect(-8.0, -4.5, 8.0, 4.5), rect(200.0, 0.0, 1800.0, 900.0)),
+            ]),
+            ((1600.0, 1200.0), [
+                (RegionId::TopBar, rect(-8.0, 4.5, 8.0, 6.0), rect(0.0, 1050.0, 1600.0, 1200.0)),
+                (RegionId::BottomBar, rect(-8.0, -6.0, 8.0, -4.5), rect(0.0, 0.0, 1600.0, 150.0)),
+                (RegionId::LeftGutter, rect(-8.0, -6.0, -8.0, 6.0), rect(0.0, 0.0, 0.0, 1200.0)),
+                (RegionId::RightGutter, rect(8.0, -6.0, 8.0, 6.0), rect(1600.0, 0.0, 1600.0, 1200.0)),
+                (RegionId::Picture, rect(-8.0, -4.5, 8.0, 4.5), rect(0.0, 150.0, 1600.0, 1050.0)),
+            ]),
+            ((1600.0, 900.0), [
+                (RegionId::TopBar, rect(-8.0, 4.5, 8.0, 4.5), rect(0.0, 900.0, 1600.0, 900.0)),
+                (RegionId::BottomBar, rect(-8.0, -4.5, 8.0, -4.5), rect(0.0, 0.0, 1600.0, 0.0)),
+                (RegionId::LeftGutter, rect(-8.0, -4.5, -8.0, 4.5), rect(0.0, 0.0, 0.0, 900.0)),
+                (RegionId::RightGutter, rect(8.0, -4.5, 8.0, 4.5), rect(1600.0, 0.0, 1600.0, 900.0)),
+                (RegionId::Picture, rect(-8.0, -4.5, 8.0, 4.5), rect(0.0, 0.0, 1600.0, 900.0)),
+            ]),
+        ];
+        for ((width, height), expected) in cases {
+            let layout = compute_letterbox_layout(Vec2::new(width, height), &UNITS, &PlatformInsets::default());
+            let mut regions = HudRegions::default();
+            regions.define("minimap", minimap);
+            regions.update(&layout);
+            for (id, world, px) in expected {
+                let region = regions.get(id);
+                assert!(region.world.approx_eq(&world, 1e-4), "{:?} in {}x{}: {:?}", id, width, height, region);
+                assert!(region.px.approx_eq(&px, 1e-3), "{:?} in {}x{}: {:?}", id, width, height, region);
+            }
+            let minimap = regions.custom("minimap").unwrap();
+            assert!(minimap.world.approx_eq(&rect(0.0, 0.0, 8.0, 4.5), 1e-4));
+            assert!(minimap.px.approx_eq(&layout.to_window_px(minimap.world), 1e-3));
+        }
+    }
+
+    #[test]
+    fn follows_the_applied_layout() {
+        let mut app = LetterboxTestApp::new()
+            .plugin(LetterboxPlugin {
+                spawn_camera: true,
+                ..default()
+            })
+            .window(1600.0, 900.0, 1.0)
+            .build();
+        assert!(app.world.resource::<HudRegions>().custom("minimap").is_none());
+        app.world.resource_mut::<HudRegions>().define("minimap", minimap);
+        app.update();
+        let regions = app.world.resource::<HudRegions>();
+        assert!(regions.custom("minimap").unwrap().px.approx_eq(&rect(800.0, 450.0, 1600.0, 900.0), 1e-3));
+
+        resize(&mut app, 2000.0, 900.0);
+        let regions = app.world.resource::<HudRegions>();
+        assert!(regions.get(RegionId::LeftGutter).px.approx_eq(&rect(0.0, 0.0, 200.0, 900.0), 1e-3));
+        assert!(regions.custom("minimap").unwrap().px.approx_eq(&rect(1000.0, 450.0, 1800.0, 900.0), 1e-3));
+    }
+}
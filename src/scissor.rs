@@ -0,0 +1,111 @@
+use bevy::core_pipeline::{draw_2d_graph, MainPass2dNode, Transparent2d};
+use bevy::prelude::*;
+use bevy::render::render_graph::{Node, NodeRunError, RenderGraph, RenderGraphContext, SlotInfo, SlotType};
+use bevy::render::render_phase::{DrawFunctions, RenderPhase, TrackedRenderPass};
+use bevy::render::render_resource::{LoadOp, Operations, RenderPassDescriptor};
+use bevy::render::renderer::RenderContext;
+use bevy::render::view::{ExtractedView, ViewTarget};
+use bevy::render::RenderApp;
+
+use crate::mask::{add_mask_pipeline, draw_letterbox_mask, MaskPass, ViewLetterboxMask};
+
+// The scissor backend needs its own 2D main pass, so the core one is replaced with one which behaves
+// the same for every other view. Edges other plugins added to the core node are lost, so this has to
+// be added before them, which LetterboxPlugin is when added after DefaultPlugins.
+pub(crate) fn add_scissor_pass(app: &mut App) {
+    add_mask_pipeline(app);
+    // Headless apps have no renderer.
+    let render_app = match app.get_sub_app_mut(RenderApp) {
+        Ok(render_app) => render_app,
+        Err(_) => return,
+    };
+    let pass_node = ScissoredMainPass2dNode::new(&mut render_app.world);
+    let mut graph = render_app.world.resource_mut::<RenderGraph>();
+    let draw_2d = match graph.get_sub_graph_mut(draw_2d_graph::NAME) {
+        Some(draw_2d) => draw_2d,
+        None => return,
+    };
+    draw_2d.remove_node(draw_2d_graph::node::MAIN_PASS).unwrap();
+    draw_2d.add_node(draw_2d_graph::node::MAIN_PASS, pass_node);
+    let input_node = draw_2d.input_node().unwrap().id;
+    draw_2d
+        .add_slot_edge(
+            input_node,
+            draw_2d_graph::input::VIEW_ENTITY,
+            draw_2d_graph::node::MAIN_PASS,
+            MainPass2dNode::IN_VIEW,
+        )
+        .unwrap();
+}
+
+// The core 2D main pass, except that the scissor backend's view gets its letterboxes painted first and
+// everything else clipped to the rest of the target. With BackgroundLayers the background camera has
+// already drawn the bar area, so it's only clipped.
+struct ScissoredMainPass2dNode {
+    query: QueryState<(&'static RenderPhase<Transparent2d>, &'static ViewTarget), With<ExtractedView>>,
+    mask_query: QueryState<&'static ViewLetterboxMask>,
+}
+
+impl ScissoredMainPass2dNode {
+    fn new(world: &mut World) -> Self {
+        Self {
+            query: QueryState::new(world),
+            mask_query: QueryState::new(world),
+        }
+    }
+}
+
+impl Node for ScissoredMainPass2dNode {
+    fn input(&self) -> Vec<SlotInfo> {
+        vec![SlotInfo::new(MainPass2dNode::IN_VIEW, SlotType::Entity)]
+    }
+
+    fn update(&mut self, world: &mut World) {
+        self.query.update_archetypes(world);
+        self.mask_query.update_archetypes(world);
+    }
+
+    fn run(
+        &self,
+        graph: &mut RenderGraphContext,
+        render_context: &mut RenderContext,
+        world: &World,
+    ) -> Result<(), NodeRunError> {
+        let view_entity = graph.get_input_entity(MainPass2dNode::IN_VIEW)?;
+        let (transparent_phase, target) = self
+            .query
+            .get_manual(world, view_entity)
+            .expect("view entity should exist");
+
+        let pass_descriptor = RenderPassDescriptor {
+            label: Some("main_pass_2d"),
+            color_attachments: &[target.get_color_attachment(Operations {
+                load: LoadOp::Load,
+                store: true,
+            })],
+            depth_stencil_attachment: None,
+        };
+        let render_pass = render_context.command_encoder.begin_render_pass(&pass_descriptor);
+        let mut tracked_pass = TrackedRenderPass::new(render_pass);
+
+        let mask = self.mask_query.get_manual(world, view_entity).ok();
+        if let Some(mask) = mask.filter(|mask| mask.pass == MaskPass::Scissor) {
+            if mask.paint_bars {
+                draw_letterbox_mask(world, &mut tracked_pass, mask);
+            }
+            let rect = mask.rect;
+            // wgpu accepts an empty scissor rect, but there's no point drawing anything into it.
+            if rect.width == 0 || rect.height == 0 {
+                return Ok(());
+            }
+            tracked_pass.set_scissor_rect(rect.x, rect.y, rect.width, rect.height);
+        }
+
+        let mut draw_functions = world.resource::<DrawFunctions<Transparent2d>>().write();
+        for item in &transparent_phase.items {
+            let draw_function = draw_functions.get_mut(item.draw_function).unwrap();
+            draw_function.draw(world, &mut tracked_pass, view_entity, item);
+        }
+        Ok(())
+    }
+}
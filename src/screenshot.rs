@@ -0,0 +1,248 @@
+use std::path::PathBuf;
+
+use bevy::prelude::*;
+use bevy::render::render_resource::{Extent3d, TextureDimension};
+
+use crate::SafeAreaPx;
+
+// Bevy doesn't provide a way to capture the rendered frame, so frames captured by other means are
+// sent with this event and answered with a SafeAreaScreenshot holding just the game picture.
+pub struct CaptureSafeArea {
+    // The full window frame in physical pixels.
+    pub frame: Image,
+}
+
+pub struct SafeAreaScreenshot {
+    // Exactly the size of SafeAreaPx when the frame was cropped.
+    pub image: Image,
+}
+
+// Resource choosing which variants each capture also produces as LetterboxScreenshot events, and the
+// paths suggested for saving them: directory/prefix_0001_kind.png, counting captures from 1. The
+// SafeAreaScreenshot event is always sent as well.
+pub struct ScreenshotSettings {
+    pub window: bool,
+    pub safe_area: bool,
+    pub presentation: bool,
+    // Physical pixels of the surrounding frame, letterboxes included, kept around the safe area in the
+    // presentation variant.
+    pub presentation_padding: u32,
+    pub directory: PathBuf,
+    pub prefix: String,
+    captures: u32,
+}
+
+impl Default for ScreenshotSettings {
+    fn default() -> Self {
+        Self {
+            window: false,
+            safe_area: false,
+            presentation: false,
+            presentation_padding: 32,
+            directory: PathBuf::from("screenshots"),
+            prefix: "screenshot".to_string(),
+            captures: 0,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum ScreenshotKind {
+    // The captured frame unchanged: the window's full physical size, letterboxes included.
+    Window,
+    // Just the game picture, exactly the size of SafeAreaPx.
+    SafeArea,
+    // The safe area plus presentation_padding pixels of the frame around it on each side, cut off where
+    // it reaches the edge of the window. Each side's padding is min(presentation_padding, distance from
+    // the safe area to the window's edge).
+    Presentation,
+}
+
+impl ScreenshotKind {
+    fn suffix(&self) -> &'static str {
+        match self {
+            ScreenshotKind::Window => "window",
+            ScreenshotKind::SafeArea => "safe_area",
+            ScreenshotKind::Presentation => "presentation",
+        }
+    }
+}
+
+// One variant of a capture. The crate doesn't encode images, so saving it to path is up to the receiver.
+pub struct LetterboxScreenshot {
+    pub kind: ScreenshotKind,
+    pub image: Image,
+    pub path: PathBuf,
+}
+
+pub(crate) fn crop_captured_frames(
+    safe_area_px: Res<SafeAreaPx>,
+    mut settings: ResMut<ScreenshotSettings>,
+    mut capture_events: EventReader<CaptureSafeArea>,
+    mut screenshot_events: EventWriter<SafeAreaScreenshot>,
+    mut variant_events: EventWriter<LetterboxScreenshot>,
+) {
+    for capture in capture_events.iter() {
+        let cropped = match crop_to_safe_area(&capture.frame, &safe_area_px) {
+            Some(image) => image,
+            None => {
+                warn!("Captured frame doesn't contain the safe area {:?}", *safe_area_px);
+                continue;
+            },
+        };
+
+        if settings.window || settings.safe_area || settings.presentation {
+            settings.captures += 1;
+        }
+        let mut variants = Vec::new();
+        if settings.window {
+            variants.push((ScreenshotKind::Window, capture.frame.clone()));
+        }
+        if settings.safe_area {
+            variants.push((ScreenshotKind::SafeArea, cropped.clone()));
+        }
+        if settings.presentation {
+            let frame_size = capture.frame.texture_descriptor.size;
+            let padding = settings.presentation_padding;
+            let left = safe_area_px.x.saturating_sub(padding);
+            let top = safe_area_px.y.saturating_sub(padding);
+            let right = (safe_area_px.x + safe_area_px.width).saturating_add(padding).min(frame_size.width);
+            let bottom = (safe_area_px.y + safe_area_px.height).saturating_add(padding).min(frame_size.height);
+            if let Some(image) = crop(&capture.frame, left, top, right - left, bottom - top) {
+                variants.push((ScreenshotKind::Presentation, image));
+            }
+        }
+        for (kind, image) in variants {
+            let name = format!("{}_{:04}_{}.png", settings.prefix, settings.captures, kind.suffix());
+            variant_events.send(LetterboxScreenshot {
+                kind,
+                image,
+                path: settings.directory.join(name),
+            });
+        }
+
+        screenshot_events.send(SafeAreaScreenshot { image: cropped });
+    }
+}
+
+// Copies the safe area out of a full window frame. Returns None if the frame is smaller than the
+// safe area, e.g. because it was captured before the window was resized.
+pub fn crop_to_safe_area(frame: &Image, safe_area_px: &SafeAreaPx) -> Option<Image> {
+    crop(frame, safe_area_px.x, safe_area_px.y, safe_area_px.width, safe_area_px.height)
+}
+
+// Copies a rectangle in pixels from the top left out of an image, or None if it doesn't fit inside.
+fn crop(frame: &Image, x: u32, y: u32, width: u32, height: u32) -> Option<Image> {
+    let frame_size = frame.texture_descriptor.size;
+    let format = frame.texture_descriptor.format;
+    let bytes_per_pixel = format.describe().block_size as usize;
+
+    if x + width > frame_size.width || y + height > frame_size.height {
+        return None;
+    }
+
+    let frame_row = frame_size.width as usize * bytes_per_pixel;
+    let cropped_row = width as usize * bytes_per_pixel;
+    let mut data = Vec::with_capacity(cropped_row * height as usize);
+    for row in y..y + height {
+        let start = row as usize * frame_row + x as usize * bytes_per_pixel;
+        data.extend_from_slice(&frame.data[start..start + cropped_row]);
+    }
+
+    Some(Image::new(
+        Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        format,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::event::Events;
+    use bevy::render::render_resource::TextureFormat;
+
+    use super::*;
+    use crate::test_utils::*;
+    use crate::LetterboxPlugin;
+
+    // A frame whose pixels hold their own position, x in red and blue, y in green and alpha.
+    fn numbered_frame(width: u32, height: u32) -> Image {
+        let data = (0..height)
+            .flat_map(|y| (0..width).flat_map(move |x| [x as u8, y as u8, (x >> 8) as u8, (y >> 8) as u8]))
+            .collect();
+        Image::new(
+            Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            data,
+            TextureFormat::Rgba8UnormSrgb,
+        )
+    }
+
+    fn pixel(image: &Image, x: u32, y: u32) -> [u8; 4] {
+        let start = (y * image.texture_descriptor.size.width + x) as usize * 4;
+        image.data[start..start + 4].try_into().unwrap()
+    }
+
+    fn screenshots(app: &App) -> Vec<Image> {
+        let events = app.world.resource::<Events<SafeAreaScreenshot>>();
+        events.get_reader().iter(events).map(|screenshot| screenshot.image.clone()).collect()
+    }
+
+    #[test]
+    fn crops_captures_to_exactly_the_safe_area() {
+        // Window size in logical pixels and scale factor.
+        for (width, height, scale_factor) in [(2000.0, 900.0, 1.0), (1600.0, 1200.0, 1.5), (1000.0, 1000.0, 2.0)] {
+            let mut app = LetterboxTestApp::new()
+                .plugin(LetterboxPlugin {
+                    spawn_camera: true,
+                    ..default()
+                })
+                .window(width, height, scale_factor)
+                .build();
+            let safe_area_px = *app.world.resource::<SafeAreaPx>();
+            let frame_width = (width as f64 * scale_factor).round() as u32;
+            let frame_height = (height as f64 * scale_factor).round() as u32;
+            app.world.resource_mut::<Events<CaptureSafeArea>>().send(CaptureSafeArea {
+                frame: numbered_frame(frame_width, frame_height),
+            });
+            app.update();
+
+            let screenshots = screenshots(&app);
+            assert_eq!(screenshots.len(), 1);
+            let image = &screenshots[0];
+            let size = image.texture_descriptor.size;
+            assert_eq!((size.width, size.height), (safe_area_px.width, safe_area_px.height), "{}x{}", width, height);
+            let frame = numbered_frame(frame_width, frame_height);
+            assert_eq!(pixel(image, 0, 0), pixel(&frame, safe_area_px.x, safe_area_px.y));
+            assert_eq!(
+                pixel(image, size.width - 1, size.height - 1),
+                pixel(&frame, safe_area_px.x + size.width - 1, safe_area_px.y + size.height - 1)
+            );
+        }
+    }
+
+    #[test]
+    fn skips_frames_smaller_than_the_safe_area() {
+        let mut app = LetterboxTestApp::new()
+            .plugin(LetterboxPlugin {
+                spawn_camera: true,
+                ..default()
+            })
+            .window(2000.0, 900.0, 1.0)
+            .build();
+        app.world.resource_mut::<Events<CaptureSafeArea>>().send(CaptureSafeArea {
+            frame: numbered_frame(1600, 900),
+        });
+        app.update();
+        assert!(screenshots(&app).is_empty());
+    }
+}
@@ -0,0 +1,350 @@
+use bevy::prelude::*;
+use bevy::render::camera::{RenderTarget, ScalingMode};
+
+use crate::backend::{render_target_image, ui_rect_style};
+use crate::camera::{add_extra_camera, extra_camera_bundle};
+use crate::{
+    compute_letterbox_layout, LetterboxLayout, LetterboxRect, LetterboxSide, LetterboxStyle, ManagedCamera,
+    PlatformInsets, ScreenUnits,
+};
+
+// Markers for each player's camera. Bevy renders one active camera per marker type, so each player
+// needs their own.
+#[derive(Component, Default)]
+struct SplitPlayer1Camera;
+
+#[derive(Component, Default)]
+struct SplitPlayer2Camera;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SplitDirection {
+    // The first player on the left and the second on the right.
+    SideBySide,
+    // The first player on top and the second below.
+    Stacked,
+}
+
+// Resource for local split screen. The framed area inside the shared letterboxes is divided between two
+// players, each shown through their own camera and letterboxed to their own ScreenUnits, with a
+// divider bar in the letterbox color between them. Bevy 0.7 cameras can't draw into part of a window,
+// so the player cameras render into images which are shown with bevy_ui, which needs a UI camera. The
+// managed camera still renders the whole window underneath.
+//
+// The plugin spawns a camera with SplitPlayerCamera for each player, placed where the managed camera
+// was, and moving them is up to the game. They're despawned again when the split is turned off.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct SplitLayout {
+    // No direction shows the managed camera alone.
+    pub direction: Option<SplitDirection>,
+    // The first player's share of the framed area, from 0 to 1.
+    pub ratio: f32,
+    // Width of the divider bar in logical pixels.
+    pub divider: f32,
+    // What each player's camera shows. Without units a player shows the same as ScreenUnits.
+    pub players: [Option<ScreenUnits>; 2],
+}
+
+impl Default for SplitLayout {
+    fn default() -> Self {
+        Self {
+            direction: None,
+            ratio: 0.5,
+            divider: 8.0,
+            players: [None; 2],
+        }
+    }
+}
+
+impl SplitLayout {
+    pub fn new(direction: SplitDirection) -> Self {
+        Self {
+            direction: Some(direction),
+            ..default()
+        }
+    }
+}
+
+// Component for the cameras showing each player's view, numbered from 0.
+#[derive(Component)]
+pub struct SplitPlayerCamera {
+    pub player: usize,
+}
+
+// Component for the UI nodes drawing the split: the player views, their letterboxes and the divider.
+#[derive(Component)]
+pub struct SplitViewNode;
+
+// Where one player's view is shown. Rectangles in pixels are in layout pixels from the bottom left of
+// the window, like LetterboxLayout::safe_area_px.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct SplitView {
+    pub camera: Entity,
+    // The player's part of the window, including their letterboxes.
+    pub area_px: LetterboxRect,
+    // Where the player's ScreenUnits are shown, e.g. for anchoring their HUD.
+    pub safe_area_px: LetterboxRect,
+    // The player's ScreenUnits in world units relative to their camera.
+    pub safe_area: LetterboxRect,
+}
+
+// Resource holding each player's view while the window is split, in player order. It's empty otherwise.
+#[derive(Default, Clone, PartialEq, Debug)]
+pub struct SplitViews {
+    pub views: Vec<SplitView>,
+}
+
+impl SplitViews {
+    pub fn view(&self, player: usize) -> Option<&SplitView> {
+        self.views.get(player)
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct SplitState {
+    cameras: Vec<Entity>,
+    // Each player's view, their letterboxes and then the divider, in the order they're laid out.
+    nodes: Vec<Entity>,
+    // Each player's image and its size in physical pixels.
+    images: Vec<(Handle<Image>, Vec2)>,
+    // The inputs the split was last built for.
+    built: Option<(SplitLayout, LetterboxLayout, ScreenUnits, Color, f64)>,
+    warned: bool,
+}
+
+// What a node of the split shows, with its rectangle in logical pixels.
+enum SplitNode {
+    View(LetterboxRect, Handle<Image>),
+    Bar(LetterboxRect, Color),
+}
+
+pub(crate) fn update_split_layout(
+    mut commands: Commands,
+    split: Res<SplitLayout>,
+    layout: Res<LetterboxLayout>,
+    screen_units: Res<ScreenUnits>,
+    style: Res<LetterboxStyle>,
+    managed_camera: Res<ManagedCamera>,
+    windows: Res<Windows>,
+    mut images: ResMut<Assets<Image>>,
+    mut views: ResMut<SplitViews>,
+    transform_query: Query<&GlobalTransform>,
+    ui_camera_query: Query<(), With<CameraUi>>,
+    mut player_query: Query<(&mut Camera, &mut OrthographicProjection), With<SplitPlayerCamera>>,
+    mut node_query: Query<(&mut Style, &mut UiColor, &mut UiImage), With<SplitViewNode>>,
+    mut state: Local<SplitState>,
+) {
+    let state = &mut *state;
+    let direction = match split.direction {
+        Some(direction) => direction,
+        None => {
+            if state.built.take().is_some() {
+                for entity in state.cameras.drain(..).chain(state.nodes.drain(..)) {
+                    commands.entity(entity).despawn_recursive();
+                }
+                for (image, _) in state.images.drain(..) {
+                    images.remove(&image);
+                }
+                views.views.clear();
+            }
+            return;
+        },
+    };
+
+    if ui_camera_query.is_empty() && !state.warned {
+        warn!("The split screen views are drawn with bevy_ui, but there's no UI camera to show them");
+        state.warned = true;
+    }
+
+    let scale_factor = windows.get(managed_camera.window).map_or(1.0, |window| window.scale_factor());
+    let inputs = (*split, *layout, *screen_units, style.color, scale_factor);
+    if state.built == Some(inputs) {
+        return;
+    }
+    state.built = Some(inputs);
+
+    let (first_area, divider, second_area) = split_area(
+        layout.to_window_px(layout.framed),
+        direction,
+        split.ratio,
+        split.divider.max(0.0) * layout.dpi_scale,
+    );
+    let physical_per_layout = scale_factor as f32 / layout.dpi_scale;
+    let mut new_views = Vec::new();
+    let mut nodes = Vec::new();
+    for (player, area) in [first_area, second_area].into_iter().enumerate() {
+        let units = split.players[player].unwrap_or(*screen_units);
+        let player_layout = compute_letterbox_layout(area.size(), &units, &PlatformInsets::default());
+        let shown = player_layout.to_window_px(player_layout.framed);
+        let shown = LetterboxRect::new(area.min + shown.min, area.min + shown.max);
+
+        // Images are only replaced when the view's size in physical pixels changes.
+        let render_size = (shown.size() * physical_per_layout).round().max(Vec2::ONE);
+        let image = match state.images.get(player) {
+            Some((image, size)) if *size == render_size => image.clone(),
+            _ => {
+                let image = images.add(render_target_image(render_size.x as u32, render_size.y as u32, false));
+                let entry = (image.clone(), render_size);
+                match state.images.get_mut(player) {
+                    Some(old) => {
+                        images.remove(&old.0);
+                        *old = entry;
+                    },
+                    None => state.images.push(entry),
+                }
+                image
+            },
+        };
+        let framed = player_layout.framed;
+        let set_projection = |projection: &mut OrthographicProjection| {
+            projection.scaling_mode = ScalingMode::None;
+            projection.left = framed.min.x;
+            projection.right = framed.max.x;
+            projection.bottom = framed.min.y;
+            projection.top = framed.max.y;
+            projection.scale = 1.0;
+        };
+
+        let existing = state.cameras.get(player).copied();
+        let camera = match existing.and_then(|entity| player_query.get_mut(entity).ok().map(|camera| (entity, camera))) {
+            Some((entity, (mut camera, mut projection))) => {
+                let target = RenderTarget::Image(image.clone());
+                if camera.target != target {
+                    camera.target = target;
+                }
+                set_projection(&mut projection);
+                entity
+            },
+            None => {
+                let transform = managed_camera
+                    .entity
+                    .and_then(|entity| transform_query.get(entity).ok())
+                    .map_or(Transform::default(), |transform| Transform::from_matrix(transform.compute_matrix()));
+                let entity = match player {
+                    0 => spawn_player_camera::<SplitPlayer1Camera>(&mut commands, image.clone(), transform, set_projection),
+                    _ => spawn_player_camera::<SplitPlayer2Camera>(&mut commands, image.clone(), transform, set_projection),
+                };
+                commands.entity(entity).insert(SplitPlayerCamera { player });
+                // A player camera the game despawned is replaced.
+                match state.cameras.get_mut(player) {
+                    Some(camera) => *camera = entity,
+                    None => state.cameras.push(entity),
+                }
+                entity
+            },
+        };
+
+        // UI is positioned in logical pixels.
+        nodes.push(SplitNode::View(shown.scaled(1.0 / layout.dpi_scale), image));
+        for side in LetterboxSide::ALL {
+            let bar = area.frame_side(shown, side);
+            nodes.push(SplitNode::Bar(bar.scaled(1.0 / layout.dpi_scale), style.color_for(side)));
+        }
+
+        new_views.push(SplitView {
+            camera,
+            area_px: area,
+            safe_area_px: shown,
+            safe_area: player_layout.safe_area,
+        });
+    }
+    nodes.push(SplitNode::Bar(divider.scaled(1.0 / layout.dpi_scale), style.color));
+
+    // The nodes are updated in place, and only spawned again if any of them went missing.
+    if state.nodes.len() != nodes.len() || state.nodes.iter().any(|entity| !node_query.contains(*entity)) {
+        for entity in state.nodes.drain(..) {
+            commands.entity(entity).despawn_recursive();
+        }
+        state.nodes = nodes.into_iter().map(|node| spawn_split_node(&mut commands, node)).collect();
+    } else {
+        for (entity, node) in state.nodes.iter().zip(nodes) {
+            let (mut node_style, mut color, mut image) = node_query.get_mut(*entity).unwrap();
+            let (rect, new_color, new_image) = match node {
+                SplitNode::View(rect, handle) => (rect, Color::WHITE, handle),
+                SplitNode::Bar(rect, color) => (rect, color, UiImage::default().0),
+            };
+            *node_style = ui_rect_style(rect);
+            if color.0 != new_color {
+                color.0 = new_color;
+            }
+            if image.0 != new_image {
+                image.0 = new_image;
+            }
+        }
+    }
+
+    if views.views != new_views {
+        views.views = new_views;
+    }
+}
+
+fn spawn_player_camera<M: Component + Default>(
+    commands: &mut Commands,
+    image: Handle<Image>,
+    transform: Transform,
+    set_projection: impl Fn(&mut OrthographicProjection),
+) -> Entity {
+    let mut bundle = extra_camera_bundle::<M>(RenderTarget::Image(image));
+    set_projection(&mut bundle.orthographic_projection);
+    bundle.transform = transform;
+    commands.spawn_bundle(bundle).id()
+}
+
+fn spawn_split_node(commands: &mut Commands, node: SplitNode) -> Entity {
+    match node {
+        SplitNode::View(rect, image) => commands
+            .spawn_bundle(ImageBundle {
+                style: ui_rect_style(rect),
+                image: UiImage(image),
+                ..default()
+            })
+            .insert(SplitViewNode)
+            .id(),
+        SplitNode::Bar(rect, color) => spawn_split_bar(commands, rect, color),
+    }
+}
+
+fn spawn_split_bar(commands: &mut Commands, rect: LetterboxRect, color: Color) -> Entity {
+    commands
+        .spawn_bundle(NodeBundle {
+            style: ui_rect_style(rect),
+            color: color.into(),
+            ..default()
+        })
+        .insert(SplitViewNode)
+        .id()
+}
+
+// Divides an area into the first player's part, the divider and the second player's part.
+fn split_area(
+    area: LetterboxRect,
+    direction: SplitDirection,
+    ratio: f32,
+    divider: f32,
+) -> (LetterboxRect, LetterboxRect, LetterboxRect) {
+    let ratio = if ratio.is_finite() { ratio.clamp(0.0, 1.0) } else { 0.5 };
+    match direction {
+        SplitDirection::SideBySide => {
+            let divider = divider.min(area.size().x);
+            let first = area.min.x + (area.size().x - divider) * ratio;
+            (
+                LetterboxRect::new(area.min, Vec2::new(first, area.max.y)),
+                LetterboxRect::new(Vec2::new(first, area.min.y), Vec2::new(first + divider, area.max.y)),
+                LetterboxRect::new(Vec2::new(first + divider, area.min.y), area.max),
+            )
+        },
+        SplitDirection::Stacked => {
+            let divider = divider.min(area.size().y);
+            let first = area.max.y - (area.size().y - divider) * ratio;
+            (
+                LetterboxRect::new(Vec2::new(area.min.x, first), area.max),
+                LetterboxRect::new(Vec2::new(area.min.x, first - divider), Vec2::new(area.max.x, first)),
+                LetterboxRect::new(area.min, Vec2::new(area.max.x, first - divider)),
+            )
+        },
+    }
+}
+
+pub(crate) fn add_player_cameras(app: &mut App) {
+    add_extra_camera::<SplitPlayer1Camera>(app, "letterbox_split_player_1_driver");
+    add_extra_camera::<SplitPlayer2Camera>(app, "letterbox_split_player_2_driver");
+}
@@ -0,0 +1,165 @@
+use std::fmt;
+
+use bevy::prelude::*;
+use bevy::render::camera::{Camera2d, RenderTarget};
+use bevy::window::WindowId;
+
+use crate::{
+    LetterboxBackend, LetterboxCamera, LetterboxMode, ManagedCamera, ScreenUnits, TransparentLetterboxes, LETTERBOX_Z,
+};
+
+// A reason the letterboxes aren't doing what they should.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum LetterboxError {
+    // No camera is marked with LetterboxCamera and there's no 2D camera to adopt.
+    NoManagedCamera,
+    // More than one camera could be managed and it's unclear which one was meant.
+    AmbiguousCamera,
+    // The window the managed camera renders to doesn't exist.
+    NoTargetWindow,
+    // ScreenUnits is missing or fails ScreenUnits::validate.
+    InvalidScreenUnits,
+    // The managed camera isn't the 2D camera, which is the only one Bevy draws sprites with, so the
+    // letterbox sprites are drawn with a different projection or not at all.
+    BarsNotOn2dCamera,
+    // The managed camera's near and far planes don't include LETTERBOX_Z, so the letterboxes are clipped.
+    BarsOutsideDepthRange,
+    // TransparentLetterboxes is enabled, but the backend isn't the render texture one or the primary
+    // window wasn't created transparent, so the letterboxes can't be holes.
+    TransparencyUnsupported,
+}
+
+impl fmt::Display for LetterboxError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let message = match self {
+            LetterboxError::NoManagedCamera => "no 2D camera or camera marked with LetterboxCamera was found",
+            LetterboxError::AmbiguousCamera => "more than one camera could be managed, mark one with LetterboxCamera",
+            LetterboxError::NoTargetWindow => "the managed camera's window doesn't exist",
+            LetterboxError::InvalidScreenUnits => "ScreenUnits is missing or invalid",
+            LetterboxError::BarsNotOn2dCamera => "the managed camera isn't the 2D camera, so it doesn't draw the letterboxes",
+            LetterboxError::BarsOutsideDepthRange => "the managed camera's near and far planes clip the letterboxes",
+            LetterboxError::TransparencyUnsupported => {
+                "transparent letterboxes need the render texture backend and a window created transparent"
+            },
+        };
+        f.write_str(message)
+    }
+}
+
+impl std::error::Error for LetterboxError {}
+
+// Resource listing the problems currently stopping the letterboxes from working, e.g. for showing a
+// developer facing notice. Problems are removed as soon as they're resolved.
+#[derive(Default, Clone, PartialEq, Debug)]
+pub struct LetterboxStatus {
+    problems: Vec<LetterboxError>,
+}
+
+impl LetterboxStatus {
+    pub fn problems(&self) -> &[LetterboxError] {
+        &self.problems
+    }
+
+    pub fn has(&self, problem: LetterboxError) -> bool {
+        self.problems.contains(&problem)
+    }
+
+    pub fn is_ok(&self) -> bool {
+        self.problems.is_empty()
+    }
+}
+
+pub(crate) fn update_letterbox_status(
+    mode: Res<LetterboxMode>,
+    backend: Res<LetterboxBackend>,
+    managed: Res<ManagedCamera>,
+    windows: Res<Windows>,
+    screen_units: Option<Res<ScreenUnits>>,
+    transparency: Res<TransparentLetterboxes>,
+    window_descriptor: Option<Res<WindowDescriptor>>,
+    camera_query: Query<(&Camera, Option<&LetterboxCamera>, Option<&Camera2d>)>,
+    projection_query: Query<(&GlobalTransform, &OrthographicProjection)>,
+    mut status: ResMut<LetterboxStatus>,
+) {
+    let mut problems = Vec::new();
+
+    // Camera problems only matter when a camera is meant to be managed. Marked cameras take priority
+    // over the 2D camera, like when choosing the managed camera.
+    let mut target = RenderTarget::Window(managed.window);
+    if *mode == LetterboxMode::Managed {
+        let marked = camera_query.iter().filter(|(_, marker, _)| marker.is_some()).count();
+        let unmarked_2d = camera_query
+            .iter()
+            .filter(|(_, marker, camera_2d)| marker.is_none() && camera_2d.is_some())
+            .count();
+        let candidates = if marked > 0 { marked } else { unmarked_2d };
+        if candidates == 0 {
+            problems.push(LetterboxError::NoManagedCamera);
+        } else if candidates > 1 {
+            problems.push(LetterboxError::AmbiguousCamera);
+        }
+
+        if let Some(entity) = managed.entity {
+            if let Ok((camera, _, camera_2d)) = camera_query.get(entity) {
+                target = camera.target.clone();
+
+                // The render texture backend draws its letterboxes with bevy_ui instead, and the post
+                // process backend paints them over any camera. The scissor backend only scissors the
+                // 2D pass.
+                let needs_2d = match *backend {
+                    LetterboxBackend::Sprites => true,
+                    #[cfg(feature = "scissor")]
+                    LetterboxBackend::Scissor => true,
+                    _ => false,
+                };
+                if needs_2d && camera_2d.is_none() {
+                    problems.push(LetterboxError::BarsNotOn2dCamera);
+                }
+            }
+            if let Ok((transform, projection)) = projection_query.get(entity) {
+                let depth = transform.translation.z - LETTERBOX_Z;
+                if *backend == LetterboxBackend::Sprites && (depth < projection.near || depth > projection.far) {
+                    problems.push(LetterboxError::BarsOutsideDepthRange);
+                }
+            }
+        }
+    }
+
+    // A camera rendering into its own image doesn't need a window, but the render texture backend's
+    // image is still shown in one.
+    let needs_window = !matches!(target, RenderTarget::Image(_)) || !backend.renders_to_camera_target();
+    if needs_window && windows.get(managed.window).is_none() {
+        problems.push(LetterboxError::NoTargetWindow);
+    }
+
+    if screen_units.is_none_or(|units| units.validate().is_err()) {
+        problems.push(LetterboxError::InvalidScreenUnits);
+    }
+
+    // Only the primary window's descriptor is kept around, so other windows are taken on trust.
+    if transparency.enabled {
+        let opaque_window = managed.window == WindowId::primary()
+            && window_descriptor.is_none_or(|descriptor| !descriptor.transparent);
+        if !transparency.is_active(&backend) || opaque_window {
+            problems.push(LetterboxError::TransparencyUnsupported);
+        }
+    }
+
+    if problems == status.problems {
+        return;
+    }
+    for problem in problems.iter().filter(|problem| !status.problems.contains(problem)) {
+        match problem {
+            LetterboxError::NoManagedCamera | LetterboxError::AmbiguousCamera => warn!("Letterboxes: {}", problem),
+            LetterboxError::NoTargetWindow
+            | LetterboxError::InvalidScreenUnits
+            | LetterboxError::BarsNotOn2dCamera
+            | LetterboxError::BarsOutsideDepthRange
+            | LetterboxError::TransparencyUnsupported => error!("Letterboxes: {}", problem),
+        }
+    }
+    for problem in status.problems.iter().filter(|problem| !problems.contains(problem)) {
+        info!("Letterboxes: resolved, {}", problem);
+    }
+    status.problems = problems;
+}
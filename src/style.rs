@@ -0,0 +1,532 @@
+use bevy::ecs::system::Command;
+use bevy::prelude::*;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use bevy::render::texture::DEFAULT_IMAGE_HANDLE;
+use bevy::sprite::Rect;
+
+use crate::camera::ManagedCameraPosition;
+use crate::{
+    CollapsedLetterboxes, Letterbox, LetterboxLayout, LetterboxRect, LetterboxSide, LetterboxSuspend, LETTERBOX_Z,
+};
+
+// Resource which defines how the letterboxes look.
+pub struct LetterboxStyle {
+    pub visible: bool,
+    // Converted to linear space the same way bevy_ui converts UiColor, so a bar and a UI node given the
+    // same Color draw the same pixels. Bevy 0.7 only tonemaps lit PBR materials, in their own shader,
+    // and no backend draws the bars with one, so black bars are exactly #000000 with nothing to bypass.
+    pub color: Color,
+    // Replaces color with a color per side, e.g. warm horizontal bars and cool pillarboxes.
+    pub side_colors: Option<SideColors>,
+    // Image drawn on every letterbox, stretched over the bar unless slices are given.
+    pub texture: Option<Handle<Image>>,
+    // Borders of the texture which shouldn't stretch, so decorated edges keep their size as the bars resize.
+    pub slices: Option<BarSlices>,
+    // Extra layers drawn over each bar in order, e.g. a shadow where the bar meets the picture. Only the
+    // sprite backend draws them.
+    pub layers: Vec<BarLayer>,
+    // Width in logical pixels of a soft edge fading from each bar's color into the picture, so bars
+    // don't end on a hard line. It overlaps the picture rather than taking space from the safe area.
+    // Zero turns it off. Only the sprite backend draws it.
+    pub inner_falloff: f32,
+}
+
+impl Default for LetterboxStyle {
+    fn default() -> Self {
+        Self {
+            visible: true,
+            color: Color::BLACK,
+            side_colors: None,
+            texture: None,
+            slices: None,
+            layers: Vec::new(),
+            inner_falloff: 0.0,
+        }
+    }
+}
+
+impl LetterboxStyle {
+    // The color the letterbox on a side is drawn with.
+    pub fn color_for(&self, side: LetterboxSide) -> Color {
+        self.side_colors.map_or(self.color, |colors| colors.get(side))
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct SideColors {
+    pub left: Color,
+    pub right: Color,
+    pub top: Color,
+    pub bottom: Color,
+}
+
+impl SideColors {
+    pub fn uniform(color: Color) -> Self {
+        Self {
+            left: color,
+            right: color,
+            top: color,
+            bottom: color,
+        }
+    }
+
+    pub fn get(&self, side: LetterboxSide) -> Color {
+        match side {
+            LetterboxSide::Left => self.left,
+            LetterboxSide::Right => self.right,
+            LetterboxSide::Top => self.top,
+            LetterboxSide::Bottom => self.bottom,
+        }
+    }
+
+    // Applies a function to each side's color.
+    pub fn map(&self, mut f: impl FnMut(LetterboxSide, Color) -> Color) -> Self {
+        Self {
+            left: f(LetterboxSide::Left, self.left),
+            right: f(LetterboxSide::Right, self.right),
+            top: f(LetterboxSide::Top, self.top),
+            bottom: f(LetterboxSide::Bottom, self.bottom),
+        }
+    }
+}
+
+// A layer drawn over the letterbox on each side.
+#[derive(Clone, PartialEq, Debug)]
+pub enum BarLayer {
+    // Fills the whole bar.
+    Solid(Color),
+    // Fades from color at the bar's inner edge to transparent width logical pixels into the picture.
+    InnerShadow { color: Color, width: f32 },
+    // Stretched over the whole bar and tinted with color.
+    Texture { texture: Handle<Image>, color: Color },
+}
+
+// Distance between the layers of a bar, keeping the layers of every bar below the bleed.
+const LAYER_Z_STEP: f32 = 0.005;
+
+// Component for the entities which draw one layer of a letterbox.
+#[derive(Component)]
+pub struct LetterboxLayer {
+    pub side: LetterboxSide,
+    // Index into the style's layers.
+    pub index: usize,
+}
+
+// Component for the entities which draw the style's inner falloff along a letterbox.
+#[derive(Component)]
+pub struct LetterboxFalloff {
+    pub side: LetterboxSide,
+}
+
+// Nine-slice border insets in texture pixels. The corners keep their size, the edges stretch along
+// one axis and the middle stretches along both. Each texture pixel is drawn as one logical window pixel.
+#[derive(Default, Clone, Copy, PartialEq, Debug)]
+pub struct BarSlices {
+    pub left: f32,
+    pub right: f32,
+    pub top: f32,
+    pub bottom: f32,
+}
+
+// Component for the entities which draw one slice of a sliced letterbox.
+#[derive(Component)]
+pub struct LetterboxSlice {
+    pub side: LetterboxSide,
+    // Column and row of the slice, counting from the bottom left.
+    pub column: usize,
+    pub row: usize,
+}
+
+// Keeps the plain letterbox sprites in line with the style. They're hidden while sliced bars are shown.
+pub(crate) fn apply_letterbox_style(
+    style: Res<LetterboxStyle>,
+    suspend: Res<LetterboxSuspend>,
+    collapsed: Res<CollapsedLetterboxes>,
+    mut letterbox_query: Query<(&Letterbox, &mut Sprite, &mut Handle<Image>, &mut Visibility)>,
+    added_query: Query<(), Added<Letterbox>>,
+) {
+    if !style.is_changed() && !suspend.is_changed() && !collapsed.is_changed() && added_query.is_empty() {
+        return;
+    }
+
+    for (letterbox, mut sprite, mut texture, mut visibility) in letterbox_query.iter_mut() {
+        sprite.color = style.color_for(letterbox.side);
+        *texture = style.texture.clone().unwrap_or_else(|| DEFAULT_IMAGE_HANDLE.typed());
+        visibility.is_visible = style.visible
+            && !suspend.is_suspended()
+            && !collapsed.0.contains(&letterbox.side)
+            && (style.slices.is_none() || style.texture.is_none());
+    }
+}
+
+pub(crate) fn update_sliced_letterboxes(
+    mut commands: Commands,
+    style: Res<LetterboxStyle>,
+    suspend: Res<LetterboxSuspend>,
+    layout: Res<LetterboxLayout>,
+    collapsed: Res<CollapsedLetterboxes>,
+    camera_position: Res<ManagedCameraPosition>,
+    images: Res<Assets<Image>>,
+    mut atlases: ResMut<Assets<TextureAtlas>>,
+    mut slice_query: Query<(Entity, &LetterboxSlice, &mut Transform, &mut TextureAtlasSprite, &mut Visibility)>,
+    added_query: Query<(), Added<LetterboxSlice>>,
+    mut atlas: Local<Option<(Handle<Image>, BarSlices, Handle<TextureAtlas>)>>,
+) {
+    // Without both a texture and slices the plain letterboxes are used.
+    let (texture, slices) = match (&style.texture, style.slices) {
+        (Some(texture), Some(slices)) => (texture, slices),
+        _ => {
+            for (entity, ..) in slice_query.iter() {
+                commands.entity(entity).despawn();
+            }
+            *atlas = None;
+            return;
+        },
+    };
+    let texture_size = match images.get(texture) {
+        Some(image) => Vec2::new(
+            image.texture_descriptor.size.width as f32,
+            image.texture_descriptor.size.height as f32,
+        ),
+        None => return,
+    };
+
+    // Build an atlas with one entry per slice whenever the texture or slices change.
+    let rebuild = match &*atlas {
+        Some((atlas_texture, atlas_slices, _)) => atlas_texture != texture || *atlas_slices != slices,
+        None => true,
+    };
+    if rebuild {
+        let columns = [0.0, slices.left, texture_size.x - slices.right, texture_size.x];
+        // Texture rows run top to bottom while slice rows count from the bottom.
+        let rows = [texture_size.y, texture_size.y - slices.bottom, slices.top, 0.0];
+        let mut texture_atlas = TextureAtlas::new_empty(texture.clone(), texture_size);
+        for row in 0..3 {
+            for column in 0..3 {
+                texture_atlas.add_texture(Rect {
+                    min: Vec2::new(columns[column], rows[row + 1]),
+                    max: Vec2::new(columns[column + 1], rows[row]),
+                });
+            }
+        }
+        let handle = atlases.add(texture_atlas);
+
+        for (entity, ..) in slice_query.iter() {
+            commands.entity(entity).despawn();
+        }
+        for side in LetterboxSide::ALL {
+            for row in 0..3 {
+                for column in 0..3 {
+                    commands
+                        .spawn_bundle(SpriteSheetBundle {
+                            sprite: TextureAtlasSprite::new(row * 3 + column),
+                            texture_atlas: handle.clone(),
+                            ..default()
+                        })
+                        .insert(LetterboxSlice { side, column, row });
+                }
+            }
+        }
+        *atlas = Some((texture.clone(), slices, handle));
+        // The new slices are positioned next frame once they exist.
+        return;
+    }
+    let changed = style.is_changed()
+        || layout.is_changed()
+        || suspend.is_changed()
+        || collapsed.is_changed()
+        || camera_position.is_changed();
+    if !changed && added_query.is_empty() {
+        return;
+    }
+
+    for (_, slice, mut transform, mut sprite, mut visibility) in slice_query.iter_mut() {
+        visibility.is_visible = style.visible && !suspend.is_suspended() && !collapsed.0.contains(&slice.side);
+        if !visibility.is_visible {
+            continue;
+        }
+        let logical_per_unit = layout.pixels_per_unit / layout.dpi_scale;
+        let rect = slice_rect(layout.bar(slice.side), &slices, logical_per_unit, slice.column, slice.row);
+        let size = rect.size();
+        let center = camera_position.0 + rect.center();
+        sprite.color = style.color_for(slice.side);
+        sprite.custom_size = Some(size);
+        transform.translation = Vec3::new( center.x, center.y, LETTERBOX_Z );
+    }
+}
+
+pub(crate) fn update_letterbox_layers(
+    mut commands: Commands,
+    style: Res<LetterboxStyle>,
+    suspend: Res<LetterboxSuspend>,
+    layout: Res<LetterboxLayout>,
+    collapsed: Res<CollapsedLetterboxes>,
+    camera_position: Res<ManagedCameraPosition>,
+    mut images: ResMut<Assets<Image>>,
+    mut layer_query: Query<(Entity, &LetterboxLayer, &mut Transform, &mut Sprite, &mut Handle<Image>, &mut Visibility)>,
+    added_query: Query<(), Added<LetterboxLayer>>,
+    mut ramps: Local<ShadowRamps>,
+) {
+    // Spawn one entity per layer and side whenever the number of layers changes.
+    let spawned = layer_query.iter().count();
+    if spawned != style.layers.len() * LetterboxSide::ALL.len() {
+        for (entity, ..) in layer_query.iter() {
+            commands.entity(entity).despawn();
+        }
+        for side in LetterboxSide::ALL {
+            for index in 0..style.layers.len() {
+                commands
+                    .spawn_bundle(SpriteBundle {
+                        sprite: Sprite {
+                            custom_size: Some(Vec2::ZERO),
+                            ..default()
+                        },
+                        visibility: Visibility { is_visible: false },
+                        ..default()
+                    })
+                    .insert(LetterboxLayer { side, index });
+            }
+        }
+        // The new layers are positioned next frame once they exist.
+        return;
+    }
+    let changed = style.is_changed()
+        || layout.is_changed()
+        || suspend.is_changed()
+        || collapsed.is_changed()
+        || camera_position.is_changed();
+    if style.layers.is_empty() || (!changed && added_query.is_empty()) {
+        return;
+    }
+
+    for (_, layer, mut transform, mut sprite, mut texture, mut visibility) in layer_query.iter_mut() {
+        let bar_layer = &style.layers[layer.index];
+        let side = layer.side;
+        visibility.is_visible = style.visible && !suspend.is_suspended() && !collapsed.0.contains(&side);
+        if !visibility.is_visible {
+            continue;
+        }
+
+        let (rect, color) = match bar_layer {
+            BarLayer::Solid(color) => {
+                *texture = DEFAULT_IMAGE_HANDLE.typed();
+                sprite.flip_x = false;
+                sprite.flip_y = false;
+                (layout.bar(side), *color)
+            },
+            BarLayer::InnerShadow { color, width } => {
+                *texture = ramps.get(&mut images, side);
+                // The ramps fade away from the left and top, so the other sides flip them.
+                sprite.flip_x = side == LetterboxSide::Right;
+                sprite.flip_y = side == LetterboxSide::Bottom;
+                (shadow_rect(&layout, side, *width), *color)
+            },
+            BarLayer::Texture { texture: layer_texture, color } => {
+                *texture = layer_texture.clone();
+                sprite.flip_x = false;
+                sprite.flip_y = false;
+                (layout.bar(side), *color)
+            },
+        };
+        let size = rect.size();
+        let center = camera_position.0 + rect.center();
+        let z = LETTERBOX_Z + LAYER_Z_STEP * (layer.index + 1) as f32;
+        sprite.color = color;
+        sprite.custom_size = Some(size);
+        transform.translation = Vec3::new( center.x, center.y, z );
+    }
+}
+
+pub(crate) fn update_inner_falloff(
+    mut commands: Commands,
+    style: Res<LetterboxStyle>,
+    suspend: Res<LetterboxSuspend>,
+    layout: Res<LetterboxLayout>,
+    collapsed: Res<CollapsedLetterboxes>,
+    camera_position: Res<ManagedCameraPosition>,
+    mut images: ResMut<Assets<Image>>,
+    mut falloff_query: Query<(&LetterboxFalloff, &mut Transform, &mut Sprite, &mut Visibility)>,
+    added_query: Query<(), Added<LetterboxFalloff>>,
+    mut ramps: Local<ShadowRamps>,
+) {
+    if falloff_query.is_empty() {
+        if style.inner_falloff > 0.0 {
+            for side in LetterboxSide::ALL {
+                commands
+                    .spawn_bundle(SpriteBundle {
+                        sprite: Sprite {
+                            custom_size: Some(Vec2::ZERO),
+                            // The ramps fade away from the left and top, so the other sides flip them.
+                            flip_x: side == LetterboxSide::Right,
+                            flip_y: side == LetterboxSide::Bottom,
+                            ..default()
+                        },
+                        texture: ramps.get(&mut images, side),
+                        visibility: Visibility { is_visible: false },
+                        ..default()
+                    })
+                    .insert(LetterboxFalloff { side });
+            }
+        }
+        // The new strips are positioned next frame once they exist.
+        return;
+    }
+    let changed = style.is_changed()
+        || layout.is_changed()
+        || suspend.is_changed()
+        || collapsed.is_changed()
+        || camera_position.is_changed();
+    if !changed && added_query.is_empty() {
+        return;
+    }
+
+    for (falloff, mut transform, mut sprite, mut visibility) in falloff_query.iter_mut() {
+        let side = falloff.side;
+        // A bar with no size has no edge to soften.
+        visibility.is_visible = style.visible
+            && style.inner_falloff > 0.0
+            && !suspend.is_suspended()
+            && !collapsed.0.contains(&side);
+        if !visibility.is_visible {
+            continue;
+        }
+        let rect = shadow_rect(&layout, side, style.inner_falloff);
+        let center = camera_position.0 + rect.center();
+        sprite.color = style.color_for(side);
+        sprite.custom_size = Some(rect.size());
+        transform.translation = Vec3::new( center.x, center.y, LETTERBOX_Z );
+    }
+}
+
+// White images fading from opaque to transparent, shared by every shadow along a bar's inner edge.
+#[derive(Default)]
+pub(crate) struct ShadowRamps(Option<[Handle<Image>; 2]>);
+
+impl ShadowRamps {
+    // The ramp running across a bar on a side, created the first time it's needed.
+    fn get(&mut self, images: &mut Assets<Image>, side: LetterboxSide) -> Handle<Image> {
+        let [horizontal, vertical] = self
+            .0
+            .get_or_insert_with(|| [images.add(shadow_ramp(true)), images.add(shadow_ramp(false))]);
+        match side {
+            LetterboxSide::Left | LetterboxSide::Right => horizontal.clone(),
+            LetterboxSide::Top | LetterboxSide::Bottom => vertical.clone(),
+        }
+    }
+}
+
+// The world space rectangle a shadow covers, running width logical pixels along the bar's inner edge
+// into the picture.
+fn shadow_rect(layout: &LetterboxLayout, side: LetterboxSide, width: f32) -> LetterboxRect {
+    let framed = layout.framed;
+    let logical_per_unit = layout.pixels_per_unit_axes() / layout.dpi_scale;
+    let width = match side {
+        LetterboxSide::Left | LetterboxSide::Right => (width.max(0.0) / logical_per_unit.x).min(framed.size().x),
+        LetterboxSide::Top | LetterboxSide::Bottom => (width.max(0.0) / logical_per_unit.y).min(framed.size().y),
+    };
+    match side {
+        LetterboxSide::Left => LetterboxRect::new(framed.min, Vec2::new(framed.min.x + width, framed.max.y)),
+        LetterboxSide::Right => LetterboxRect::new(Vec2::new(framed.max.x - width, framed.min.y), framed.max),
+        LetterboxSide::Top => LetterboxRect::new(Vec2::new(framed.min.x, framed.max.y - width), framed.max),
+        LetterboxSide::Bottom => LetterboxRect::new(framed.min, Vec2::new(framed.max.x, framed.min.y + width)),
+    }
+}
+
+// White image fading from opaque to transparent, left to right or top to bottom.
+fn shadow_ramp(horizontal: bool) -> Image {
+    const STEPS: u32 = 64;
+    let data = (0..STEPS)
+        .flat_map(|step| {
+            let alpha = 255 - (step * 255 / (STEPS - 1)) as u8;
+            [255, 255, 255, alpha]
+        })
+        .collect();
+    let (width, height) = if horizontal { (STEPS, 1) } else { (1, STEPS) };
+    Image::new(
+        Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+    )
+}
+
+// The world space rectangle of one slice of a bar.
+fn slice_rect(bar: LetterboxRect, slices: &BarSlices, pixels_per_unit: f32, column: usize, row: usize) -> LetterboxRect {
+    // Shrink the borders proportionally if the bar is too small to fit them.
+    let size = bar.size();
+    let mut border_min = Vec2::new(slices.left, slices.bottom) / pixels_per_unit;
+    let mut border_max = Vec2::new(slices.right, slices.top) / pixels_per_unit;
+    let fit = (size / (border_min + border_max)).min(Vec2::ONE);
+    border_min *= fit;
+    border_max *= fit;
+
+    let columns = [bar.min.x, bar.min.x + border_min.x, bar.max.x - border_max.x, bar.max.x];
+    let rows = [bar.min.y, bar.min.y + border_min.y, bar.max.y - border_max.y, bar.max.y];
+    LetterboxRect::new(
+        Vec2::new(columns[column], rows[row]),
+        Vec2::new(columns[column + 1], rows[row + 1]),
+    )
+}
+
+// Extension for restyling the letterboxes from any system with Commands. Changes go through the
+// LetterboxStyle resource, so they're applied to bars spawned later as well.
+pub trait LetterboxCommandsExt<'w, 's> {
+    fn letterboxes<'a>(&'a mut self) -> LetterboxCommands<'a, 'w, 's>;
+}
+
+impl<'w, 's> LetterboxCommandsExt<'w, 's> for Commands<'w, 's> {
+    fn letterboxes<'a>(&'a mut self) -> LetterboxCommands<'a, 'w, 's> {
+        LetterboxCommands { commands: self }
+    }
+}
+
+pub struct LetterboxCommands<'a, 'w, 's> {
+    commands: &'a mut Commands<'w, 's>,
+}
+
+impl<'a, 'w, 's> LetterboxCommands<'a, 'w, 's> {
+    // Colors every side the same, replacing any side colors.
+    pub fn set_color(&mut self, color: Color) -> &mut Self {
+        self.commands.add(RestyleLetterboxes(move |style: &mut LetterboxStyle| {
+            style.color = color;
+            style.side_colors = None;
+        }));
+        self
+    }
+
+    pub fn set_side_colors(&mut self, colors: SideColors) -> &mut Self {
+        self.commands.add(RestyleLetterboxes(move |style: &mut LetterboxStyle| style.side_colors = Some(colors)));
+        self
+    }
+
+    pub fn set_texture(&mut self, texture: Option<Handle<Image>>) -> &mut Self {
+        self.commands.add(RestyleLetterboxes(move |style: &mut LetterboxStyle| style.texture = texture));
+        self
+    }
+
+    // Replaces the layers drawn over the bars. An empty list leaves just the bars.
+    pub fn set_layers(&mut self, layers: Vec<BarLayer>) -> &mut Self {
+        self.commands.add(RestyleLetterboxes(move |style: &mut LetterboxStyle| style.layers = layers));
+        self
+    }
+
+    pub fn set_visible(&mut self, visible: bool) -> &mut Self {
+        self.commands.add(RestyleLetterboxes(move |style: &mut LetterboxStyle| style.visible = visible));
+        self
+    }
+}
+
+struct RestyleLetterboxes<F>(F);
+
+impl<F: FnOnce(&mut LetterboxStyle) + Send + Sync + 'static> Command for RestyleLetterboxes<F> {
+    fn write(self, world: &mut World) {
+        // Does nothing if the plugin hasn't been added.
+        if let Some(mut style) = world.get_resource_mut::<LetterboxStyle>() {
+            (self.0)(&mut style);
+        }
+    }
+}
@@ -0,0 +1,42 @@
+use bevy::prelude::*;
+
+use crate::camera::ManagedCameraPosition;
+use crate::{LetterboxLayout, LETTERBOX_Z};
+
+// Resource configuring where subtitles are placed. See LetterboxLayout::subtitle_area.
+pub struct SubtitleArea {
+    // Minimum height in world units of the bottom letterbox for subtitles to go inside it.
+    pub min_bar_height: f32,
+}
+
+impl Default for SubtitleArea {
+    fn default() -> Self {
+        Self { min_bar_height: 1.5 }
+    }
+}
+
+// Component which keeps an entity, such as a Text2dBundle, centered in the subtitle area.
+#[derive(Component)]
+pub struct SubtitleAnchor;
+
+// Height of the subtitles above the letterboxes, clearing the transition bars.
+const SUBTITLE_Z_OFFSET: f32 = 0.8;
+
+pub(crate) fn anchor_subtitles(
+    subtitle_area: Res<SubtitleArea>,
+    layout: Res<LetterboxLayout>,
+    camera_position: Res<ManagedCameraPosition>,
+    mut subtitle_query: Query<&mut Transform, With<SubtitleAnchor>>,
+    added_query: Query<(), Added<SubtitleAnchor>>,
+) {
+    let changed = subtitle_area.is_changed() || layout.is_changed() || camera_position.is_changed();
+    if !changed && added_query.is_empty() {
+        return;
+    }
+
+    // Subtitles sit above the letterboxes so they're visible inside the bottom one.
+    let center = camera_position.0 + layout.subtitle_area(subtitle_area.min_bar_height).center();
+    for mut transform in subtitle_query.iter_mut() {
+        transform.translation = Vec3::new( center.x, center.y, LETTERBOX_Z + SUBTITLE_Z_OFFSET );
+    }
+}
@@ -0,0 +1,68 @@
+use bevy::prelude::*;
+use bevy::render::camera::ScalingMode;
+
+use crate::ManagedCamera;
+
+// Resource for temporarily handing the camera back, e.g. for a photo mode where players frame shots
+// freely. While suspended the letterboxes are hidden, the projection uses the released scaling mode
+// and window resizes aren't applied. Ending the suspension restores the projection as it was and
+// lays it out again for the current window size.
+pub struct LetterboxSuspend {
+    suspended: bool,
+    // Scaling mode the projection uses while suspended.
+    pub released_mode: ScalingMode,
+    snapshot: Option<(Entity, OrthographicProjection)>,
+}
+
+impl Default for LetterboxSuspend {
+    fn default() -> Self {
+        Self {
+            suspended: false,
+            released_mode: ScalingMode::WindowSize,
+            snapshot: None,
+        }
+    }
+}
+
+impl LetterboxSuspend {
+    pub fn begin(&mut self) {
+        self.suspended = true;
+    }
+
+    pub fn end(&mut self) {
+        self.suspended = false;
+    }
+
+    pub fn is_suspended(&self) -> bool {
+        self.suspended
+    }
+}
+
+pub(crate) fn apply_suspend(
+    mut suspend: ResMut<LetterboxSuspend>,
+    managed_camera: Res<ManagedCamera>,
+    mut projection_query: Query<&mut OrthographicProjection>,
+) {
+    if !suspend.is_changed() {
+        return;
+    }
+    let suspend = &mut *suspend;
+
+    if suspend.suspended && suspend.snapshot.is_none() {
+        let entity = match managed_camera.entity {
+            Some(entity) => entity,
+            None => return,
+        };
+        if let Ok(mut projection) = projection_query.get_mut(entity) {
+            suspend.snapshot = Some((entity, projection.clone()));
+            projection.scaling_mode = suspend.released_mode.clone();
+            projection.scale = 1.0;
+        }
+    } else if !suspend.suspended {
+        if let Some((entity, snapshot)) = suspend.snapshot.take() {
+            if let Ok(mut projection) = projection_query.get_mut(entity) {
+                *projection = snapshot;
+            }
+        }
+    }
+}
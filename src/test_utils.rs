@@ -0,0 +1,160 @@
+use bevy::asset::AssetPlugin;
+use bevy::ecs::event::Events;
+use bevy::ecs::system::Resource;
+use bevy::input::InputPlugin;
+use bevy::prelude::*;
+use bevy::window::{WindowCreated, WindowId, WindowPlugin, WindowResized, WindowScaleFactorChanged};
+use raw_window_handle::{RawWindowHandle, WebHandle};
+
+use crate::{LetterboxLayout, LetterboxPlugin, ScreenUnits, VirtualResolution};
+
+// Builder for a headless App with the plugin installed, for tests which need layouts without real
+// windows. Only the plugins the letterboxes read from are added, nothing renders.
+pub struct LetterboxTestApp {
+    plugin: LetterboxPlugin,
+    screen_units: ScreenUnits,
+    virtual_resolution: Option<VirtualResolution>,
+    window: Option<(f32, f32, f64)>,
+}
+
+impl Default for LetterboxTestApp {
+    fn default() -> Self {
+        Self {
+            plugin: LetterboxPlugin::default(),
+            screen_units: ScreenUnits {
+                width: 16.0,
+                height: 9.0,
+            },
+            virtual_resolution: None,
+            window: None,
+        }
+    }
+}
+
+impl LetterboxTestApp {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn plugin(mut self, plugin: LetterboxPlugin) -> Self {
+        self.plugin = plugin;
+        self
+    }
+
+    pub fn screen_units(mut self, width: f32, height: f32) -> Self {
+        self.screen_units = ScreenUnits { width, height };
+        self
+    }
+
+    // Configures a VirtualResolution as well, which takes over from the screen units on the first frame.
+    pub fn virtual_resolution(mut self, width: u32, height: u32, pixels_per_unit: f32) -> Self {
+        self.virtual_resolution = Some(VirtualResolution {
+            width,
+            height,
+            pixels_per_unit,
+        });
+        self
+    }
+
+    // Creates the primary window at a logical size and scale factor once the app is built.
+    pub fn window(mut self, width: f32, height: f32, scale_factor: f64) -> Self {
+        self.window = Some((width, height, scale_factor));
+        self
+    }
+
+    // Builds the app and runs its first update, so the startup systems and any window are processed.
+    pub fn build(self) -> App {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_plugin(TransformPlugin)
+            .add_plugin(InputPlugin)
+            .add_plugin(WindowPlugin {
+                add_primary_window: false,
+                ..default()
+            })
+            .add_plugin(AssetPlugin)
+            .add_asset::<Image>()
+            .add_asset::<TextureAtlas>()
+            .init_resource::<ClearColor>()
+            .insert_resource(self.screen_units);
+        if let Some(resolution) = self.virtual_resolution {
+            app.insert_resource(resolution);
+        }
+        app.add_plugin(self.plugin);
+        if let Some((width, height, scale_factor)) = self.window {
+            spawn_test_window(&mut app, width, height, scale_factor);
+        } else {
+            app.update();
+        }
+        app
+    }
+}
+
+// Adds the primary window at a logical size and scale factor, as if the platform had created it, and
+// runs an update so the layout follows.
+pub fn spawn_test_window(app: &mut App, width: f32, height: f32, scale_factor: f64) {
+    let id = WindowId::primary();
+    let window = Window::new(
+        id,
+        &WindowDescriptor {
+            width,
+            height,
+            ..default()
+        },
+        (width as f64 * scale_factor).round() as u32,
+        (height as f64 * scale_factor).round() as u32,
+        scale_factor,
+        None,
+        RawWindowHandle::Web(WebHandle::empty()),
+    );
+    app.world.get_resource_mut::<Windows>().expect("WindowPlugin is missing").add(window);
+    send_event(app, WindowCreated { id });
+    app.update();
+}
+
+// Resizes the primary window to a logical size the way the platform would, and runs an update.
+pub fn resize(app: &mut App, width: f32, height: f32) {
+    let id = WindowId::primary();
+    let mut windows = app.world.get_resource_mut::<Windows>().expect("WindowPlugin is missing");
+    let window = windows.get_mut(id).expect("spawn_test_window must be called before resize");
+    let scale_factor = window.scale_factor();
+    window.update_actual_size_from_backend(
+        (width as f64 * scale_factor).round() as u32,
+        (height as f64 * scale_factor).round() as u32,
+    );
+    send_event(app, WindowResized { id, width, height });
+    app.update();
+}
+
+// Overrides the primary window's scale factor the way the platform would apply
+// Window::set_scale_factor_override, keeping its physical size, and runs an update.
+pub fn set_scale_factor_override(app: &mut App, scale_factor: Option<f64>) {
+    let id = WindowId::primary();
+    let mut windows = app.world.get_resource_mut::<Windows>().expect("WindowPlugin is missing");
+    let window = windows.get_mut(id).expect("spawn_test_window must be called before set_scale_factor_override");
+    window.set_scale_factor_override(scale_factor);
+    let (scale_factor, width, height) = (window.scale_factor(), window.width(), window.height());
+    send_event(app, WindowScaleFactorChanged { id, scale_factor });
+    send_event(app, WindowResized { id, width, height });
+    app.update();
+}
+
+fn send_event<T: Resource>(app: &mut App, event: T) {
+    app.world.get_resource_mut::<Events<T>>().expect("event isn't registered").send(event);
+}
+
+// The layout currently applied.
+pub fn layout(app: &App) -> LetterboxLayout {
+    *app.world.get_resource::<LetterboxLayout>().expect("LetterboxPlugin is missing")
+}
+
+// Panics with both layouts unless the applied layout matches the expected one within 1e-3.
+pub fn assert_layout(app: &App, expected: &LetterboxLayout) {
+    let actual = layout(app);
+    assert!(
+        actual.approx_eq(expected, 1e-3),
+        "layout doesn't match\n  actual: {:?}\nexpected: {:?}",
+        actual,
+        expected
+    );
+}
@@ -0,0 +1,172 @@
+use bevy::ecs::schedule::StateData;
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+use crate::cinematic::lerp_color;
+use crate::{BarLayer, DefaultLetterboxEasing, LetterboxEasing, LetterboxStyle, ReduceMotion, SideColors};
+
+// Resource for bar colors chosen by gameplay, e.g. per biome. While it exists it owns the colors and
+// texture and layers in LetterboxStyle. Changing it crossfades the color over crossfade seconds, the
+// texture and layers switch immediately. The crossfade uses DefaultLetterboxEasing unless the theme
+// sets its own.
+#[derive(Clone, PartialEq, Debug)]
+pub struct BarTheme {
+    pub color: Color,
+    // Colors per side instead of color, faded and flashed the same way.
+    pub side_colors: Option<SideColors>,
+    pub texture: Option<Handle<Image>>,
+    pub layers: Vec<BarLayer>,
+    pub crossfade: f32,
+    pub easing: Option<LetterboxEasing>,
+}
+
+impl Default for BarTheme {
+    fn default() -> Self {
+        Self {
+            color: Color::BLACK,
+            side_colors: None,
+            texture: None,
+            layers: Vec::new(),
+            crossfade: 0.0,
+            easing: None,
+        }
+    }
+}
+
+// Resource mapping the states of a game state to themes. Add apply_state_bar_theme::<S> to switch
+// the BarTheme whenever the state changes.
+pub struct BarThemes<S: StateData> {
+    pub themes: HashMap<S, BarTheme>,
+}
+
+impl<S: StateData> Default for BarThemes<S> {
+    fn default() -> Self {
+        Self { themes: HashMap::default() }
+    }
+}
+
+pub fn apply_state_bar_theme<S: StateData>(
+    mut commands: Commands,
+    state: Res<State<S>>,
+    themes: Res<BarThemes<S>>,
+) {
+    if !state.is_changed() && !themes.is_changed() {
+        return;
+    }
+
+    if let Some(theme) = themes.themes.get(state.current()) {
+        commands.insert_resource(theme.clone());
+    }
+}
+
+// Resource for transient tints layered over the BarTheme, e.g. a damage flash. The bars jump to the
+// flash color and fade back to the theme color over the duration. Flashes need a BarTheme to return to.
+#[derive(Default)]
+pub struct BarFlash {
+    color: Color,
+    duration: f32,
+    remaining: f32,
+}
+
+impl BarFlash {
+    pub fn flash(&mut self, color: Color, duration: f32) {
+        self.color = color;
+        self.duration = duration;
+        self.remaining = duration;
+    }
+
+    pub fn is_flashing(&self) -> bool {
+        self.remaining > 0.0
+    }
+
+    // How much of the flash color is shown, from 1 when it starts to 0 when it's over.
+    fn weight(&self) -> f32 {
+        if self.duration <= 0.0 {
+            0.0
+        } else {
+            (self.remaining / self.duration).clamp(0.0, 1.0)
+        }
+    }
+}
+
+impl BarTheme {
+    // The overall color and the color of each side.
+    fn colors(&self) -> (Color, SideColors) {
+        (self.color, self.side_colors.unwrap_or_else(|| SideColors::uniform(self.color)))
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct ThemeFade {
+    // The colors being faded from, if a crossfade is running.
+    from: Option<(Color, SideColors)>,
+    elapsed: f32,
+    // The theme colors shown last frame, without any flash.
+    shown: Option<(Color, SideColors)>,
+}
+
+pub(crate) fn apply_bar_theme(
+    time: Res<Time>,
+    reduce_motion: Res<ReduceMotion>,
+    default_easing: Res<DefaultLetterboxEasing>,
+    theme: Option<Res<BarTheme>>,
+    mut flash: ResMut<BarFlash>,
+    mut style: ResMut<LetterboxStyle>,
+    mut fade: Local<ThemeFade>,
+) {
+    let theme = match theme {
+        Some(theme) => theme,
+        None => {
+            *fade = ThemeFade::default();
+            return;
+        },
+    };
+
+    // Fade from whatever is shown now, so changing theme during a crossfade doesn't jump.
+    if theme.is_changed() {
+        fade.from = fade.shown;
+        fade.elapsed = 0.0;
+        if style.texture != theme.texture {
+            style.texture = theme.texture.clone();
+        }
+        if style.layers != theme.layers {
+            style.layers = theme.layers.clone();
+        }
+    }
+
+    fade.elapsed += time.delta_seconds();
+    let (to_color, to_sides) = theme.colors();
+    let (base, base_sides) = match fade.from {
+        Some((from_color, from_sides)) if !reduce_motion.0 && fade.elapsed < theme.crossfade => {
+            let easing = theme.easing.unwrap_or(default_easing.0);
+            let t = easing.ease(fade.elapsed / theme.crossfade);
+            (
+                lerp_color(from_color, to_color, t),
+                from_sides.map(|side, from| lerp_color(from, to_sides.get(side), t)),
+            )
+        },
+        _ => {
+            fade.from = None;
+            (to_color, to_sides)
+        },
+    };
+    fade.shown = Some((base, base_sides));
+
+    if flash.is_flashing() {
+        flash.remaining = (flash.remaining - time.delta_seconds()).max(0.0);
+    }
+    let weight = flash.weight();
+    let color = lerp_color(base, flash.color, weight);
+    // Side colors are kept while fading to or from a theme with them.
+    let side_colors = if theme.side_colors.is_some() || fade.from.is_some() {
+        Some(base_sides.map(|_, side_color| lerp_color(side_color, flash.color, weight)))
+    } else {
+        None
+    };
+    if style.color != color {
+        style.color = color;
+    }
+    if style.side_colors != side_colors {
+        style.side_colors = side_colors;
+    }
+}
@@ -0,0 +1,278 @@
+use bevy::ecs::system::{Command, SystemParam};
+use bevy::prelude::*;
+use bevy::window::WindowResized;
+
+use crate::camera::ManagedCameraPosition;
+use crate::{
+    DefaultLetterboxEasing, LetterboxEasing, LetterboxLayout, LetterboxRect, LetterboxSide, LetterboxStyle,
+    ManagedCamera, ReduceMotion, ScreenUnits, LETTERBOX_Z,
+};
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum TransitionKind {
+    // The left bar grows across the whole window.
+    Wipe,
+    // The left and right bars meet in the middle.
+    Curtains,
+    // All four bars close toward a point, in world units.
+    Iris { focus: Vec2 },
+}
+
+// Event which starts a screen transition. The bars close over the duration, a TransitionCovered
+// event is sent while the screen is fully covered, and then they open again over the same duration.
+// Starting a transition while another is running replaces it, closing from the current coverage.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct StartTransition {
+    pub kind: TransitionKind,
+    // Seconds for each of the closing and opening halves.
+    pub duration: f32,
+    pub easing: LetterboxEasing,
+    pub color: Color,
+}
+
+// Event sent when the screen is fully covered, e.g. for swapping scenes.
+pub struct TransitionCovered;
+
+// Event sent when the bars have opened again.
+pub struct TransitionFinished;
+
+// Command which changes ScreenUnits behind a transition, so the projection never visibly pops, e.g.
+// across a scene load. The bars close in the letterbox color over cover_duration, the units are
+// swapped and a ReframeCovered event is sent while the screen is covered, and the bars open over
+// reveal_duration. Another reframe or transition replaces it, continuing from the current coverage.
+// A window resize before the swap aborts it: the units are swapped right away, the bars open from
+// where they are and ReframeAborted is sent instead of ReframeCovered.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct ReframeWithFade {
+    pub new_units: ScreenUnits,
+    pub cover_duration: f32,
+    pub reveal_duration: f32,
+}
+
+impl Command for ReframeWithFade {
+    fn write(self, world: &mut World) {
+        let color = world.get_resource::<LetterboxStyle>().map_or(Color::BLACK, |style| style.color);
+        let easing = world.get_resource::<DefaultLetterboxEasing>().map_or(LetterboxEasing::Linear, |easing| easing.0);
+        // Does nothing if the plugin hasn't been added.
+        if let Some(mut transition) = world.get_resource_mut::<LetterboxTransition>() {
+            transition.queued = Some(QueuedTransition {
+                start: StartTransition {
+                    kind: TransitionKind::Iris { focus: Vec2::ZERO },
+                    duration: self.cover_duration,
+                    easing,
+                    color,
+                },
+                reveal_duration: self.reveal_duration,
+                units: Some(self.new_units),
+            });
+        }
+    }
+}
+
+// Event sent when a reframe has swapped ScreenUnits behind the covered screen, e.g. for swapping scenes.
+pub struct ReframeCovered;
+
+// Event sent when a window resize cut a reframe short and the units were swapped uncovered.
+pub struct ReframeAborted;
+
+#[derive(Clone, Copy)]
+struct QueuedTransition {
+    start: StartTransition,
+    reveal_duration: f32,
+    units: Option<ScreenUnits>,
+}
+
+// Resource holding the running transition.
+#[derive(Default)]
+pub struct LetterboxTransition {
+    active: Option<StartTransition>,
+    // Seconds the bars take to open, the same as to close except for reframes.
+    reveal_duration: f32,
+    elapsed: f32,
+    covered: bool,
+    // Units a reframe swaps in once the screen is covered.
+    units: Option<ScreenUnits>,
+    queued: Option<QueuedTransition>,
+}
+
+impl LetterboxTransition {
+    pub fn is_running(&self) -> bool {
+        self.active.is_some()
+    }
+
+    // Whether a reframe is waiting to swap ScreenUnits.
+    pub fn is_reframing(&self) -> bool {
+        self.units.is_some()
+    }
+
+    // How much of the way to fully covered the bars are, from 0 to 1.
+    pub fn coverage(&self) -> f32 {
+        match &self.active {
+            Some(transition) => transition.easing.ease(self.progress()),
+            None => 0.0,
+        }
+    }
+
+    // Coverage before easing.
+    fn progress(&self) -> f32 {
+        let duration = match &self.active {
+            Some(transition) => transition.duration,
+            None => return 0.0,
+        };
+        if !self.covered {
+            if duration <= 0.0 { 1.0 } else { self.elapsed / duration }
+        } else if self.reveal_duration <= 0.0 {
+            0.0
+        } else {
+            1.0 - (self.elapsed - duration.max(0.0)) / self.reveal_duration
+        }
+    }
+}
+
+// Component for the entities which draw screen transitions. They sit above the letterboxes, which
+// are never touched, so the normal layout is exactly as before once a transition finishes.
+#[derive(Component)]
+pub struct TransitionBar { pub side: LetterboxSide }
+
+// Height of the transition bars above the letterboxes, clearing the bleed and the style's bar layers.
+const TRANSITION_Z_OFFSET: f32 = 0.5;
+
+pub(crate) fn spawn_transition_bars(mut commands: Commands) {
+    for side in LetterboxSide::ALL {
+        commands
+            .spawn_bundle(SpriteBundle {
+                sprite: Sprite {
+                    custom_size: Some(Vec2::ZERO),
+                    ..default()
+                },
+                ..default()
+            })
+            .insert(TransitionBar { side });
+    }
+}
+
+pub(crate) fn run_transitions(
+    time: Res<Time>,
+    reduce_motion: Res<ReduceMotion>,
+    managed_camera: Res<ManagedCamera>,
+    mut start_events: EventReader<StartTransition>,
+    mut resize_events: EventReader<WindowResized>,
+    mut transition: ResMut<LetterboxTransition>,
+    mut screen_units: ResMut<ScreenUnits>,
+    mut events: TransitionEvents,
+) {
+    let queued = match start_events.iter().last() {
+        Some(start) => Some(QueuedTransition {
+            start: *start,
+            reveal_duration: start.duration,
+            units: None,
+        }),
+        None => transition.queued.take(),
+    };
+    if let Some(queued) = queued {
+        // A replaced reframe's units are never shown covered, so they're dropped unless a plain
+        // transition replaced it, in which case they're applied rather than lost.
+        if let (Some(units), None) = (transition.units, queued.units) {
+            *screen_units = units;
+        }
+        // Continue closing from the current coverage rather than jumping open.
+        let progress = transition.progress().clamp(0.0, 1.0);
+        let transition = &mut *transition;
+        transition.active = Some(queued.start);
+        transition.reveal_duration = queued.reveal_duration.max(0.0);
+        transition.elapsed = progress * queued.start.duration.max(0.0);
+        transition.covered = false;
+        transition.units = queued.units;
+        transition.queued = None;
+        return;
+    }
+
+    let duration = match &transition.active {
+        Some(active) => active.duration.max(0.0),
+        None => return,
+    };
+    let end = duration + transition.reveal_duration;
+
+    if !transition.covered && resize_events.iter().any(|event| event.id == managed_camera.window) {
+        if let Some(units) = transition.units.take() {
+            let progress = transition.progress().clamp(0.0, 1.0);
+            *screen_units = units;
+            transition.covered = true;
+            transition.elapsed = duration + (1.0 - progress) * transition.reveal_duration;
+            events.aborted.send(ReframeAborted);
+        }
+    }
+
+    // With reduced motion each half completes in a single step, so the screen is still covered for
+    // one frame and the events arrive in the same order.
+    if reduce_motion.0 {
+        transition.elapsed = if transition.covered { end } else { duration };
+    } else {
+        transition.elapsed += time.delta_seconds();
+    }
+
+    if !transition.covered && transition.elapsed >= duration {
+        transition.covered = true;
+        if let Some(units) = transition.units.take() {
+            *screen_units = units;
+            events.reframe_covered.send(ReframeCovered);
+        }
+        events.covered.send(TransitionCovered);
+    }
+    if transition.covered && transition.elapsed >= end {
+        transition.active = None;
+        events.finished.send(TransitionFinished);
+    }
+}
+
+#[derive(SystemParam)]
+pub(crate) struct TransitionEvents<'w, 's> {
+    covered: EventWriter<'w, 's, TransitionCovered>,
+    finished: EventWriter<'w, 's, TransitionFinished>,
+    reframe_covered: EventWriter<'w, 's, ReframeCovered>,
+    aborted: EventWriter<'w, 's, ReframeAborted>,
+}
+
+pub(crate) fn update_transition_bars(
+    transition: Res<LetterboxTransition>,
+    layout: Res<LetterboxLayout>,
+    camera_position: Res<ManagedCameraPosition>,
+    mut bar_query: Query<(&TransitionBar, &mut Transform, &mut Sprite)>,
+) {
+    if !transition.is_changed() && !layout.is_changed() && !camera_position.is_changed() {
+        return;
+    }
+
+    let coverage = transition.coverage();
+    let window = layout.visible;
+    let width = window.size().x;
+
+    for (bar, mut transform, mut sprite) in bar_query.iter_mut() {
+        let rect = match transition.active.map(|active| active.kind) {
+            Some(TransitionKind::Wipe) => match bar.side {
+                LetterboxSide::Left => LetterboxRect::new(window.min, Vec2::new(window.min.x + width * coverage, window.max.y)),
+                _ => LetterboxRect::default(),
+            },
+            Some(TransitionKind::Curtains) => match bar.side {
+                LetterboxSide::Left => LetterboxRect::new(window.min, Vec2::new(window.min.x + width / 2.0 * coverage, window.max.y)),
+                LetterboxSide::Right => LetterboxRect::new(Vec2::new(window.max.x - width / 2.0 * coverage, window.min.y), window.max),
+                _ => LetterboxRect::default(),
+            },
+            Some(TransitionKind::Iris { focus }) => {
+                // The uncovered hole shrinks from the whole window down to the focus point.
+                let focus = focus.clamp(window.min, window.max);
+                let hole = LetterboxRect::new(
+                    window.min + (focus - window.min) * coverage,
+                    window.max + (focus - window.max) * coverage,
+                );
+                window.frame_side(hole, bar.side)
+            },
+            None => LetterboxRect::default(),
+        };
+
+        let center = camera_position.0 + rect.center();
+        sprite.color = transition.active.map_or(Color::NONE, |active| active.color);
+        sprite.custom_size = Some(rect.size());
+        transform.translation = Vec3::new(center.x, center.y, LETTERBOX_Z + TRANSITION_Z_OFFSET);
+    }
+}
@@ -0,0 +1,111 @@
+use bevy::prelude::*;
+
+use crate::{LayoutInputs, LetterboxBackend, ManagedCamera};
+
+// Resource for transparent windows, e.g. desktop toys, where the letterboxes should be holes showing the
+// desktop and only the picture is opaque. The window has to be created with WindowDescriptor::transparent.
+// While enabled the window is cleared to transparent, overriding LetterboxClearColor, the letterboxes
+// are drawn fully transparent, and an opaque backdrop of the background color is drawn behind the world
+// in the picture. Hit testing is unaffected, so points over the holes still classify as letterboxes.
+//
+// Only the render texture backend can show true holes, since it renders the world into an image and
+// nothing outside it. Sprite letterboxes can't erase what the world draws under them, so with the sprite
+// backend LetterboxError::TransparencyUnsupported is reported and the letterboxes stay opaque.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct TransparentLetterboxes {
+    pub enabled: bool,
+    pub background: Color,
+}
+
+impl Default for TransparentLetterboxes {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            background: Color::BLACK,
+        }
+    }
+}
+
+impl TransparentLetterboxes {
+    // Whether the window is currently being made transparent.
+    pub(crate) fn is_active(&self, backend: &LetterboxBackend) -> bool {
+        self.enabled && matches!(backend, LetterboxBackend::RenderTexture(_))
+    }
+}
+
+// Component for the sprite drawn behind the world while the letterboxes are transparent.
+#[derive(Component)]
+pub struct TransparencyBackdrop;
+
+// Distance of the backdrop in front of the managed camera's far plane.
+const BACKDROP_DEPTH: f32 = 0.05;
+
+#[derive(Default)]
+pub(crate) struct TransparencyState {
+    // The clear color before the window was made transparent.
+    original_clear_color: Option<Color>,
+}
+
+pub(crate) fn apply_transparent_letterboxes(
+    mut commands: Commands,
+    transparency: Res<TransparentLetterboxes>,
+    inputs: LayoutInputs,
+    managed_camera: Res<ManagedCamera>,
+    mut clear_color: ResMut<ClearColor>,
+    mut backdrop_query: Query<(Entity, &mut Transform, &mut Sprite), With<TransparencyBackdrop>>,
+    camera_query: Query<(&GlobalTransform, &OrthographicProjection)>,
+    mut state: Local<TransparencyState>,
+) {
+    let settings = match *inputs.backend {
+        LetterboxBackend::RenderTexture(settings) if transparency.enabled => settings,
+        _ => {
+            if let Some(color) = state.original_clear_color.take() {
+                clear_color.0 = color;
+            }
+            for (entity, ..) in backdrop_query.iter() {
+                commands.entity(entity).despawn();
+            }
+            return;
+        },
+    };
+
+    if state.original_clear_color.is_none() {
+        state.original_clear_color = Some(clear_color.0);
+    }
+    if clear_color.0 != Color::NONE {
+        clear_color.0 = Color::NONE;
+    }
+
+    // The backdrop fills the whole image, behind everything the camera draws.
+    let (camera_transform, projection) = match managed_camera.entity.and_then(|entity| camera_query.get(entity).ok()) {
+        Some(camera) => camera,
+        None => return,
+    };
+    let image = settings.image_layout(&inputs.screen_units()).visible;
+    let center = camera_transform.translation.truncate() + image.center();
+    let translation = Vec3::new( center.x, center.y, camera_transform.translation.z - projection.far + BACKDROP_DEPTH );
+    match backdrop_query.get_single_mut() {
+        Ok((_, mut transform, mut sprite)) => {
+            if transform.translation != translation {
+                transform.translation = translation;
+            }
+            if sprite.custom_size != Some(image.size()) || sprite.color != transparency.background {
+                sprite.custom_size = Some(image.size());
+                sprite.color = transparency.background;
+            }
+        },
+        Err(_) => {
+            commands
+                .spawn_bundle(SpriteBundle {
+                    sprite: Sprite {
+                        color: transparency.background,
+                        custom_size: Some(image.size()),
+                        ..default()
+                    },
+                    transform: Transform::from_translation(translation),
+                    ..default()
+                })
+                .insert(TransparencyBackdrop);
+        },
+    }
+}
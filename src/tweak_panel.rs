@@ -0,0 +1,301 @@
+use bevy::prelude::*;
+
+use crate::{BandSize, FillMode, LetterboxLayout, LetterboxRect, LetterboxStyle, ReservedBands, ScreenUnits};
+
+// Resource for a panel for adjusting the framing live during playtests. It writes straight into
+// ScreenUnits, ReservedBands, LetterboxStyle and FillMode, so changes apply like any other. Up and down
+// or the d-pad pick a setting, left and right change it, and enter or the south button on the last
+// row logs the settings as code to paste into the app. It sits in the top left of the safe area and
+// needs a UI camera and a font.
+pub struct TweakPanel {
+    pub enabled: bool,
+    // Key which shows and hides the panel.
+    pub toggle_key: Option<KeyCode>,
+    pub font: Option<Handle<Font>>,
+    // How much each press changes the width and height by, in world units.
+    pub units_step: f32,
+    // How much each press changes the overscan by, in logical pixels. Overscan sets a band of the same
+    // size on every side, replacing whatever ReservedBands held.
+    pub overscan_step: f32,
+}
+
+impl Default for TweakPanel {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            toggle_key: Some(KeyCode::F10),
+            font: None,
+            units_step: 1.0,
+            overscan_step: 4.0,
+        }
+    }
+}
+
+// Component for identifying the panel's UI node.
+#[derive(Component)]
+pub struct TweakPanelNode;
+
+// Bar colors the panel steps through.
+const BAR_COLORS: [(&str, Color); 6] = [
+    ("black", Color::BLACK),
+    ("dark gray", Color::DARK_GRAY),
+    ("gray", Color::GRAY),
+    ("white", Color::WHITE),
+    ("midnight blue", Color::MIDNIGHT_BLUE),
+    ("maroon", Color::MAROON),
+];
+
+// Fill modes the panel steps through. Other modes step to the first.
+const FILL_MODES: [FillMode; 4] = [
+    FillMode::Bars,
+    FillMode::Crop,
+    FillMode::Expand {
+        min_aspect: 4.0 / 3.0,
+        max_aspect: 21.0 / 9.0,
+    },
+    FillMode::Stretch,
+];
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Row {
+    Width,
+    Height,
+    Overscan,
+    BarColor,
+    FillMode,
+    CopyConfig,
+}
+
+const ROWS: [Row; 6] = [Row::Width, Row::Height, Row::Overscan, Row::BarColor, Row::FillMode, Row::CopyConfig];
+
+const PANEL_MARGIN: f32 = 8.0;
+
+#[derive(Default)]
+pub(crate) struct TweakPanelState {
+    panel: Option<Entity>,
+    text: Option<Entity>,
+    selected: usize,
+    warned: bool,
+}
+
+pub(crate) fn run_tweak_panel(
+    mut commands: Commands,
+    keys: Res<Input<KeyCode>>,
+    buttons: Res<Input<GamepadButton>>,
+    gamepads: Res<Gamepads>,
+    mut panel: ResMut<TweakPanel>,
+    layout: Res<LetterboxLayout>,
+    mut screen_units: ResMut<ScreenUnits>,
+    mut bands: ResMut<ReservedBands>,
+    mut style: ResMut<LetterboxStyle>,
+    mut fill_mode: ResMut<FillMode>,
+    mut panel_query: Query<&mut Style, With<TweakPanelNode>>,
+    mut text_query: Query<&mut Text>,
+    mut state: Local<TweakPanelState>,
+) {
+    let pressed = |key: KeyCode, button: GamepadButtonType| {
+        keys.just_pressed(key) || gamepads.iter().any(|gamepad| buttons.just_pressed(GamepadButton(*gamepad, button)))
+    };
+
+    if panel.toggle_key.is_some_and(|key| keys.just_pressed(key)) {
+        panel.enabled = !panel.enabled;
+    }
+    let font = match &panel.font {
+        Some(font) if panel.enabled => font.clone(),
+        _ => {
+            if panel.enabled && !state.warned {
+                warn!("The letterbox tweak panel needs TweakPanel::font to be set");
+                state.warned = true;
+            }
+            if let Some(entity) = state.panel.take() {
+                commands.entity(entity).despawn_recursive();
+                state.text = None;
+            }
+            return;
+        },
+    };
+
+    let mut changed = state.panel.is_none() || panel.is_changed();
+    if pressed(KeyCode::Up, GamepadButtonType::DPadUp) {
+        state.selected = (state.selected + ROWS.len() - 1) % ROWS.len();
+        changed = true;
+    }
+    if pressed(KeyCode::Down, GamepadButtonType::DPadDown) {
+        state.selected = (state.selected + 1) % ROWS.len();
+        changed = true;
+    }
+    let decrease = pressed(KeyCode::Left, GamepadButtonType::DPadLeft);
+    let increase = pressed(KeyCode::Right, GamepadButtonType::DPadRight);
+    let step = increase as i32 - decrease as i32;
+
+    let row = ROWS[state.selected];
+    if step != 0 {
+        changed = true;
+        match row {
+            Row::Width => screen_units.width = step_units(screen_units.width, step, panel.units_step),
+            Row::Height => screen_units.height = step_units(screen_units.height, step, panel.units_step),
+            Row::Overscan => {
+                let overscan = BandSize::Logical((overscan(&bands) + step as f32 * panel.overscan_step).max(0.0));
+                *bands = ReservedBands {
+                    left: overscan,
+                    right: overscan,
+                    top: overscan,
+                    bottom: overscan,
+                };
+            },
+            Row::BarColor => {
+                let index = BAR_COLORS.iter().position(|(_, color)| *color == style.color);
+                style.color = BAR_COLORS[cycle(index, step, BAR_COLORS.len())].1;
+            },
+            Row::FillMode => {
+                let index = FILL_MODES.iter().position(|mode| *mode == *fill_mode);
+                *fill_mode = FILL_MODES[cycle(index, step, FILL_MODES.len())];
+            },
+            Row::CopyConfig => {},
+        }
+    }
+    if row == Row::CopyConfig && pressed(KeyCode::Return, GamepadButtonType::South) {
+        info!("Current letterbox settings:\n{}", config_code(&screen_units, &bands, &style, *fill_mode));
+    }
+
+    // Anchored to the part of the safe area inside the window, in logical pixels.
+    let window = LetterboxRect::new(Vec2::ZERO, layout.window_size);
+    let safe = layout.safe_area_px.intersect(window).scaled(1.0 / layout.dpi_scale);
+    let position = Rect {
+        left: Val::Px(safe.min.x + PANEL_MARGIN),
+        top: Val::Px(layout.window_size.y / layout.dpi_scale - safe.max.y + PANEL_MARGIN),
+        ..default()
+    };
+    let settings_changed = screen_units.is_changed() || bands.is_changed() || style.is_changed() || fill_mode.is_changed();
+    if !changed && !settings_changed && !layout.is_changed() {
+        return;
+    }
+
+    let sections = ROWS
+        .iter()
+        .enumerate()
+        .map(|(index, row)| {
+            let selected = index == state.selected;
+            TextSection {
+                value: format!(
+                    "{} {}\n",
+                    if selected { ">" } else { " " },
+                    row_text(*row, &screen_units, &bands, &style, *fill_mode),
+                ),
+                style: TextStyle {
+                    font: font.clone(),
+                    font_size: 16.0,
+                    color: if selected { Color::YELLOW } else { Color::WHITE },
+                },
+            }
+        })
+        .collect::<Vec<_>>();
+
+    match state.panel.and_then(|entity| panel_query.get_mut(entity).ok()) {
+        Some(mut panel_style) => {
+            panel_style.position = position;
+            if let Some(mut text) = state.text.and_then(|entity| text_query.get_mut(entity).ok()) {
+                text.sections = sections;
+            }
+        },
+        None => {
+            let mut text = None;
+            let panel = commands
+                .spawn_bundle(NodeBundle {
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        position,
+                        padding: Rect::all(Val::Px(6.0)),
+                        ..default()
+                    },
+                    color: Color::rgba(0.0, 0.0, 0.0, 0.75).into(),
+                    ..default()
+                })
+                .insert(TweakPanelNode)
+                .with_children(|parent| {
+                    text = Some(
+                        parent
+                            .spawn_bundle(TextBundle {
+                                text: Text {
+                                    sections,
+                                    ..default()
+                                },
+                                ..default()
+                            })
+                            .id(),
+                    );
+                })
+                .id();
+            state.panel = Some(panel);
+            state.text = text;
+        },
+    }
+}
+
+// Never steps a dimension below one step, so ScreenUnits stays valid.
+fn step_units(value: f32, step: i32, size: f32) -> f32 {
+    (value + step as f32 * size).max(size)
+}
+
+// Steps an index through a list, starting from the first entry when there's no index.
+fn cycle(index: Option<usize>, step: i32, len: usize) -> usize {
+    match index {
+        Some(index) => (index as i32 + step).rem_euclid(len as i32) as usize,
+        None => 0,
+    }
+}
+
+// The overscan the bands were last set to, or none if they aren't an overscan in logical pixels.
+fn overscan(bands: &ReservedBands) -> f32 {
+    match bands.left {
+        BandSize::Logical(size) => size,
+        _ => 0.0,
+    }
+}
+
+fn row_text(
+    row: Row,
+    screen_units: &ScreenUnits,
+    bands: &ReservedBands,
+    style: &LetterboxStyle,
+    fill_mode: FillMode,
+) -> String {
+    match row {
+        Row::Width => format!("Width: {}", screen_units.width),
+        Row::Height => format!("Height: {}", screen_units.height),
+        Row::Overscan => format!("Overscan: {} px", overscan(bands)),
+        Row::BarColor => {
+            let name = BAR_COLORS.iter().find(|(_, color)| *color == style.color).map_or("custom", |(name, _)| *name);
+            format!("Bar color: {}", name)
+        },
+        Row::FillMode => format!("Fill mode: {:?}", fill_mode),
+        Row::CopyConfig => "Copy config".to_string(),
+    }
+}
+
+// The settings as the code which sets them up.
+fn config_code(
+    screen_units: &ScreenUnits,
+    bands: &ReservedBands,
+    style: &LetterboxStyle,
+    fill_mode: FillMode,
+) -> String {
+    let [red, green, blue, alpha] = style.color.as_rgba_f32();
+    format!(
+        ".insert_resource(ScreenUnits {{ width: {:?}, height: {:?} }})\n\
+         .insert_resource(FillMode::{:?})\n\
+         .insert_resource(ReservedBands {{ left: BandSize::{:?}, right: BandSize::{:?}, top: BandSize::{:?}, bottom: BandSize::{:?} }})\n\
+         .insert_resource(LetterboxStyle {{ color: Color::rgba({:?}, {:?}, {:?}, {:?}), ..default() }})",
+        screen_units.width,
+        screen_units.height,
+        fill_mode,
+        bands.left,
+        bands.right,
+        bands.top,
+        bands.bottom,
+        red,
+        green,
+        blue,
+        alpha,
+    )
+}
@@ -0,0 +1,89 @@
+use bevy::prelude::*;
+use bevy_tweening::Lens;
+
+use crate::cinematic::lerp_color;
+use crate::{CinematicBars, ScreenUnits};
+
+// Component which bevy_tweening animators drive in place of the letterbox resources, since animators
+// only run on components. Put it on any entity along with an Animator<LetterboxTweenTarget>. Each
+// value a lens has written is copied into its resource every frame before the layout is computed,
+// values no lens has touched are left alone.
+#[derive(Component, Default, Clone, Copy, PartialEq, Debug)]
+pub struct LetterboxTweenTarget {
+    pub cinematic_fraction: Option<f32>,
+    pub cinematic_color: Option<Color>,
+    pub screen_units: Option<ScreenUnits>,
+}
+
+// Tweens the fraction of the safe area the cinematic bars cover.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct CinematicFractionLens {
+    pub start: f32,
+    pub end: f32,
+}
+
+impl Lens<LetterboxTweenTarget> for CinematicFractionLens {
+    fn lerp(&mut self, target: &mut LetterboxTweenTarget, ratio: f32) {
+        target.cinematic_fraction = Some(self.start + (self.end - self.start) * ratio);
+    }
+}
+
+// Tweens the color of the cinematic bars.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct CinematicColorLens {
+    pub start: Color,
+    pub end: Color,
+}
+
+impl Lens<LetterboxTweenTarget> for CinematicColorLens {
+    fn lerp(&mut self, target: &mut LetterboxTweenTarget, ratio: f32) {
+        target.cinematic_color = Some(lerp_color(self.start, self.end, ratio));
+    }
+}
+
+// Tweens the framing. Both axes are interpolated independently, so the aspect ratio passes through
+// everything in between.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct ScreenUnitsLens {
+    pub start: ScreenUnits,
+    pub end: ScreenUnits,
+}
+
+impl Lens<LetterboxTweenTarget> for ScreenUnitsLens {
+    fn lerp(&mut self, target: &mut LetterboxTweenTarget, ratio: f32) {
+        target.screen_units = Some(ScreenUnits {
+            width: self.start.width + (self.end.width - self.start.width) * ratio,
+            height: self.start.height + (self.end.height - self.start.height) * ratio,
+        });
+    }
+}
+
+// Label for ordering the animators before apply_letterbox_tweens.
+#[derive(SystemLabel, Clone, PartialEq, Eq, Hash, Debug)]
+pub(crate) struct ApplyLetterboxTweens;
+
+// Copies tweened values into the resources. Only changed targets are copied, so a finished tween
+// leaves its final value in place and other code is free to change the resources afterwards.
+pub(crate) fn apply_letterbox_tweens(
+    target_query: Query<&LetterboxTweenTarget, Changed<LetterboxTweenTarget>>,
+    mut cinematic_bars: ResMut<CinematicBars>,
+    mut screen_units: ResMut<ScreenUnits>,
+) {
+    for target in target_query.iter() {
+        if let Some(fraction) = target.cinematic_fraction {
+            if cinematic_bars.fraction != fraction {
+                cinematic_bars.fraction = fraction;
+            }
+        }
+        if let Some(color) = target.cinematic_color {
+            if cinematic_bars.color != color {
+                cinematic_bars.color = color;
+            }
+        }
+        if let Some(units) = target.screen_units {
+            if *screen_units != units {
+                *screen_units = units;
+            }
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use bevy::prelude::*;
+
+use crate::ScreenUnits;
+
+// Resource controlling what happens when ScreenUnits is given invalid dimensions. They're reported and
+// replaced with the last valid ones, or with strict set debug builds panic so the mistake can't be missed.
+#[derive(Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ScreenUnitsValidation {
+    pub strict: bool,
+    // How many times invalid ScreenUnits have been reported, e.g. for tests.
+    pub reported: u32,
+}
+
+// Checks ScreenUnits as configured when the plugin is added. There's no earlier value to fall back on,
+// so invalid units stay in place and nothing is laid out until they're fixed.
+pub(crate) fn validate_initial_screen_units(app: &mut App) {
+    let screen_units = match app.world.get_resource::<ScreenUnits>() {
+        Some(screen_units) => *screen_units,
+        None => return,
+    };
+    if let Err(message) = screen_units.validate() {
+        let mut validation = app.world.get_resource_or_insert_with(ScreenUnitsValidation::default);
+        validation.reported += 1;
+        report_invalid(validation.strict, &screen_units, &message);
+    }
+}
+
+pub(crate) fn validate_screen_units(
+    mut validation: ResMut<ScreenUnitsValidation>,
+    screen_units: Option<ResMut<ScreenUnits>>,
+    mut last_valid: Local<Option<ScreenUnits>>,
+) {
+    let mut screen_units = match screen_units {
+        Some(screen_units) if screen_units.is_changed() => screen_units,
+        _ => return,
+    };
+
+    match screen_units.validate() {
+        Ok(()) => *last_valid = Some(*screen_units),
+        Err(message) => {
+            validation.reported += 1;
+            report_invalid(validation.strict, &screen_units, &message);
+            if let Some(valid) = *last_valid {
+                *screen_units = valid;
+            }
+        },
+    }
+}
+
+fn report_invalid(strict: bool, screen_units: &ScreenUnits, message: &str) {
+    if strict && cfg!(debug_assertions) {
+        panic!("Invalid {:?}: {}", screen_units, message);
+    }
+    error!("Ignoring invalid {:?}: {}", screen_units, message);
+}
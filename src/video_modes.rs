@@ -0,0 +1,160 @@
+use bevy::prelude::*;
+use bevy::winit::WinitWindows;
+use winit::monitor::{MonitorHandle, VideoMode};
+use winit::window::Fullscreen;
+
+use crate::{LayoutInputs, ManagedCamera, ScreenUnits};
+
+// A video mode of the monitor the managed window is on.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct VideoModeCandidate {
+    // In physical pixels.
+    pub size: UVec2,
+    pub refresh_rate: u16,
+    pub bit_depth: u16,
+    // Whether it's the monitor's own resolution.
+    pub native: bool,
+    // Fraction of the screen the letterboxes would cover, from 0 to 1.
+    pub bar_fraction: f32,
+    // Higher is better. Less bar area always wins, then the native resolution, then larger
+    // resolutions, and refresh rate breaks ties.
+    pub score: f32,
+}
+
+impl VideoModeCandidate {
+    fn new(size: UVec2, refresh_rate: u16, bit_depth: u16, native_size: UVec2, screen_units: &ScreenUnits) -> Self {
+        let native = size == native_size;
+        let bar_fraction = bar_fraction(size.as_vec2(), screen_units);
+        let native_pixels = (native_size.x as f32 * native_size.y as f32).max(1.0);
+        let pixels = (size.x as f32 * size.y as f32 / native_pixels).min(1.0);
+        // Bar area is measured in whole tenths of a percent, so rounding noise doesn't outrank the rest.
+        let score = (1.0 - (bar_fraction * 1000.0).round() / 1000.0) * 100.0
+            + if native { 1.0 } else { 0.0 }
+            + pixels * 0.5
+            + refresh_rate.min(1000) as f32 / 10000.0;
+        Self {
+            size,
+            refresh_rate,
+            bit_depth,
+            native,
+            bar_fraction,
+            score,
+        }
+    }
+
+    fn matches(&self, mode: &VideoMode) -> bool {
+        let size = mode.size();
+        UVec2::new(size.width, size.height) == self.size
+            && mode.refresh_rate() == self.refresh_rate
+            && mode.bit_depth() == self.bit_depth
+    }
+}
+
+// Fraction of a screen of this size the letterboxes cover when ScreenUnits is fitted into it.
+fn bar_fraction(size: Vec2, screen_units: &ScreenUnits) -> f32 {
+    if size.min_element() <= 0.0 || screen_units.validate().is_err() {
+        return 1.0;
+    }
+    let screen_aspect = size.x / size.y;
+    let units_aspect = screen_units.width / screen_units.height;
+    1.0 - (screen_aspect / units_aspect).min(units_aspect / screen_aspect)
+}
+
+// Resource for choosing an exclusive fullscreen video mode which suits ScreenUnits, e.g. for a settings
+// menu. The candidates are the video modes of the monitor the managed window is on, best first, and
+// are listed again whenever the window changes monitor, a monitor is plugged in or out, or ScreenUnits
+// changes. Selecting a mode switches the window to it directly through winit, so Window::mode isn't
+// updated, and the layout follows from the resize like any other.
+#[derive(Default)]
+pub struct FullscreenVideoModes {
+    candidates: Vec<VideoModeCandidate>,
+    request: Option<VideoModeRequest>,
+    // The monitor and units the candidates were listed for.
+    listed_for: Option<(Option<String>, UVec2, usize, ScreenUnits)>,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum VideoModeRequest {
+    Fullscreen(VideoModeCandidate),
+    Windowed,
+}
+
+impl FullscreenVideoModes {
+    pub fn candidates(&self) -> &[VideoModeCandidate] {
+        &self.candidates
+    }
+
+    pub fn best(&self) -> Option<VideoModeCandidate> {
+        self.candidates.first().copied()
+    }
+
+    // Switches to a candidate's video mode in exclusive fullscreen.
+    pub fn select(&mut self, candidate: VideoModeCandidate) {
+        self.request = Some(VideoModeRequest::Fullscreen(candidate));
+    }
+
+    // Switches to the best candidate. Does nothing until the candidates are listed.
+    pub fn select_best(&mut self) {
+        if let Some(best) = self.best() {
+            self.select(best);
+        }
+    }
+
+    // Leaves exclusive fullscreen.
+    pub fn select_windowed(&mut self) {
+        self.request = Some(VideoModeRequest::Windowed);
+    }
+}
+
+pub(crate) fn update_fullscreen_video_modes(
+    mut video_modes: ResMut<FullscreenVideoModes>,
+    inputs: LayoutInputs,
+    managed_camera: Res<ManagedCamera>,
+    // Missing in headless apps.
+    winit_windows: Option<NonSend<WinitWindows>>,
+) {
+    let window = match winit_windows.as_ref().and_then(|windows| windows.get_window(managed_camera.window)) {
+        Some(window) => window,
+        None => return,
+    };
+    let monitor = match window.current_monitor() {
+        Some(monitor) => monitor,
+        None => return,
+    };
+
+    // The number of monitors changes when one is plugged in or out, which can change which modes the
+    // current one offers too.
+    let screen_units = inputs.screen_units();
+    let native_size = UVec2::new(monitor.size().width, monitor.size().height);
+    let listed_for = Some((monitor.name(), native_size, window.available_monitors().count(), screen_units));
+    if video_modes.listed_for != listed_for {
+        video_modes.candidates = list_candidates(&monitor, native_size, &screen_units);
+        video_modes.listed_for = listed_for;
+    }
+
+    // Only taken when set, so the resource isn't marked changed every frame.
+    let request = if video_modes.request.is_some() { video_modes.request.take() } else { None };
+    match request {
+        Some(VideoModeRequest::Fullscreen(candidate)) => {
+            // winit video modes aren't Send on every platform, so the chosen one is looked up again.
+            match monitor.video_modes().find(|mode| candidate.matches(mode)) {
+                Some(mode) => window.set_fullscreen(Some(Fullscreen::Exclusive(mode))),
+                None => warn!("Video mode {:?} is no longer available on this monitor", candidate.size),
+            }
+        },
+        Some(VideoModeRequest::Windowed) => window.set_fullscreen(None),
+        None => {},
+    }
+}
+
+fn list_candidates(monitor: &MonitorHandle, native_size: UVec2, screen_units: &ScreenUnits) -> Vec<VideoModeCandidate> {
+    let mut candidates: Vec<_> = monitor
+        .video_modes()
+        .map(|mode| {
+            let size = UVec2::new(mode.size().width, mode.size().height);
+            VideoModeCandidate::new(size, mode.refresh_rate(), mode.bit_depth(), native_size, screen_units)
+        })
+        .collect();
+    candidates.sort_by(|a, b| b.score.total_cmp(&a.score).then(b.bit_depth.cmp(&a.bit_depth)));
+    candidates
+}
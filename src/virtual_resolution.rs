@@ -0,0 +1,96 @@
+use bevy::prelude::*;
+
+use crate::ScreenUnits;
+
+// Resource for configuring the framing in reference pixels, e.g. designing for 640x360 with 32 pixels
+// per world unit, instead of in ScreenUnits. While it exists the two are kept in sync, whichever one was
+// changed last wins, and FramingAuthority says which one that was.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct VirtualResolution {
+    pub width: u32,
+    pub height: u32,
+    pub pixels_per_unit: f32,
+}
+
+impl VirtualResolution {
+    pub fn screen_units(&self) -> ScreenUnits {
+        ScreenUnits {
+            width: self.width as f32 / self.pixels_per_unit,
+            height: self.height as f32 / self.pixels_per_unit,
+        }
+    }
+
+    // The resolution showing screen units at a given pixels per unit, rounded to whole pixels.
+    pub fn from_screen_units(screen_units: &ScreenUnits, pixels_per_unit: f32) -> Self {
+        Self {
+            width: (screen_units.width * pixels_per_unit).round().max(0.0) as u32,
+            height: (screen_units.height * pixels_per_unit).round().max(0.0) as u32,
+            pixels_per_unit,
+        }
+    }
+}
+
+// Resource saying which representation of the framing was set last and the other is derived from.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub enum FramingAuthority {
+    #[default]
+    ScreenUnits,
+    VirtualResolution,
+}
+
+// Makes ScreenUnits available when only a VirtualResolution was configured.
+pub(crate) fn insert_initial_screen_units(app: &mut App) {
+    let resolution = match app.world.get_resource::<VirtualResolution>() {
+        Some(resolution) => *resolution,
+        None => return,
+    };
+    if app.world.get_resource::<ScreenUnits>().is_none() {
+        app.insert_resource(resolution.screen_units());
+        app.insert_resource(FramingAuthority::VirtualResolution);
+    }
+}
+
+pub(crate) fn sync_virtual_resolution(
+    mut commands: Commands,
+    resolution: Option<ResMut<VirtualResolution>>,
+    screen_units: Option<ResMut<ScreenUnits>>,
+    mut authority: ResMut<FramingAuthority>,
+    mut synced: Local<Option<(VirtualResolution, ScreenUnits)>>,
+) {
+    let mut resolution = match resolution {
+        Some(resolution) => resolution,
+        None => {
+            *synced = None;
+            return;
+        },
+    };
+    let mut screen_units = match screen_units {
+        Some(screen_units) => screen_units,
+        None => {
+            commands.insert_resource(resolution.screen_units());
+            *authority = FramingAuthority::VirtualResolution;
+            return;
+        },
+    };
+
+    // Compare against the values last synced rather than using change detection, since this system's
+    // own writes would otherwise look like changes on the next frame. Rounding to whole pixels means
+    // the derived resolution isn't always exact, so it's never written back over ScreenUnits. Adding a
+    // VirtualResolution makes it the authority.
+    let (last_resolution, last_units) = match *synced {
+        Some(synced) => synced,
+        None => (VirtualResolution { width: 0, height: 0, pixels_per_unit: 0.0 }, *screen_units),
+    };
+    if *resolution != last_resolution {
+        *screen_units = resolution.screen_units();
+        if *authority != FramingAuthority::VirtualResolution {
+            *authority = FramingAuthority::VirtualResolution;
+        }
+    } else if *screen_units != last_units {
+        *resolution = VirtualResolution::from_screen_units(&screen_units, resolution.pixels_per_unit);
+        if *authority != FramingAuthority::ScreenUnits {
+            *authority = FramingAuthority::ScreenUnits;
+        }
+    }
+    *synced = Some((*resolution, *screen_units));
+}
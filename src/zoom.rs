@@ -0,0 +1,128 @@
+use bevy::input::mouse::{MouseScrollUnit, MouseWheel};
+use bevy::prelude::*;
+
+use crate::{LetterboxLayout, ManagedCamera, ScreenUnits};
+
+// Resource for zooming with the mouse wheel by scaling ScreenUnits, so the layout, letterboxes and
+// projection all follow through the normal recompute. The zoom is relative to the ScreenUnits it
+// started from: 2 shows half as many units along each axis. Setting ScreenUnits directly starts over
+// from the new units at a zoom of 1.
+pub struct ScrollZoom {
+    pub enabled: bool,
+    pub min_zoom: f32,
+    pub max_zoom: f32,
+    // Zoom multiplier for each line scrolled.
+    pub step: f32,
+    // Moves the managed camera so the world point under the cursor stays under it.
+    pub toward_cursor: bool,
+    zoom: f32,
+    // The zoom ScreenUnits was last scaled to, and the units it was scaled from and to.
+    applied: f32,
+    base: Option<ScreenUnits>,
+    written: Option<ScreenUnits>,
+}
+
+impl Default for ScrollZoom {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_zoom: 0.5,
+            max_zoom: 4.0,
+            step: 1.1,
+            toward_cursor: true,
+            zoom: 1.0,
+            applied: 1.0,
+            base: None,
+            written: None,
+        }
+    }
+}
+
+impl ScrollZoom {
+    pub fn zoom(&self) -> f32 {
+        self.zoom
+    }
+
+    // Zooms to a factor within the bounds, around the center of the camera.
+    pub fn set_zoom(&mut self, zoom: f32) {
+        self.zoom = zoom.clamp(self.min_zoom, self.max_zoom);
+    }
+
+    // Lets touch gestures go past the bounds before easing back within them.
+    pub(crate) fn set_zoom_unclamped(&mut self, zoom: f32) {
+        self.zoom = zoom;
+    }
+
+    // Goes back to the ScreenUnits the zoom started from.
+    pub fn reset(&mut self) {
+        self.zoom = 1.0;
+    }
+}
+
+// Label for ordering other zoom controls, like touch gestures, before the zoom is applied.
+#[derive(SystemLabel, Clone, PartialEq, Eq, Hash, Debug)]
+pub(crate) struct ApplyScrollZoom;
+
+// Scroll deltas reported in pixels, e.g. by touchpads, count as a line per this many pixels.
+const PIXELS_PER_LINE: f32 = 100.0;
+
+pub(crate) fn apply_scroll_zoom(
+    mut zoom: ResMut<ScrollZoom>,
+    mut screen_units: ResMut<ScreenUnits>,
+    layout: Res<LetterboxLayout>,
+    managed_camera: Res<ManagedCamera>,
+    windows: Res<Windows>,
+    mut wheel_events: EventReader<MouseWheel>,
+    mut camera_query: Query<&mut Transform>,
+) {
+    if zoom.written != Some(*screen_units) {
+        zoom.base = Some(*screen_units);
+        zoom.written = Some(*screen_units);
+        zoom.zoom = 1.0;
+        zoom.applied = 1.0;
+    }
+
+    let lines: f32 = wheel_events
+        .iter()
+        .map(|event| match event.unit {
+            MouseScrollUnit::Line => event.y,
+            MouseScrollUnit::Pixel => event.y / PIXELS_PER_LINE,
+        })
+        .sum();
+    let scrolled = zoom.enabled && lines != 0.0;
+    if scrolled {
+        let target = zoom.zoom * zoom.step.powf(lines);
+        zoom.set_zoom(target);
+    }
+    if zoom.zoom == zoom.applied {
+        return;
+    }
+    let base = match zoom.base {
+        Some(base) => base,
+        None => return,
+    };
+
+    // Scaling ScreenUnits scales the pixels per unit by the inverse, so a point under the cursor moves
+    // towards the camera by the ratio of the zooms. Moving the camera by the difference keeps it in place.
+    let ratio = zoom.zoom / zoom.applied;
+    let cursor = windows
+        .get(managed_camera.window)
+        .and_then(|window| window.cursor_position())
+        .filter(|cursor| layout.to_window_px(layout.framed).contains(*cursor * layout.dpi_scale));
+    if let Some(cursor) = cursor.filter(|_| scrolled && zoom.toward_cursor) {
+        let offset = layout.window_point_to_units(cursor) * (1.0 - 1.0 / ratio);
+        let camera = managed_camera.entity.and_then(|entity| camera_query.get_mut(entity).ok());
+        if let Some(mut transform) = camera {
+            transform.translation.x += offset.x;
+            transform.translation.y += offset.y;
+        }
+    }
+
+    let units = ScreenUnits {
+        width: base.width / zoom.zoom,
+        height: base.height / zoom.zoom,
+    };
+    *screen_units = units;
+    zoom.written = Some(units);
+    zoom.applied = zoom.zoom;
+}
@@ -0,0 +1,153 @@
+use bevy::prelude::*;
+
+use crate::{DefaultLetterboxEasing, ReduceMotion, ScreenUnits};
+
+// Resource for a fixed set of framings to switch between, e.g. close, medium and far views. The
+// selected level is written to ScreenUnits, so the layout, letterboxes and projection follow through
+// the normal recompute. Nothing is written while there are no levels. Both fields can be changed
+// directly, and current is kept in range: when the levels change, it follows the framing shown if
+// that's still a level, and otherwise stays at the same index as far as it can.
+#[derive(Clone, PartialEq, Debug)]
+pub struct ZoomLevels {
+    pub levels: Vec<ScreenUnits>,
+    pub current: usize,
+    // Whether stepping past either end goes around to the other, instead of stopping there.
+    pub wrap: bool,
+    // Seconds to ease from one level to the next, using DefaultLetterboxEasing. With 0, or with
+    // ReduceMotion, the level is applied right away.
+    pub transition: f32,
+}
+
+impl Default for ZoomLevels {
+    fn default() -> Self {
+        Self {
+            levels: Vec::new(),
+            current: 0,
+            wrap: false,
+            transition: 0.0,
+        }
+    }
+}
+
+impl ZoomLevels {
+    pub fn new(levels: Vec<ScreenUnits>) -> Self {
+        Self {
+            levels,
+            ..default()
+        }
+    }
+
+    // The units of the selected level, which ScreenUnits shows once any transition finishes.
+    pub fn current_units(&self) -> Option<ScreenUnits> {
+        self.levels.get(self.current).copied()
+    }
+
+    // Moves by a number of levels, forwards when positive, clamping or wrapping at the ends.
+    pub fn step(&mut self, steps: i32) {
+        let count = self.levels.len() as i64;
+        if count == 0 {
+            return;
+        }
+        let target = self.current as i64 + steps as i64;
+        let level = if self.wrap { target.rem_euclid(count) } else { target.clamp(0, count - 1) };
+        self.current = level as usize;
+    }
+
+    pub fn next(&mut self) {
+        self.step(1);
+    }
+
+    pub fn previous(&mut self) {
+        self.step(-1);
+    }
+
+    // Selects a level, clamped to the last one.
+    pub fn select(&mut self, level: usize) {
+        self.current = level.min(self.levels.len().saturating_sub(1));
+    }
+}
+
+// Event for changing the zoom level, e.g. from a key binding, instead of changing ZoomLevels directly.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ChangeZoomLevel {
+    Step(i32),
+    Select(usize),
+}
+
+#[derive(Default)]
+pub(crate) struct ZoomLevelsState {
+    // The levels when they were last applied, to notice the list changing.
+    levels: Vec<ScreenUnits>,
+    applied: Option<ScreenUnits>,
+    animation: Option<LevelAnimation>,
+}
+
+struct LevelAnimation {
+    from: ScreenUnits,
+    to: ScreenUnits,
+    elapsed: f32,
+}
+
+pub(crate) fn apply_zoom_levels(
+    time: Res<Time>,
+    mut zoom_levels: ResMut<ZoomLevels>,
+    reduce_motion: Res<ReduceMotion>,
+    easing: Res<DefaultLetterboxEasing>,
+    mut screen_units: ResMut<ScreenUnits>,
+    mut change_events: EventReader<ChangeZoomLevel>,
+    mut state: Local<ZoomLevelsState>,
+) {
+    for event in change_events.iter() {
+        match *event {
+            ChangeZoomLevel::Step(steps) => zoom_levels.step(steps),
+            ChangeZoomLevel::Select(level) => zoom_levels.select(level),
+        }
+    }
+
+    if zoom_levels.is_changed() {
+        if zoom_levels.levels != state.levels {
+            let shown = state.applied.and_then(|applied| zoom_levels.levels.iter().position(|units| *units == applied));
+            if let Some(level) = shown {
+                if zoom_levels.current != level {
+                    zoom_levels.current = level;
+                }
+            }
+            state.levels = zoom_levels.levels.clone();
+        }
+        let last = zoom_levels.levels.len().saturating_sub(1);
+        if zoom_levels.current > last {
+            zoom_levels.current = last;
+        }
+
+        if let Some(target) = zoom_levels.current_units() {
+            if state.applied != Some(target) {
+                // The first level is shown right away rather than eased in from whatever came before.
+                let animate = state.applied.is_some() && zoom_levels.transition > 0.0 && !reduce_motion.0;
+                state.applied = Some(target);
+                state.animation = Some(LevelAnimation {
+                    from: *screen_units,
+                    to: target,
+                    elapsed: if animate { 0.0 } else { zoom_levels.transition },
+                });
+            }
+        }
+    }
+
+    let animation = match &mut state.animation {
+        Some(animation) => animation,
+        None => return,
+    };
+    animation.elapsed += time.delta_seconds();
+    let t = if zoom_levels.transition <= 0.0 || reduce_motion.0 { 1.0 } else { animation.elapsed / zoom_levels.transition };
+    let progress = easing.0.ease(t);
+    let units = ScreenUnits {
+        width: animation.from.width + (animation.to.width - animation.from.width) * progress,
+        height: animation.from.height + (animation.to.height - animation.from.height) * progress,
+    };
+    if t >= 1.0 {
+        state.animation = None;
+    }
+    if *screen_units != units {
+        *screen_units = units;
+    }
+}
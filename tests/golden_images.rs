@@ -0,0 +1,301 @@
+// Golden-image tests rendering a deterministic scene through the real renderer and comparing the frames
+// with the references in tests/golden. They need a GPU, so they only build with the golden-images
+// feature and are ignored unless asked for:
+//
+//     cargo test --features golden-images --test golden_images -- --ignored
+//
+// The references are created, and regenerated after an intentional visual change, by running them
+// with BLESS=1 on a machine with a GPU; review the new images before committing them:
+//
+//     BLESS=1 cargo test --features golden-images --test golden_images -- --ignored
+//
+// Frames which don't match are written next to the build's other temporary files, and the failure
+// names the path.
+use std::num::NonZeroU32;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use bevy::core_pipeline::node::MAIN_PASS_DRIVER;
+use bevy::log::LogPlugin;
+use bevy::prelude::*;
+use bevy::render::camera::RenderTarget;
+use bevy::render::render_asset::RenderAssets;
+use bevy::render::render_graph::{Node, NodeRunError, RenderGraph, RenderGraphContext};
+use bevy::render::render_resource::*;
+use bevy::render::renderer::{RenderContext, RenderDevice};
+use bevy::render::texture::BevyDefault;
+use bevy::render::view::Msaa;
+use bevy::render::{RenderApp, RenderStage};
+use bevy::ui::node::UI_PASS_DRIVER;
+use bevy::winit::WinitPlugin;
+use bevy_letterboxes::*;
+
+const READBACK_NODE: &str = "golden_readback";
+
+// Target sizes covering an exact fit, pillarboxes, letterboxes and a size which doesn't divide evenly.
+const SIZES: [(u32, u32); 5] = [(320, 180), (400, 180), (320, 240), (300, 300), (333, 177)];
+
+// How far a channel may stray before the pixel counts as different, and how many pixels out of every
+// thousand may differ. Drivers rasterize edges and blend slightly differently.
+const CHANNEL_TOLERANCE: u8 = 4;
+const MISMATCHES_PER_THOUSAND: usize = 1;
+
+// Frames to render after a resize before reading one back, so pipelines have compiled and the layout
+// has been applied.
+const SETTLE_FRAMES: usize = 6;
+
+#[test]
+#[ignore = "needs a GPU, run with --ignored"]
+fn sprite_backend_matches_references() {
+    check_backend("sprites", LetterboxBackend::Sprites);
+}
+
+#[cfg(feature = "scissor")]
+#[test]
+#[ignore = "needs a GPU, run with --ignored"]
+fn scissor_backend_matches_references() {
+    check_backend("scissor", LetterboxBackend::Scissor);
+}
+
+#[cfg(feature = "post-process")]
+#[test]
+#[ignore = "needs a GPU, run with --ignored"]
+fn post_process_backend_matches_references() {
+    check_backend("post_process", LetterboxBackend::PostProcess);
+}
+
+fn check_backend(name: &str, backend: LetterboxBackend) {
+    let (first_width, first_height) = SIZES[0];
+    let (mut app, target) = golden_app(backend, first_width, first_height);
+    let mut failures = Vec::new();
+    for (width, height) in SIZES {
+        app.world
+            .resource_mut::<Assets<Image>>()
+            .get_mut(&target)
+            .unwrap()
+            .resize(Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            });
+        for _ in 0..SETTLE_FRAMES {
+            app.update();
+        }
+        let frame = app.world.resource::<FrameReadback>().0.lock().unwrap().pixels.take();
+        let frame = frame.expect("nothing was read back from the target");
+        assert_eq!((frame.width(), frame.height()), (width, height));
+        if let Err(failure) = compare_with_reference(&format!("{}_{}x{}", name, width, height), &frame) {
+            failures.push(failure);
+        }
+    }
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
+// A scene of colored quads running past the safe area on every side, under the letterboxes, rendered
+// into an image instead of a window so no windowing system is needed.
+fn golden_app(backend: LetterboxBackend, width: u32, height: u32) -> (App, Handle<Image>) {
+    let mut app = App::new();
+    // Logging installs a global subscriber, which the other tests' apps would try to install again.
+    app.add_plugins_with(DefaultPlugins, |group| group.disable::<WinitPlugin>().disable::<LogPlugin>())
+        // Without multisampling the frames don't depend on how a driver resolves edges.
+        .insert_resource(Msaa { samples: 1 })
+        .insert_resource(ClearColor(Color::rgb(0.5, 0.5, 0.5)))
+        .insert_resource(ScreenUnits {
+            width: 16.0,
+            height: 9.0,
+        })
+        .insert_resource(backend)
+        .add_plugin(LetterboxPlugin::default());
+
+    let mut image = Image::new_fill(
+        Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &[0, 0, 0, 255],
+        TextureFormat::bevy_default(),
+    );
+    image.texture_descriptor.usage =
+        TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST | TextureUsages::COPY_SRC | TextureUsages::RENDER_ATTACHMENT;
+    let target = app.world.resource_mut::<Assets<Image>>().add(image);
+
+    let mut camera = OrthographicCameraBundle::new_2d();
+    camera.camera.target = RenderTarget::Image(target.clone());
+    app.world.spawn().insert_bundle(camera).insert(LetterboxCamera);
+    spawn_scene(&mut app.world);
+
+    let readback = FrameReadback::default();
+    app.insert_resource(readback.clone());
+    let render_app = app.sub_app_mut(RenderApp);
+    render_app
+        .insert_resource(readback)
+        .insert_resource(ReadbackTarget(target.clone()))
+        .add_system_to_stage(RenderStage::Cleanup, map_readback);
+    let mut graph = render_app.world.resource_mut::<RenderGraph>();
+    graph.add_node(READBACK_NODE, ReadbackNode);
+    graph.add_node_edge(MAIN_PASS_DRIVER, READBACK_NODE).unwrap();
+    #[cfg(feature = "post-process")]
+    graph.add_node_edge(LETTERBOX_MASK_PASS, READBACK_NODE).unwrap();
+    graph.add_node_edge(UI_PASS_DRIVER, READBACK_NODE).unwrap();
+    (app, target)
+}
+
+fn spawn_scene(world: &mut World) {
+    let quads = [
+        // A backdrop filling every target, so the bars show up against it.
+        (Color::rgb(0.1, 0.2, 0.6), Vec2::new(-40.0, -40.0), Vec2::new(40.0, 40.0), 0.0),
+        // One quad across each edge of the safe area.
+        (Color::rgb(0.9, 0.1, 0.1), Vec2::new(-10.0, -1.0), Vec2::new(-6.0, 1.0), 1.0),
+        (Color::rgb(0.1, 0.8, 0.2), Vec2::new(6.0, -3.0), Vec2::new(10.0, -1.0), 1.0),
+        (Color::rgb(0.9, 0.8, 0.1), Vec2::new(-2.0, 3.5), Vec2::new(2.0, 6.0), 1.0),
+        (Color::rgb(0.1, 0.8, 0.9), Vec2::new(-5.0, -6.0), Vec2::new(-1.0, -3.5), 1.0),
+        // A particle layer drawn just under the letterboxes, which must still stay inside the frame.
+        (Color::rgb(1.0, 1.0, 1.0), Vec2::new(7.0, 2.0), Vec2::new(9.0, 5.5), LETTERBOX_Z - 1.0),
+    ];
+    for (color, min, max, z) in quads {
+        let center = (min + max) / 2.0;
+        world.spawn().insert_bundle(SpriteBundle {
+            sprite: Sprite {
+                color,
+                custom_size: Some(max - min),
+                ..default()
+            },
+            transform: Transform::from_xyz(center.x, center.y, z),
+            ..default()
+        });
+    }
+}
+
+// The last frame read back from the target, shared between the main and render worlds.
+#[derive(Clone, Default)]
+struct FrameReadback(Arc<Mutex<Readback>>);
+
+#[derive(Default)]
+struct Readback {
+    // A copy of the target submitted this frame, with its size and padded row length.
+    pending: Option<(Buffer, Extent3d, u32)>,
+    pixels: Option<image::RgbaImage>,
+}
+
+struct ReadbackTarget(Handle<Image>);
+
+// Copies the target into a buffer once everything else has drawn into it.
+struct ReadbackNode;
+
+impl Node for ReadbackNode {
+    fn run(
+        &self,
+        _graph: &mut RenderGraphContext,
+        render_context: &mut RenderContext,
+        world: &World,
+    ) -> Result<(), NodeRunError> {
+        let target = world.resource::<ReadbackTarget>();
+        let gpu_image = match world.resource::<RenderAssets<Image>>().get(&target.0) {
+            Some(gpu_image) => gpu_image,
+            None => return Ok(()),
+        };
+        let size = Extent3d {
+            width: gpu_image.size.width as u32,
+            height: gpu_image.size.height as u32,
+            depth_or_array_layers: 1,
+        };
+        let padded_row = RenderDevice::align_copy_bytes_per_row(size.width as usize * 4) as u32;
+        let buffer = render_context.render_device.create_buffer(&BufferDescriptor {
+            label: Some("golden_readback_buffer"),
+            size: (padded_row * size.height) as u64,
+            usage: BufferUsages::COPY_DST | BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+        render_context.command_encoder.copy_texture_to_buffer(
+            ImageCopyTexture {
+                texture: &gpu_image.texture,
+                mip_level: 0,
+                origin: Origin3d::ZERO,
+                aspect: TextureAspect::All,
+            },
+            ImageCopyBuffer {
+                buffer: &buffer,
+                layout: ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: NonZeroU32::new(padded_row),
+                    rows_per_image: None,
+                },
+            },
+            size,
+        );
+        world.resource::<FrameReadback>().0.lock().unwrap().pending = Some((buffer, size, padded_row));
+        Ok(())
+    }
+}
+
+// Runs after the frame was submitted, so the copy can be waited on.
+fn map_readback(render_device: Res<RenderDevice>, readback: Res<FrameReadback>) {
+    let mut readback = readback.0.lock().unwrap();
+    let (buffer, size, padded_row) = match readback.pending.take() {
+        Some(pending) => pending,
+        None => return,
+    };
+    let slice = buffer.slice(..);
+    render_device.map_buffer(&slice, MapMode::Read);
+    let mut pixels = Vec::with_capacity((size.width * size.height * 4) as usize);
+    for row in slice.get_mapped_range().chunks(padded_row as usize) {
+        pixels.extend_from_slice(&row[..size.width as usize * 4]);
+    }
+    buffer.unmap();
+    // The target has the default format, which is BGRA outside of Android and the web.
+    if TextureFormat::bevy_default() == TextureFormat::Bgra8UnormSrgb {
+        for pixel in pixels.chunks_mut(4) {
+            pixel.swap(0, 2);
+        }
+    }
+    readback.pixels = image::RgbaImage::from_raw(size.width, size.height, pixels);
+}
+
+fn compare_with_reference(name: &str, frame: &image::RgbaImage) -> Result<(), String> {
+    let reference_path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/golden").join(format!("{}.png", name));
+    if std::env::var_os("BLESS").is_some() {
+        std::fs::create_dir_all(reference_path.parent().unwrap()).unwrap();
+        frame.save(&reference_path).unwrap();
+        return Ok(());
+    }
+
+    let reference = match image::open(&reference_path) {
+        Ok(reference) => reference.into_rgba8(),
+        Err(error) => {
+            return Err(format!(
+                "{}: no reference at {} ({}), run with BLESS=1 to create it",
+                name,
+                reference_path.display(),
+                error
+            ))
+        },
+    };
+    let mismatches = if reference.dimensions() == frame.dimensions() {
+        reference
+            .pixels()
+            .zip(frame.pixels())
+            .filter(|(expected, actual)| {
+                expected.0.iter().zip(actual.0.iter()).any(|(a, b)| a.abs_diff(*b) > CHANNEL_TOLERANCE)
+            })
+            .count()
+    } else {
+        usize::MAX
+    };
+    let allowed = (frame.width() * frame.height()) as usize * MISMATCHES_PER_THOUSAND / 1000;
+    if mismatches <= allowed {
+        return Ok(());
+    }
+
+    let actual_path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(format!("{}.png", name));
+    frame.save(&actual_path).unwrap();
+    Err(format!(
+        "{}: {} pixels differ from {}, more than the {} allowed; the frame was written to {}",
+        name,
+        if mismatches == usize::MAX { "all".to_string() } else { mismatches.to_string() },
+        reference_path.display(),
+        allowed,
+        actual_path.display()
+    ))
+}